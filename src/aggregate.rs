@@ -0,0 +1,199 @@
+//! Reduction of several measurements into one, for periodic reporting.
+//!
+//! It is common to sample more often than you report: a datalogger might
+//! measure every 2 seconds but only record the mean for each minute. An
+//! [`Aggregator`] accumulates measurements with [`Aggregator::add()`] and
+//! produces the reduced measurement with [`Aggregator::finish()`], which also
+//! resets it for the next reporting interval.
+//!
+//! Use [`PeriodicSampler::next_aggregate()`] to have the sampler take the
+//! measurements for each reporting interval and feed them to an aggregator.
+//!
+//! All of the built-in aggregators operate on the raw sensor readings, and
+//! treat temperature and humidity separately, so (for instance) the result
+//! from [`Min`] pairs the lowest temperature with the lowest humidity, even
+//! if they came from different measurements. The result follows the
+//! driver's [`Config::humidity_clamp`] setting, as the measurements added
+//! do, and is flagged as implausible (see [`Measurement::is_plausible()`])
+//! if any of them was.
+//!
+//! [`PeriodicSampler::next_aggregate()`]: crate::sampler::PeriodicSampler::next_aggregate
+//! [`Config::humidity_clamp`]: crate::common::Config::humidity_clamp
+//! [`Measurement::is_plausible()`]: crate::common::Measurement::is_plausible
+use crate::common::Measurement;
+
+/// Accumulates measurements and reduces them to a single measurement.
+pub trait Aggregator {
+    /// Add a measurement to the current reporting interval.
+    fn add(&mut self, measurement: Measurement);
+
+    /// Return the reduced measurement for the current reporting interval,
+    /// and reset the aggregator for the next one.
+    ///
+    /// Returns `None` if no measurements were added.
+    fn finish(&mut self) -> Option<Measurement>;
+}
+
+impl<A> Aggregator for &mut A
+where
+    A: Aggregator + ?Sized,
+{
+    fn add(&mut self, measurement: Measurement) {
+        (**self).add(measurement)
+    }
+
+    fn finish(&mut self) -> Option<Measurement> {
+        (**self).finish()
+    }
+}
+
+/// Mean of the measurements, rounded to the nearest raw reading.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mean {
+    temp_sum: u64,
+    humidity_sum: u64,
+    count: u32,
+    /// The most recent measurement, flagged implausible if any added was.
+    last: Option<Measurement>,
+}
+
+impl Aggregator for Mean {
+    fn add(&mut self, measurement: Measurement) {
+        self.temp_sum += u64::from(measurement.raw_temperature_reading());
+        self.humidity_sum += u64::from(measurement.raw_humidity_reading());
+        self.count = self.count.saturating_add(1);
+        self.last = Some(match self.last {
+            Some(last) => measurement.merge_flags(&last),
+            None => measurement,
+        });
+    }
+
+    fn finish(&mut self) -> Option<Measurement> {
+        let Mean {
+            temp_sum,
+            humidity_sum,
+            count,
+            last,
+        } = core::mem::take(self);
+        let last = last?;
+        let count = u64::from(count);
+        let mean = |sum: u64| ((sum + count / 2) / count) as u16;
+        Some(Measurement::from_raw_like(
+            &last,
+            mean(temp_sum),
+            mean(humidity_sum),
+        ))
+    }
+}
+
+/// Lowest temperature and lowest humidity.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min {
+    min: Option<Measurement>,
+}
+
+impl Aggregator for Min {
+    fn add(&mut self, measurement: Measurement) {
+        let (temp, humidity) = (
+            measurement.raw_temperature_reading(),
+            measurement.raw_humidity_reading(),
+        );
+        self.min = Some(match self.min {
+            Some(min) => Measurement::from_raw_like(
+                &measurement.merge_flags(&min),
+                min.raw_temperature_reading().min(temp),
+                min.raw_humidity_reading().min(humidity),
+            ),
+            None => measurement,
+        });
+    }
+
+    fn finish(&mut self) -> Option<Measurement> {
+        self.min.take()
+    }
+}
+
+/// Highest temperature and highest humidity.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max {
+    max: Option<Measurement>,
+}
+
+impl Aggregator for Max {
+    fn add(&mut self, measurement: Measurement) {
+        let (temp, humidity) = (
+            measurement.raw_temperature_reading(),
+            measurement.raw_humidity_reading(),
+        );
+        self.max = Some(match self.max {
+            Some(max) => Measurement::from_raw_like(
+                &measurement.merge_flags(&max),
+                max.raw_temperature_reading().max(temp),
+                max.raw_humidity_reading().max(humidity),
+            ),
+            None => measurement,
+        });
+    }
+
+    fn finish(&mut self) -> Option<Measurement> {
+        self.max.take()
+    }
+}
+
+/// The most recent measurement.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Last {
+    last: Option<Measurement>,
+}
+
+impl Aggregator for Last {
+    fn add(&mut self, measurement: Measurement) {
+        self.last = Some(measurement);
+    }
+
+    fn finish(&mut self) -> Option<Measurement> {
+        self.last.take()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Aggregator, Last, Max, Mean, Min};
+    use crate::common::Measurement;
+
+    fn run(aggregator: &mut impl Aggregator) -> Option<(u16, u16)> {
+        for (temp, humidity) in [(100, 900), (300, 700), (201, 800)] {
+            aggregator.add(Measurement::from_raw(temp, humidity));
+        }
+        aggregator
+            .finish()
+            .map(|m| (m.raw_temperature_reading(), m.raw_humidity_reading()))
+    }
+
+    #[test]
+    fn reducers() {
+        assert_eq!(run(&mut Mean::default()), Some((200, 800)));
+        assert_eq!(run(&mut Min::default()), Some((100, 700)));
+        assert_eq!(run(&mut Max::default()), Some((300, 900)));
+        assert_eq!(run(&mut Last::default()), Some((201, 800)));
+    }
+
+    #[test]
+    fn implausible_input_flags_result() {
+        let mut mean = Mean::default();
+        mean.add(Measurement::from_raw(100, 900).flag_implausible());
+        mean.add(Measurement::from_raw(300, 700));
+        assert!(!mean.finish().unwrap().is_plausible());
+        mean.add(Measurement::from_raw(300, 700));
+        assert!(mean.finish().unwrap().is_plausible());
+    }
+
+    #[test]
+    fn finish_resets() {
+        let mut mean = Mean::default();
+        run(&mut mean);
+        assert!(mean.finish().is_none());
+        mean.add(Measurement::from_raw(5, 6));
+        assert_eq!(mean.finish().map(|m| m.raw_temperature_reading()), Some(5));
+    }
+}
@@ -1,12 +1,26 @@
 //! Async driver for SHT4x
+use core::mem::MaybeUninit;
+
 use embedded_hal_async::delay::DelayNs;
 use embedded_hal_async::i2c::{I2c, SevenBitAddress};
 
-use crate::common::{Config, DelayMode, Measurement, ReadingMode, Unvalidated};
+use crate::calibration::{Calibration, CalibrationStore};
+use crate::clock::Clock;
+#[cfg(feature = "defmt")]
+use crate::common::SensorId;
+use crate::common::{
+    Address, AveragedMeasurement, BurstAccumulator, CachedMeasurement, Config, DelayMode,
+    DriverState, DriverStats, HeaterCycle, HeaterDuration, HeaterPower, HeaterStats, HumidityClamp,
+    LatestMeasurement, Measurement, MeasurementSummary, PingPongSensor, PingPongState,
+    PlausibilityAction, RateLimitAction, ReadingMode, ResetVerification, Sensor, TimedMeasurement,
+    Unvalidated,
+};
 use crate::common::{
-    READ_SERIAL_NUMBER_COMMAND, SOFT_RESET_COMMAND, serial_number_from_read_bytes,
+    READ_SERIAL_NUMBER_COMMAND, SOFT_RESET_COMMAND, classify_nack, is_nack,
+    serial_number_from_read_bytes,
 };
-use crate::error::Error;
+use crate::error::{ConfigError, Error};
+use crate::retry::{FixedRetry, RetryPolicy};
 
 /// Async SHT4x sensor interface
 ///
@@ -49,6 +63,47 @@ use crate::error::Error;
 /// #   Ok(())
 /// # }
 /// ```
+///
+/// ## Sharing the I2C bus
+///
+/// Each measurement is made with two separate I2C transactions, writing
+/// the command and later reading the result, and the bus is not used
+/// during the delay in between. So if the driver is given a shared bus
+/// device (such as those from `embedded-hal-bus`), other devices can use
+/// the bus while a measurement is in progress, even during a 1-second
+/// heater pulse. Avoid locking a shared bus around a whole call to
+/// [`SHT4x::measure()`], which would hold the bus during the delay.
+///
+/// If the driver owns the bus outright, use
+/// [`SHT4x::measure_with_bus_window()`] to borrow the bus back from the
+/// driver while the measurement is in progress.
+///
+/// ## Cancellation
+///
+/// The futures returned by the driver methods may be dropped at any await
+/// point (for instance, by losing an embassy `select!`) without leaving the
+/// driver unusable. If a measurement future is dropped after the measurement
+/// command may have been sent, the sensor could still be busy converting, and
+/// would reject (with NACK) the next command sent to it.
+///
+/// To avoid this, the driver records that a measurement is in flight, and the
+/// next call to [`SHT4x::measure_with_settings()`] (or [`SHT4x::measure()`]
+/// or [`SHT4x::soft_reset()`]) first tries to read and discard the stale
+/// measurement. If the sensor is still busy, the driver waits for the
+/// maximum conversion time of the interrupted measurement before continuing.
+/// In the worst case this adds the full [`DelayMode::Maximum`] delay for the
+/// interrupted reading mode to the next call.
+///
+/// [`SHT4x::serial_number()`] takes no delay and so cannot wait for the
+/// sensor, and will return an I2C error if the sensor is still busy.
+/// Once the sensor accepts the serial number command the in-flight
+/// measurement is forgotten, unless it was interrupted on another sensor by
+/// dropping a [`SHT4x::measure_at()`] future.
+///
+/// To deal with an interrupted measurement straight away, rather than on
+/// the next call, use [`SHT4x::recover()`], which also soft resets the
+/// sensor. [`SHT4x::has_interrupted_measurement()`] tells you whether there
+/// is one.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SHT4x<I: I2c> {
     i2c: I,
@@ -59,19 +114,77 @@ pub struct SHT4x<I: I2c> {
     /// This buffer is reused for each read from the sensor.
     read_buffer: [u8; 6],
 
-    /// I2C address of your SHT4x sensor.
+    /// Optional short label for the sensor, such as `"outdoor"`.
     ///
-    /// If your sensor is not at all the default address (`0x44`), write to
-    /// this field after instantiation. The new address will affect all
-    /// subsequent I2C interactions.
-    pub address: SevenBitAddress,
+    /// When the `defmt` feature is enabled the label is included in log
+    /// messages alongside the address, and it is included in the output of
+    /// [`SHT4x::dump_state()`], which makes it easier to tell several
+    /// sensors apart. Write to this field after instantiation.
+    pub label: Option<&'static str>,
+
+    /// Default settings, such as the reading and delay modes used by
+    /// [`SHT4x::measure()`]. Read with [`SHT4x::config()`] and changed with
+    /// [`SHT4x::set_config()`].
+    config: Config,
+
+    /// Heater use recorded since construction.
+    heater_stats: HeaterStats,
+
+    /// Most recent measurement read by any method, for
+    /// [`SHT4x::last_measurement()`].
+    latest: LatestMeasurement,
+
+    /// The last unheated reading to pass the CRC check, plausible or not,
+    /// for the step limits of [`Config::plausibility`].
+    last_reading: Option<Measurement>,
+
+    /// Whether the last measurement command used the heater, so its reading
+    /// is exempt from the step limits of [`Config::plausibility`].
+    heated_reading: bool,
 
-    /// Default reading and delay modes used by [`SHT4x::measure()`].
-    pub config: Config,
+    /// Last measurement taken by [`SHT4x::measure()`] when the time was
+    /// known, for [`Config::cache_max_age_us`].
+    cache: Option<CachedMeasurement>,
+
+    /// When the last measurement limited by [`Config::rate_limit`] started.
+    rate_limited_at_us: Option<u64>,
+
+    /// The driver's own clock, set with [`SHT4x::set_clock()`].
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    clock: Option<fn() -> u64>,
+
+    /// The time most recently read from a clock passed to the driver, for
+    /// [`Config::heater_max_duty_cycle_percent`].
+    clock_us: Option<u64>,
+
+    /// When the last heater pulse began, by `clock_us`, and its duration.
+    heater_pulse: Option<(u64, HeaterDuration)>,
+
+    /// Whether no measurement has been taken since construction or the last
+    /// soft reset, for [`Config::discard_first_measurement`].
+    first_measurement_pending: bool,
+
+    /// Counters reported by [`SHT4x::dump_state()`].
+    stats: DriverStats,
+
+    /// Reading mode of a measurement that has been requested but not yet
+    /// read, if any, and the sensor taking it.
+    ///
+    /// This is only left set if a measurement future is dropped before
+    /// completion. See the "Cancellation" section of the struct docs.
+    in_flight: Option<(ReadingMode, Sensor)>,
 }
 
 impl<I: I2c> SHT4x<I> {
-    /// Create a new sensor with the default address of `0x44`.
+    /// Create a new sensor at the address given in `config`.
+    ///
+    /// This is a `const fn`, so if your I2C interface can also be created
+    /// in a `const` context, the driver can be placed directly in a `static`
+    /// (see also [`Config::new()`]).
+    ///
+    /// The config is not validated, so an invalid one, such as a reserved
+    /// custom address, only shows up as errors once the sensor is used. Use
+    /// [`SHT4x::try_new()`] to check it with [`Config::validate()`] first.
     ///
     /// Example usage of configuring the driver to use the heater on
     /// highest power, longest pulse, and maximum delay:
@@ -87,31 +200,304 @@ impl<I: I2c> SHT4x<I> {
     ///         HeaterDuration::Long,
     ///     ),
     ///     delay_mode: DelayMode::Maximum,
+    ///     ..Default::default()
     /// });
     /// # sensor.destroy().done();
     /// ```
-    pub fn new(i2c: I, config: Config) -> Self {
+    pub const fn new(i2c: I, config: Config) -> Self {
         Self {
             i2c,
-            address: 0x44,
+            label: None,
             read_buffer: [0u8; 6],
             config,
+            heater_stats: HeaterStats::new(),
+            latest: LatestMeasurement::new(),
+            last_reading: None,
+            heated_reading: false,
+            cache: None,
+            rate_limited_at_us: None,
+            clock: None,
+            clock_us: None,
+            heater_pulse: None,
+            first_measurement_pending: true,
+            stats: DriverStats::new(),
+            in_flight: None,
         }
     }
 
+    /// Create a new sensor as with [`SHT4x::new()`], if `config` is valid.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::Mock;
+    /// # let mut mock = Mock::new(&[]);
+    /// # let i2c = mock.clone();
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::common::{Address, Config};
+    /// use sht4x_rjw::error::ConfigError;
+    ///
+    /// let config = Config {
+    ///     address: Address::Custom(0x7F),
+    ///     ..Default::default()
+    /// };
+    /// let result = SHT4x::try_new(i2c, config);
+    /// assert!(matches!(result, Err(ConfigError::InvalidAddress(0x7F))));
+    /// # mock.done();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`Config::validate()`].
+    pub fn try_new(i2c: I, config: Config) -> Result<Self, ConfigError> {
+        config.validate()?;
+        Ok(Self::new(i2c, config))
+    }
+
+    /// Create a new sensor in caller-provided storage, returning a reference
+    /// to it with the same lifetime as the storage.
+    ///
+    /// This is useful for placing the driver in static storage and handing
+    /// a `&'static mut` reference to a task, as is common in async firmware.
+    /// The storage can come from [`static_cell::StaticCell::uninit()`],
+    /// `cortex_m::singleton!`, or a `static mut` accessed once at startup.
+    ///
+    /// Note that a driver created this way is never dropped, so its I2C
+    /// interface can't be recovered with [`SHT4x::destroy()`] unless you
+    /// move the driver out of the storage yourself.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::Mock;
+    /// # let i2c = Mock::new(&[]);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use static_cell::StaticCell;
+    ///
+    /// static SENSOR: StaticCell<SHT4x<Mock>> = StaticCell::new();
+    /// let sensor: &'static mut SHT4x<_> = SHT4x::new_in(SENSOR.uninit(), i2c, Default::default());
+    /// sensor.label = Some("indoor");
+    /// ```
+    ///
+    /// [`static_cell::StaticCell::uninit()`]: https://docs.rs/static_cell/latest/static_cell/struct.StaticCell.html#method.uninit
+    pub fn new_in(slot: &mut MaybeUninit<Self>, i2c: I, config: Config) -> &mut Self {
+        slot.write(Self::new(i2c, config))
+    }
+
     /// Drop the sensor struct and return its I2C interface.
     pub fn destroy(self) -> I {
         self.i2c
     }
 
+    /// The driver's current settings.
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    /// Replace the driver's settings with `config`, if it is valid.
+    ///
+    /// The new settings take effect all at once, from the next operation.
+    /// If the address changes, any measurement cached by
+    /// [`SHT4x::measure()`] is discarded, as it came from another
+    /// sensor, as is the reading used for the step limits of
+    /// [`Config::plausibility`]. The time of the last rate-limited measurement is kept, so
+    /// a new [`Config::rate_limit`] applies from that measurement.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::Mock;
+    /// # let i2c = Mock::new(&[]);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::common::{Config, DelayMode};
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let config = Config {
+    ///     delay_mode: DelayMode::Maximum,
+    ///     ..sensor.config()
+    /// };
+    /// sensor.set_config(config).unwrap();
+    /// assert_eq!(sensor.config().delay_mode, DelayMode::Maximum);
+    ///
+    /// let invalid = Config {
+    ///     delay_mode: DelayMode::Poll { interval_us: 0 },
+    ///     ..config
+    /// };
+    /// assert!(sensor.set_config(invalid).is_err());
+    /// assert_eq!(sensor.config(), config);
+    /// # sensor.destroy().done();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`Config::validate()`], in which case the driver's settings
+    /// are left unchanged.
+    pub fn set_config(&mut self, config: Config) -> Result<(), ConfigError> {
+        config.validate()?;
+        if config.address != self.config.address {
+            self.cache = None;
+            self.last_reading = None;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Give the driver a clock of its own, or take it away with `None`.
+    ///
+    /// The driver otherwise only knows the time when it is passed a
+    /// [`Clock`], as with [`SHT4x::measure_with_clock()`]. With a clock of
+    /// its own, [`SHT4x::measure()`] and the methods built on it follow
+    /// [`Config::cache_max_age_us`] and [`Config::rate_limit`] too. The clock
+    /// returns the current time in microseconds, as for [`Clock::now_us()`],
+    /// and can be any closure that captures nothing.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::Mock;
+    /// # let i2c = Mock::new(&[]);
+    /// use std::sync::OnceLock;
+    /// use std::time::Instant;
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// static START: OnceLock<Instant> = OnceLock::new();
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// sensor.set_clock(Some(|| START.get_or_init(Instant::now).elapsed().as_micros() as u64));
+    /// # sensor.destroy().done();
+    /// ```
+    pub fn set_clock(&mut self, clock: Option<fn() -> u64>) {
+        self.clock = clock;
+    }
+
+    /// Heater use recorded by this driver since it was constructed.
+    ///
+    /// See [`HeaterStats`] for how the heater pulses and on-time are counted,
+    /// and for an estimate of the remaining heater duty budget.
+    pub fn heater_stats(&self) -> HeaterStats {
+        self.heater_stats
+    }
+
+    /// The driver's calibration offsets and heater use, for persisting with
+    /// [`SHT4x::store_calibration()`].
+    pub fn calibration(&self) -> Calibration {
+        Calibration {
+            temperature_offset_raw: self.config.temperature_offset_raw,
+            humidity_offset_raw: self.config.humidity_offset_raw,
+            heater_stats: self.heater_stats,
+        }
+    }
+
+    /// Apply calibration offsets and restore heater use, typically as
+    /// loaded from persistent storage.
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.config.temperature_offset_raw = calibration.temperature_offset_raw;
+        self.config.humidity_offset_raw = calibration.humidity_offset_raw;
+        self.heater_stats = calibration.heater_stats;
+    }
+
+    /// Load calibration from `store` and apply it to the driver.
+    ///
+    /// Returns `Ok(false)`, leaving the driver unchanged, if the store holds
+    /// no calibration. See the [`calibration`](crate::calibration) module
+    /// for an example.
+    pub fn load_calibration<S: CalibrationStore>(
+        &mut self,
+        store: &mut S,
+    ) -> Result<bool, S::Error> {
+        let calibration = store.load_calibration()?;
+        if let Some(calibration) = calibration {
+            self.set_calibration(calibration);
+        }
+        Ok(calibration.is_some())
+    }
+
+    /// Save the driver's current calibration to `store`.
+    pub fn store_calibration<S: CalibrationStore>(&self, store: &mut S) -> Result<(), S::Error> {
+        store.store_calibration(&self.calibration())
+    }
+
+    /// The serial number from the last successful read, by
+    /// [`SHT4x::serial_number()`] or a method that uses it such as
+    /// [`SHT4x::init()`], or `None` if it hasn't been read.
+    ///
+    /// This doesn't communicate with the sensor, so it is suitable for
+    /// logging on busy shared buses. It is not cleared if the config's
+    /// address is changed.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::Mock;
+    /// # let i2c = Mock::new(&[]);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// let sensor = SHT4x::new(i2c, Default::default());
+    /// assert_eq!(sensor.cached_serial(), None);
+    /// # sensor.destroy().done();
+    /// ```
+    pub fn cached_serial(&self) -> Option<u32> {
+        self.stats.serial_number
+    }
+
+    /// Snapshot of the driver's settings and statistics, for debugging.
+    ///
+    /// The returned [`DriverState`] includes the sensor address, config, the
+    /// last serial number read from the sensor, counts of measurements and
+    /// errors, heater use, and the most recent error. It implements
+    /// [`Display`](core::fmt::Display) for logging, and with the `defmt`
+    /// feature enabled this method also logs the snapshot at info level,
+    /// so a single call gives a full picture over RTT.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::Mock;
+    /// # let i2c = Mock::new(&[]);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// sensor.label = Some("outdoor");
+    /// let state = sensor.dump_state();
+    /// assert_eq!(state.measurements, 0);
+    /// assert!(state.to_string().starts_with("SHT4x outdoor at 0x44"));
+    /// # sensor.destroy().done();
+    /// ```
+    pub fn dump_state(&self) -> DriverState {
+        let state = DriverState {
+            address: self.address(),
+            label: self.label,
+            config: self.config,
+            serial_number: self.stats.serial_number,
+            measurements: self.stats.measurements,
+            errors: self.stats.errors,
+            heater_stats: self.heater_stats,
+            last_error: self.stats.last_error,
+        };
+
+        #[cfg(feature = "defmt")]
+        defmt::info!("{}", state);
+
+        state
+    }
+
     /// Read the 4-byte serial number from the sensor.
     ///
     /// # Errors
     ///
     /// An error may be returned if the serial number data bytes fail
     /// to pass CRC validation, or if a problem occurs with the I2C
-    /// interface.
+    /// interface. Transient failures are retried immediately, up to the
+    /// number of attempts in [`Config::retry`].
+    ///
+    /// As this method takes no delay, the [`backoff`] in [`Config::retry`]
+    /// is ignored. To wait between attempts, pass the config's retry
+    /// settings to [`serial_number_with_retry`] instead:
+    /// `sensor.serial_number_with_retry(delay, sensor.config().retry)`.
+    ///
+    /// [`backoff`]: crate::retry::RetryConfig::backoff
+    /// [`serial_number_with_retry`]: Self::serial_number_with_retry
     pub async fn serial_number(&mut self) -> Result<u32, Error<I::Error>> {
+        let mut policy = self.config.retry;
+        let mut failures = 0;
+        loop {
+            let error = match self.read_serial_number().await {
+                Ok(serial) => return Ok(serial),
+                Err(error) => error,
+            };
+            failures += 1;
+            policy.retry_after_us(failures, error.kind()).ok_or(error)?;
+        }
+    }
+
+    /// Read the serial number once, without retrying.
+    async fn read_serial_number(&mut self) -> Result<u32, Error<I::Error>> {
         // Note that the SHT4x I2C interface requires a STOP condition after
         // the write, so we cannot use self.i2c.write_read(), which issues
         // a REPEATED-START between writing the command and attempting to
@@ -120,21 +506,196 @@ impl<I: I2c> SHT4x<I> {
         // This is the case even here, where no delay is needed for the
         // sensor to make the data available for reading.
         #[cfg(feature = "defmt")]
-        defmt::debug!("Reading serial of sensor at {=u8:#02X}", self.address);
+        defmt::debug!("Reading serial of sensor {}", self.sensor_id());
 
-        self.i2c
-            .write(self.address, &[READ_SERIAL_NUMBER_COMMAND])
-            .await?;
-        self.i2c.read(self.address, &mut self.read_buffer).await?;
+        let result = self
+            .i2c
+            .write(self.address(), &[READ_SERIAL_NUMBER_COMMAND])
+            .await;
+        self.record(result)?;
+        // The sensor is evidently idle, so any measurement interrupted on it
+        // has completed and its data will be replaced by the serial number.
+        if let Some((_, Sensor::Configured)) = self.in_flight {
+            self.in_flight = None;
+        }
+        let result = self.i2c.read(self.address(), &mut self.read_buffer).await;
+        self.record(result)?;
 
         #[cfg(feature = "defmt")]
         defmt::debug!(
-            "Bytes from sensor {=u8:#02X}: {=[u8; 6]:#02X}",
-            self.address,
+            "Bytes from sensor {}: {=[u8; 6]:#02X}",
+            self.sensor_id(),
             self.read_buffer
         );
 
-        serial_number_from_read_bytes(Unvalidated::new(self.read_buffer))
+        let serial = self.record(serial_number_from_read_bytes(Unvalidated::new(
+            self.read_buffer,
+            self.config.validate_crc,
+        )))?;
+        self.stats.serial_number = Some(serial);
+        Ok(serial)
+    }
+
+    /// Read the serial number, retrying failures according to `policy`.
+    ///
+    /// See [`SHT4x::serial_number()`] and the [`retry`](crate::retry) module.
+    pub async fn serial_number_with_retry(
+        &mut self,
+        mut delay: impl DelayNs,
+        mut policy: impl RetryPolicy,
+    ) -> Result<u32, Error<I::Error>> {
+        let mut failures = 0;
+        loop {
+            let error = match self.read_serial_number().await {
+                Ok(serial) => return Ok(serial),
+                Err(error) => error,
+            };
+            failures += 1;
+            let wait_us = policy.retry_after_us(failures, error.kind()).ok_or(error)?;
+            delay.delay_us(wait_us).await;
+        }
+    }
+
+    /// Bring the sensor to a known-good state: soft reset it, read its serial
+    /// number and, if [`Config::discard_first_measurement`] is set, take and
+    /// discard the first measurement.
+    ///
+    /// The serial number is returned, and is also included in the output of
+    /// [`SHT4x::dump_state()`]. The serial number is read following the
+    /// config's retry settings, and checked against
+    /// [`Config::expected_serial`] if set.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0x94]),
+    /// #     Transaction::write(0x44, vec![0x89]),
+    /// #     Transaction::read(0x44, vec![0x01, 0x02, 0x17, 0x3, 0x4, 0x68]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let serial = sensor.init(&mut delay).await?;
+    /// assert_eq!(serial, 0x01020304);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::soft_reset()`], [`SHT4x::verify_serial()`] and
+    /// [`SHT4x::measure()`]. The remaining steps are skipped after an error.
+    pub async fn init(&mut self, mut delay: impl DelayNs) -> Result<u32, Error<I::Error>> {
+        self.soft_reset(&mut delay).await?;
+        let serial = self.serial_number().await?;
+        if let Some(expected) = self.config.expected_serial {
+            self.check_serial(expected, serial)?;
+        }
+        if self.config.discard_first_measurement {
+            let Config {
+                reading_mode,
+                delay_mode,
+                ..
+            } = self.config;
+            self.measure_once(
+                Sensor::Configured,
+                &mut delay,
+                reading_mode,
+                delay_mode,
+                None,
+                None,
+            )
+            .await?;
+            self.first_measurement_pending = false;
+        }
+        Ok(serial)
+    }
+
+    /// Read the sensor's serial number and check that it is `expected`.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0x89]),
+    /// #     Transaction::read(0x44, vec![0x01, 0x02, 0x17, 0x3, 0x4, 0x68]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::error::Error;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// assert!(matches!(
+    ///     sensor.verify_serial(0x0BADCAFE).await,
+    ///     Err(Error::SerialMismatch { actual: 0x01020304, .. })
+    /// ));
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`Error::SerialMismatch`] if the sensor reports another serial
+    /// number, and otherwise as for [`SHT4x::serial_number()`].
+    pub async fn verify_serial(&mut self, expected: u32) -> Result<(), Error<I::Error>> {
+        let serial = self.serial_number().await?;
+        self.check_serial(expected, serial)
+    }
+
+    /// Fail with [`Error::SerialMismatch`], recording the error, if `actual`
+    /// is not `expected`.
+    fn check_serial(&mut self, expected: u32, actual: u32) -> Result<(), Error<I::Error>> {
+        if actual == expected {
+            return Ok(());
+        }
+        self.record(Err(Error::SerialMismatch { expected, actual }))
+    }
+
+    /// Check that a device acknowledges at the sensor's address.
+    ///
+    /// This reads the serial number, once and without retries, which is
+    /// quick and doesn't disturb the sensor, making it suitable for startup
+    /// diagnostics. A sensor that is busy measuring also fails to
+    /// acknowledge, so only probe when no measurement is in progress.
+    ///
+    /// ```rust
+    /// # use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0x89])
+    /// #         .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::error::Error;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// assert!(matches!(sensor.probe().await, Err(Error::NotPresent)));
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NotPresent`] if the device doesn't acknowledge. Other errors
+    /// mean that something responded, but not correctly: an I2C error other
+    /// than a NACK, or a serial number that fails CRC validation.
+    pub async fn probe(&mut self) -> Result<(), Error<I::Error>> {
+        match self.read_serial_number().await {
+            Ok(_) => Ok(()),
+            Err(Error::I2c(e)) if is_nack(&e) => Err(Error::NotPresent),
+            Err(e) => Err(e),
+        }
     }
 
     /// Reset the sensor and wait for it to return to its idle state.
@@ -144,68 +705,2320 @@ impl<I: I2c> SHT4x<I> {
     /// An error may be returned if there is a problem with the I2C interface.
     pub async fn soft_reset(&mut self, mut delay: impl DelayNs) -> Result<(), Error<I::Error>> {
         #[cfg(feature = "defmt")]
-        defmt::debug!("Issuing soft reset to sensor at {=u8:#02X}", self.address);
+        defmt::debug!("Issuing soft reset to sensor {}", self.sensor_id());
 
-        self.i2c.write(self.address, &[SOFT_RESET_COMMAND]).await?;
+        self.finish_interrupted_measurement(&mut delay).await;
+        let result = self.i2c.write(self.address(), &[SOFT_RESET_COMMAND]).await;
+        self.record(result)?;
+        self.first_measurement_pending = true;
         delay.delay_ms(1).await;
         Ok(())
     }
 
+    /// Whether a measurement future was dropped after the measurement
+    /// command may have been sent, so the sensor could still be busy.
+    ///
+    /// See [Cancellation](SHT4x#cancellation).
+    pub fn has_interrupted_measurement(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Bring the sensor back to its idle state after a measurement future
+    /// was dropped mid-measurement, so that the next command succeeds.
+    ///
+    /// If there is an interrupted measurement, it is read and discarded,
+    /// waiting for it to complete if the sensor is still busy, and then the
+    /// sensor is soft reset. Otherwise this does nothing. See
+    /// [Cancellation](SHT4x#cancellation).
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::soft_reset()`].
+    pub async fn recover(&mut self, mut delay: impl DelayNs) -> Result<(), Error<I::Error>> {
+        if self.in_flight.is_none() {
+            return Ok(());
+        }
+        self.soft_reset(&mut delay).await
+    }
+
+    /// Reset the sensor, then check that it is responsive.
+    ///
+    /// After the reset's settle delay, the sensor's serial number is read or
+    /// a low-precision measurement is taken, according to `verification`.
+    /// This lets you know that the reset actually worked, rather than only
+    /// that the reset command was sent.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0x94]),
+    /// #     Transaction::write(0x44, vec![0x89]),
+    /// #     Transaction::read(0x44, vec![0x01, 0x02, 0x17, 0x3, 0x4, 0x68]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::common::ResetVerification;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// sensor
+    ///     .soft_reset_and_verify(&mut delay, ResetVerification::SerialNumber).await?;
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if there is a problem with the I2C interface,
+    /// either when resetting or when verifying, or if the verification
+    /// response fails its CRC check.
+    pub async fn soft_reset_and_verify(
+        &mut self,
+        mut delay: impl DelayNs,
+        verification: ResetVerification,
+    ) -> Result<(), Error<I::Error>> {
+        self.soft_reset(&mut delay).await?;
+        match verification {
+            ResetVerification::SerialNumber => self.serial_number().await.map(|_| ()),
+            ResetVerification::Measurement => self
+                .measure_from(
+                    Sensor::Configured,
+                    delay,
+                    ReadingMode::LowPrecision,
+                    DelayMode::Typical,
+                    None,
+                    None,
+                )
+                .await
+                .map(|_| ()),
+        }
+    }
+
     /// Measure temperature and humidity with the settings provided upon
     /// construction of the sensor struct.
     ///
     /// This method is a convenience wrapper around [`SHT4x::measure_with_settings()`]
     /// so that it is not necessary to specify the reading and delay mode
-    /// each time you wish to obtain a measurement from the sensor.
+    /// each time you wish to obtain a measurement from the sensor. Failures
+    /// are retried according to [`Config::retry`].
+    ///
+    /// When the driver knows the time, from its own clock set with
+    /// [`SHT4x::set_clock()`] or from the clock passed to
+    /// [`SHT4x::measure_with_clock()`], a measurement taken within
+    /// [`Config::cache_max_age_us`] is returned again without communicating
+    /// with the sensor, and [`Config::rate_limit`] applies to new
+    /// measurements. Without a clock, a new measurement is always taken,
+    /// and it is not limited.
     pub async fn measure(&mut self, delay: impl DelayNs) -> Result<Measurement, Error<I::Error>> {
-        self.measure_with_settings(delay, self.config.reading_mode, self.config.delay_mode)
-            .await
+        let now_us = self.read_clock();
+        self.measure_or_reuse(delay, now_us).await
     }
 
-    /// Measure temperature and humidity with the given settings.
-    ///
-    /// # Errors
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, retrying failures according to
+    /// `policy`.
     ///
-    /// An error may be returned if either the temperature or humidity
-    /// data bytes fail to pass CRC validation, or if a problem occurs
-    /// with the I2C interface.
+    /// Both I2C errors and CRC failures are passed to the policy, which
+    /// decides whether to retry and how long to wait first. If the policy
+    /// gives up, the last error is returned. The policy is used instead of
+    /// [`Config::retry`].
     ///
-    /// # Timing
+    /// ```rust
+    /// # use embedded_hal::i2c::ErrorKind;
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]).with_error(ErrorKind::Bus),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::retry::ExponentialBackoff;
     ///
-    /// A delay is required between requesting the measurement and being able
-    /// to read in the data. This varies depending on your reading and delay
-    /// modes. Refer to the [DelayMode] documentation for the length
-    /// of the delay.
-    pub async fn measure_with_settings(
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// // Retry up to 3 times, waiting 1ms, 2ms, then 4ms.
+    /// let policy = ExponentialBackoff::new(3, 1_000, 10_000);
+    /// let measurement = sensor.measure_with_retry(&mut delay, policy).await?;
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn measure_with_retry(
+        &mut self,
+        delay: impl DelayNs,
+        policy: impl RetryPolicy,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let now_us = self.read_clock();
+        self.measure_from_with_retry(Sensor::Configured, delay, policy, now_us, None)
+            .await
+    }
+
+    /// Measure from `sensor` as for [`SHT4x::measure_with_retry()`], reading
+    /// into `buffer` if given rather than the internal buffer. Only the
+    /// first attempt is checked against [`Config::rate_limit`] at `now_us`,
+    /// as the retries are part of the same measurement.
+    async fn measure_from_with_retry(
         &mut self,
+        sensor: Sensor,
         mut delay: impl DelayNs,
-        reading_mode: ReadingMode,
-        delay_mode: DelayMode,
+        mut policy: impl RetryPolicy,
+        mut now_us: Option<u64>,
+        mut buffer: Option<&mut [u8; 6]>,
     ) -> Result<Measurement, Error<I::Error>> {
-        let command = reading_mode.command_byte();
-        let us = delay_mode.us_for_reading_mode(reading_mode);
+        let mut failures = 0;
+        loop {
+            let Config {
+                reading_mode,
+                delay_mode,
+                ..
+            } = self.config;
+            let result = self
+                .measure_from(
+                    sensor,
+                    &mut delay,
+                    reading_mode,
+                    delay_mode,
+                    now_us.take(),
+                    buffer.as_deref_mut(),
+                )
+                .await;
+            let error = match result {
+                Ok(measurement) => return Ok(measurement),
+                Err(error) => error,
+            };
+            failures += 1;
+            let wait_us = policy.retry_after_us(failures, error.kind()).ok_or(error)?;
+            delay.delay_us(wait_us).await;
+        }
+    }
 
-        #[cfg(feature = "defmt")]
-        defmt::debug!(
-            "Measuring from sensor {=u8:#02X}: {} ({=u8:#02X}), {} ({=u32} us)",
-            self.address,
-            reading_mode,
-            command,
-            delay_mode,
-            us
-        );
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, making up to `attempts` attempts.
+    ///
+    /// Transient errors, such as CRC failures and reads made before the
+    /// measurement is ready, are retried immediately. A NACK of the
+    /// measurement command is reported as [`Error::NotPresent`] and is not
+    /// retried. This is a shorthand for [`SHT4x::measure_with_retry()`]
+    /// with a [`FixedRetry`] policy.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x00, 0x56, 0x78, 0x7D]),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// // The first attempt fails CRC validation, and the second succeeds.
+    /// let measurement = sensor.measure_with_retries(&mut delay, 3).await?;
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The last error is returned if every attempt fails, or the first
+    /// error that isn't transient. A single attempt is made if `attempts`
+    /// is zero.
+    pub async fn measure_with_retries(
+        &mut self,
+        delay: impl DelayNs,
+        attempts: u32,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let policy = FixedRetry::new(attempts.saturating_sub(1), 0);
+        self.measure_with_retry(delay, policy).await
+    }
 
-        self.i2c.write(self.address, &[command]).await?;
-        delay.delay_us(us).await;
-        self.i2c.read(self.address, &mut self.read_buffer).await?;
+    /// Take `samples` consecutive measurements with the settings provided
+    /// upon construction of the sensor struct, and return their mean and
+    /// spread.
+    ///
+    /// The readings are summed as integers, so no precision is lost before
+    /// the mean is rounded to the nearest raw reading. At least one
+    /// measurement is taken, even if `samples` is zero.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x36, 0x55, 0x56, 0x76, 0x62]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let averaged = sensor.measure_averaged(&mut delay, 2).await?;
+    /// assert_eq!(averaged.measurement.raw_temperature_reading(), 0x1235);
+    /// assert_eq!(averaged.raw_temperature_spread, 2);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`], except that each measurement is a new
+    /// one rather than cached. If any measurement fails, the error is
+    /// returned and the others are discarded.
+    pub async fn measure_averaged(
+        &mut self,
+        mut delay: impl DelayNs,
+        samples: u16,
+    ) -> Result<AveragedMeasurement, Error<I::Error>> {
+        let mut accumulator = BurstAccumulator::default();
+        for _ in 0..samples.max(1) {
+            let now_us = self.read_clock();
+            accumulator.add(self.take_measurement(&mut delay, now_us).await?);
+        }
+        // At least one measurement was added.
+        Ok(accumulator.averaged().unwrap())
+    }
 
-        #[cfg(feature = "defmt")]
+    /// Take a burst of `samples` measurements with the settings provided
+    /// upon construction of the sensor struct, and summarise them with the
+    /// minimum, maximum and mean temperature and humidity.
+    ///
+    /// If `samples` is zero, a single measurement is taken.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x36, 0x55, 0x56, 0x76, 0x62]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let summary = sensor.measure_summary(&mut delay, 2).await?;
+    /// assert_eq!(summary.min.raw_temperature_reading(), 0x1234);
+    /// assert_eq!(summary.max.raw_temperature_reading(), 0x1236);
+    /// assert_eq!(summary.mean.raw_temperature_reading(), 0x1235);
+    /// assert_eq!(summary.min.raw_humidity_reading(), 0x5676);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`], except that each measurement is a new
+    /// one rather than cached. If any measurement fails, the error is
+    /// returned and the others are discarded.
+    pub async fn measure_summary(
+        &mut self,
+        mut delay: impl DelayNs,
+        samples: u16,
+    ) -> Result<MeasurementSummary, Error<I::Error>> {
+        let mut accumulator = BurstAccumulator::default();
+        for _ in 0..samples.max(1) {
+            let now_us = self.read_clock();
+            accumulator.add(self.take_measurement(&mut delay, now_us).await?);
+        }
+        // At least one measurement was added.
+        Ok(accumulator.summary().unwrap())
+    }
+
+    /// Take a burst of `N` measurements with the settings provided upon
+    /// construction of the sensor struct, waiting `gap_us` microseconds
+    /// between the end of one measurement and the start of the next.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x36, 0x55, 0x56, 0x76, 0x62]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// // Two measurements, 10ms apart.
+    /// let [first, second] = sensor.measure_n(&mut delay, 10_000).await?;
+    /// assert_eq!(second.raw_temperature_reading(), 0x1236);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`], except that each measurement is a new
+    /// one rather than cached. If any measurement fails, the error is
+    /// returned and the others are discarded.
+    pub async fn measure_n<const N: usize>(
+        &mut self,
+        mut delay: impl DelayNs,
+        gap_us: u32,
+    ) -> Result<[Measurement; N], Error<I::Error>> {
+        let mut measurements = [Measurement::from_raw(0, 0); N];
+        for (i, measurement) in measurements.iter_mut().enumerate() {
+            if i > 0 {
+                delay.delay_us(gap_us).await;
+            }
+            let now_us = self.read_clock();
+            *measurement = self.take_measurement(&mut delay, now_us).await?;
+        }
+        Ok(measurements)
+    }
+
+    /// The most recent measurement read from the sensor by any method, or
+    /// `None` if there hasn't been one.
+    ///
+    /// This doesn't communicate with the sensor, so several parts of your
+    /// program can share the latest measurement without each triggering a
+    /// new one. Use [`SHT4x::failures_since_last_measurement()`] to tell
+    /// whether it is stale.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x00, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// sensor.measure(&mut delay).await?;
+    /// // The next measurement fails its CRC check.
+    /// assert!(sensor.measure(&mut delay).await.is_err());
+    ///
+    /// let latest = sensor.last_measurement().unwrap();
+    /// assert_eq!(latest.raw_temperature_reading(), 0x1234);
+    /// assert_eq!(sensor.failures_since_last_measurement(), 1);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub fn last_measurement(&self) -> Option<Measurement> {
+        self.latest.measurement
+    }
+
+    /// The number of measurement attempts that have failed since
+    /// [`SHT4x::last_measurement()`] was read from the sensor, or since
+    /// construction if there hasn't been a measurement.
+    pub fn failures_since_last_measurement(&self) -> u32 {
+        self.latest.failures_since
+    }
+
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, recording the time at which the
+    /// measurement was read according to `clock`.
+    ///
+    /// Timestamps let downstream filtering and logging use the actual time
+    /// between measurements, which varies with the reading mode, retries
+    /// and task scheduling. The measurement is always a new one rather than
+    /// cached, and is subject to [`Config::rate_limit`].
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let clock = || 1_500_000;
+    /// let timed = sensor.measure_timestamped(&mut delay, &clock).await?;
+    /// assert_eq!(timed.taken_at_us, 1_500_000);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`].
+    pub async fn measure_timestamped(
+        &mut self,
+        delay: impl DelayNs,
+        clock: &impl Clock,
+    ) -> Result<TimedMeasurement, Error<I::Error>> {
+        let now_us = self.note_time(clock);
+        let measurement = self.take_measurement(delay, Some(now_us)).await?;
+        Ok(TimedMeasurement {
+            measurement,
+            taken_at_us: clock.now_us(),
+        })
+    }
+
+    /// Measure with the settings provided upon construction of the sensor
+    /// struct, returning only the temperature in celsius.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let celsius = sensor.measure_celsius(&mut delay).await?;
+    /// assert!((celsius - 25.0).abs() < 0.01);
+    /// let humidity = sensor.measure_humidity(&mut delay).await?;
+    /// assert!((humidity - 56.5).abs() < 0.01);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`].
+    #[cfg(feature = "float")]
+    pub async fn measure_celsius(&mut self, delay: impl DelayNs) -> Result<f32, Error<I::Error>> {
+        Ok(self.measure(delay).await?.celsius())
+    }
+
+    /// Measure with the settings provided upon construction of the sensor
+    /// struct, returning only the relative humidity in percent.
+    ///
+    /// See [`SHT4x::measure_celsius()`] for an example.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`].
+    #[cfg(feature = "float")]
+    pub async fn measure_humidity(&mut self, delay: impl DelayNs) -> Result<f32, Error<I::Error>> {
+        Ok(self.measure(delay).await?.humidity())
+    }
+
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, from the sensor at `address`
+    /// rather than the one in the config.
+    ///
+    /// This lets one driver serve several sensors on the same bus, such as
+    /// SHT4x variants at `0x44`, `0x45` and `0x46`, without changing the
+    /// config's address around each call. The driver's config and statistics
+    /// are shared between the sensors, but the state it keeps for the config's
+    /// sensor is not: a measurement from `address` is never cached, rate
+    /// limited or discarded as the first after a reset, is not returned by
+    /// [`SHT4x::last_measurement()`], is not checked against the step limits
+    /// of [`Config::plausibility`], and is not recorded in
+    /// [`SHT4x::heater_stats()`].
+    ///
+    /// If the future is dropped before it completes, the interrupted
+    /// measurement is finished at `address` by the next call (see
+    /// [Cancellation](SHT4x#cancellation)). The config's address is never
+    /// changed.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x45, vec![0xFD]),
+    /// #     Transaction::read(0x45, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::common::Address;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let measurement = sensor.measure_at(Address::Alt0x45, &mut delay).await?;
+    /// assert_eq!(sensor.config().address, Address::Default0x44);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] with [`ConfigError::InvalidAddress`],
+    /// without communicating with the sensor, if `address` is reserved or
+    /// does not fit in seven bits. Otherwise as for [`SHT4x::measure()`].
+    pub async fn measure_at(
+        &mut self,
+        address: Address,
+        delay: impl DelayNs,
+    ) -> Result<Measurement, Error<I::Error>> {
+        if !address.is_valid() {
+            return Err(Error::InvalidConfig(ConfigError::InvalidAddress(
+                address.value(),
+            )));
+        }
+        self.measure_from_with_retry(Sensor::At(address), delay, self.config.retry, None, None)
+            .await
+    }
+
+    /// Measure temperature and humidity as for [`SHT4x::measure()`], with the
+    /// time read from `clock` rather than the driver's own clock.
+    ///
+    /// This lets the driver follow [`Config::cache_max_age_us`] and
+    /// [`Config::rate_limit`] without a clock set with
+    /// [`SHT4x::set_clock()`], such as when the clock needs to capture some
+    /// state.
+    ///
+    /// A measurement taken no more than `cache_max_age_us` microseconds
+    /// earlier, by this method or by `measure()`, is returned again without
+    /// communicating with the sensor. This is useful when several parts of
+    /// your program independently want the current temperature or
+    /// humidity, and it doesn't matter if the reading is slightly old.
+    ///
+    /// If a new measurement is needed but the rate limit's minimum interval
+    /// has not passed since the start of the last limited measurement, the
+    /// measurement is either refused with [`Error::TooSoon`] or taken after
+    /// waiting for the rest of the interval, depending on the rate limit's
+    /// [`RateLimitAction`].
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # use core::cell::Cell;
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::common::{Config, RateLimit, RateLimitAction};
+    /// use sht4x_rjw::error::Error;
+    /// let config = Config {
+    ///     cache_max_age_us: Some(1_000_000),
+    ///     rate_limit: Some(RateLimit {
+    ///         min_interval_us: 10_000_000,
+    ///         action: RateLimitAction::Reject,
+    ///     }),
+    ///     ..Default::default()
+    /// };
+    /// let mut sensor = SHT4x::new(i2c, config);
+    /// let now_us = Cell::new(0);
+    /// let clock = || now_us.get();
+    ///
+    /// let first = sensor.measure_with_clock(&mut delay, &clock).await?;
+    /// now_us.set(500_000);
+    /// // Half a second later the cached measurement is returned.
+    /// let second = sensor.measure_with_clock(&mut delay, &clock).await?;
+    /// assert_eq!(first.raw_temperature_reading(), second.raw_temperature_reading());
+    /// now_us.set(2_000_000);
+    /// // Two seconds later a new measurement is needed, but refused.
+    /// assert!(matches!(
+    ///     sensor.measure_with_clock(&mut delay, &clock).await,
+    ///     Err(Error::TooSoon)
+    /// ));
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`Error::TooSoon`] if the measurement is refused by the rate limit,
+    /// and otherwise as for [`SHT4x::measure()`]. Errors are not cached.
+    pub async fn measure_with_clock(
+        &mut self,
+        delay: impl DelayNs,
+        clock: &impl Clock,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let now_us = self.note_time(clock);
+        self.measure_or_reuse(delay, Some(now_us)).await
+    }
+
+    /// Measure as for [`SHT4x::measure()`] at `now_us`, if the time is known,
+    /// reusing a cached measurement that is fresh enough.
+    async fn measure_or_reuse(
+        &mut self,
+        delay: impl DelayNs,
+        now_us: Option<u64>,
+    ) -> Result<Measurement, Error<I::Error>> {
+        if let (Some(now_us), Some(cached), Some(max_age_us)) =
+            (now_us, self.cache, self.config.cache_max_age_us)
+            && let Some(measurement) = cached.fresh(now_us, max_age_us)
+        {
+            return Ok(measurement);
+        }
+
+        let measurement = self.take_measurement(delay, now_us).await?;
+        if let Some(now_us) = now_us {
+            self.cache = Some(CachedMeasurement {
+                measurement,
+                taken_at_us: now_us,
+            });
+        }
+        Ok(measurement)
+    }
+
+    /// Take a new measurement with the settings provided upon construction
+    /// of the sensor struct, retried according to [`Config::retry`] and,
+    /// if the time `now_us` is known, limited by [`Config::rate_limit`].
+    pub(crate) async fn take_measurement(
+        &mut self,
+        delay: impl DelayNs,
+        now_us: Option<u64>,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let retry = self.config.retry;
+        self.measure_from_with_retry(Sensor::Configured, delay, retry, now_us, None)
+            .await
+    }
+
+    /// Run the heater, then take a normal high-precision measurement once
+    /// the sensor has had `settle_us` microseconds to cool.
+    ///
+    /// Pulsing the heater drives off condensation or creep after long
+    /// periods at high humidity, but the heated measurement's temperature
+    /// reflects the heater rather than the surroundings. Choose `settle_us`
+    /// to allow the heater's warmth to dissipate before the ambient
+    /// measurement. Both measurements use the config's delay mode.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0x39]),
+    /// #     Transaction::read(0x44, vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::common::{HeaterDuration, HeaterPower};
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// // Heat at high power for a second, then let the sensor settle for 5s.
+    /// let (power, duration) = (HeaterPower::High, HeaterDuration::Long);
+    /// let cycle = sensor
+    ///     .measure_heated_then_ambient(&mut delay, power, duration, 5_000_000)
+    ///     .await?;
+    /// assert_eq!(cycle.ambient.raw_temperature_reading(), 0x1234);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure_with_settings()`]. If the heater measurement
+    /// fails, the ambient measurement is not taken.
+    pub async fn measure_heated_then_ambient(
+        &mut self,
+        mut delay: impl DelayNs,
+        heater_power: HeaterPower,
+        heater_duration: HeaterDuration,
+        settle_us: u32,
+    ) -> Result<HeaterCycle, Error<I::Error>> {
+        let delay_mode = self.config.delay_mode;
+        let heater_mode = ReadingMode::HighPrecisionWithHeater(heater_power, heater_duration);
+        let heated = self
+            .measure_with_settings(&mut delay, heater_mode, delay_mode)
+            .await?;
+        delay.delay_us(settle_us).await;
+        // The ambient measurement completes the cycle, so is not limited.
+        let ambient = self
+            .measure_from(
+                Sensor::Configured,
+                &mut delay,
+                ReadingMode::HighPrecision,
+                delay_mode,
+                None,
+                None,
+            )
+            .await?;
+        Ok(HeaterCycle { heated, ambient })
+    }
+
+    /// Measure temperature and humidity with the given settings.
+    ///
+    /// # Errors
+    ///
+    /// An error may be returned if either the temperature or humidity
+    /// data bytes fail to pass CRC validation, or if a problem occurs
+    /// with the I2C interface. If the driver has a clock of its own,
+    /// [`Error::TooSoon`] is returned if [`Config::rate_limit`] refuses the
+    /// measurement.
+    ///
+    /// # Timing
+    ///
+    /// A delay is required between requesting the measurement and being able
+    /// to read in the data. This varies depending on your reading and delay
+    /// modes. Refer to the [DelayMode] documentation for the length
+    /// of the delay.
+    ///
+    /// # Cancellation
+    ///
+    /// This future may be dropped at any point. The next measurement will
+    /// then wait out the interrupted one if necessary. See the struct-level
+    /// documentation for details.
+    pub async fn measure_with_settings(
+        &mut self,
+        delay: impl DelayNs,
+        reading_mode: ReadingMode,
+        delay_mode: DelayMode,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let now_us = self.read_clock();
+        self.measure_from(
+            Sensor::Configured,
+            delay,
+            reading_mode,
+            delay_mode,
+            now_us,
+            None,
+        )
+        .await
+    }
+
+    /// Measure from `sensor` as for [`SHT4x::measure_with_settings()`],
+    /// checking the first measurement command against [`Config::rate_limit`]
+    /// at `now_us` and reading into `buffer` if given. The first measurement
+    /// is only discarded from the config's sensor.
+    async fn measure_from(
+        &mut self,
+        sensor: Sensor,
+        mut delay: impl DelayNs,
+        reading_mode: ReadingMode,
+        delay_mode: DelayMode,
+        mut now_us: Option<u64>,
+        mut buffer: Option<&mut [u8; 6]>,
+    ) -> Result<Measurement, Error<I::Error>> {
+        if sensor != Sensor::Configured {
+            return self
+                .measure_once(sensor, delay, reading_mode, delay_mode, now_us, buffer)
+                .await;
+        }
+        if self.config.discard_first_measurement && self.first_measurement_pending {
+            let now_us = now_us.take();
+            let buffer = buffer.as_deref_mut();
+            self.measure_once(
+                Sensor::Configured,
+                &mut delay,
+                reading_mode,
+                delay_mode,
+                now_us,
+                buffer,
+            )
+            .await?;
+            self.first_measurement_pending = false;
+        }
+        let measurement = self
+            .measure_once(
+                Sensor::Configured,
+                delay,
+                reading_mode,
+                delay_mode,
+                now_us,
+                buffer,
+            )
+            .await?;
+        self.first_measurement_pending = false;
+        Ok(measurement)
+    }
+
+    /// Take a single measurement, as for [`SHT4x::measure_with_settings()`],
+    /// keeping to [`Config::rate_limit`] if the time `now_us` is known and
+    /// reading into `buffer` if given rather than the internal buffer.
+    async fn measure_once(
+        &mut self,
+        sensor: Sensor,
+        mut delay: impl DelayNs,
+        reading_mode: ReadingMode,
+        delay_mode: DelayMode,
+        now_us: Option<u64>,
+        buffer: Option<&mut [u8; 6]>,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let us = self.config.delay_us(reading_mode, delay_mode);
+
+        #[cfg(feature = "defmt")]
+        defmt::debug!(
+            "Measuring from sensor {}: {} ({=u8:#02X}), {} ({=u32} us)",
+            self.sensor_id_for(sensor),
+            reading_mode,
+            reading_mode.command_byte(),
+            delay_mode,
+            us
+        );
+
+        self.finish_interrupted_measurement(&mut delay).await;
+        let now_us = self.wait_for_rate_limit(&mut delay, now_us).await;
+        self.send_measurement_command(sensor, reading_mode, now_us)
+            .await?;
+        let mut internal = self.read_buffer;
+        let buffer = buffer.unwrap_or(&mut internal);
+        let result = if let DelayMode::Poll { interval_us } = delay_mode {
+            self.poll_measurement(sensor, delay, reading_mode, interval_us, buffer)
+                .await
+        } else {
+            delay.delay_us(us).await;
+            self.read_measurement_into(sensor, buffer).await
+        };
+        self.read_buffer = internal;
+        result
+    }
+
+    /// Measure temperature and humidity, failing if the measurement can't
+    /// be completed before `deadline`.
+    ///
+    /// The measurement uses the settings provided upon construction of the
+    /// sensor struct. If there isn't enough time left for the configured
+    /// reading mode and `allow_faster_mode` is true, the most precise
+    /// non-heater reading mode that fits the remaining time is used instead
+    /// (see [`ReadingMode::for_latency_budget()`]). If no mode fits, an
+    /// [`Error::DeadlineExceeded`] is returned without communicating with the
+    /// sensor.
+    ///
+    /// The measurement is also abandoned with [`Error::DeadlineExceeded`] if
+    /// the deadline passes while it is underway, for instance because the
+    /// I2C bus is busy. In that case the next measurement will wait out the
+    /// interrupted one, as described in the struct-level documentation.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     // Only 5ms left, so a medium-precision read.
+    /// #     Transaction::write(0x44, vec![0xF6]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use embassy_time::{Duration, Instant};
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::error::Error;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let deadline = Instant::now() + Duration::from_millis(5);
+    /// let measurement = sensor.measure_by(&mut delay, deadline, true).await?;
+    ///
+    /// let result = sensor.measure_by(&mut delay, deadline, false).await;
+    /// assert!(matches!(result, Err(Error::DeadlineExceeded)));
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "embassy-time")]
+    pub async fn measure_by(
+        &mut self,
+        delay: impl DelayNs,
+        deadline: embassy_time::Instant,
+        allow_faster_mode: bool,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let Config {
+            reading_mode,
+            delay_mode,
+            ..
+        } = self.config;
+        let remaining = deadline.saturating_duration_since(embassy_time::Instant::now());
+        let budget_us = u32::try_from(remaining.as_micros()).unwrap_or(u32::MAX);
+        let reading_mode = if self.config.delay_us(reading_mode, delay_mode) <= budget_us {
+            Some(reading_mode)
+        } else if allow_faster_mode {
+            ReadingMode::for_latency_budget(budget_us, delay_mode)
+        } else {
+            None
+        };
+        let Some(reading_mode) = reading_mode else {
+            return self.record(Err(Error::DeadlineExceeded));
+        };
+
+        let measurement = self.measure_with_settings(delay, reading_mode, delay_mode);
+        match embassy_time::with_deadline(deadline, measurement).await {
+            Ok(result) => result,
+            Err(embassy_time::TimeoutError) => self.record(Err(Error::DeadlineExceeded)),
+        }
+    }
+
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, failing with [`Error::Timeout`] if
+    /// the measurement takes longer than `timeout`.
+    ///
+    /// This guards against a wedged I2C bus hanging the task forever. Allow
+    /// for the measurement delay when choosing the timeout. If the timeout
+    /// expires during the measurement, the next measurement will wait out
+    /// the interrupted one, as described in the struct-level documentation.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use embassy_time::Duration;
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let measurement = sensor
+    ///     .measure_with_timeout(&mut delay, Duration::from_millis(50))
+    ///     .await?;
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "embassy-time")]
+    pub async fn measure_with_timeout(
+        &mut self,
+        delay: impl DelayNs,
+        timeout: embassy_time::Duration,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let result = embassy_time::with_timeout(timeout, self.measure(delay)).await;
+        self.timed_out(result)
+    }
+
+    /// Read the sensor's serial number, failing with [`Error::Timeout`] if
+    /// this takes longer than `timeout`.
+    ///
+    /// See [`SHT4x::serial_number()`] and [`SHT4x::measure_with_timeout()`].
+    #[cfg(feature = "embassy-time")]
+    pub async fn serial_number_with_timeout(
+        &mut self,
+        timeout: embassy_time::Duration,
+    ) -> Result<u32, Error<I::Error>> {
+        let result = embassy_time::with_timeout(timeout, self.serial_number()).await;
+        self.timed_out(result)
+    }
+
+    /// Soft reset the sensor, failing with [`Error::Timeout`] if this takes
+    /// longer than `timeout`.
+    ///
+    /// See [`SHT4x::soft_reset()`] and [`SHT4x::measure_with_timeout()`].
+    #[cfg(feature = "embassy-time")]
+    pub async fn soft_reset_with_timeout(
+        &mut self,
+        delay: impl DelayNs,
+        timeout: embassy_time::Duration,
+    ) -> Result<(), Error<I::Error>> {
+        let result = embassy_time::with_timeout(timeout, self.soft_reset(delay)).await;
+        self.timed_out(result)
+    }
+
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, waiting with `embassy-time`'s
+    /// timer rather than a delay you provide.
+    ///
+    /// ```rust
+    /// # use embedded_hal_async::i2c::I2c;
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::error::Error;
+    ///
+    /// async fn log_temperature<I: I2c>(sensor: &mut SHT4x<I>) -> Result<(), Error<I::Error>> {
+    ///     let measurement = sensor.measure_with_timer().await?;
+    ///     println!("{}°C", measurement.celsius());
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`].
+    #[cfg(feature = "embassy-time")]
+    pub async fn measure_with_timer(&mut self) -> Result<Measurement, Error<I::Error>> {
+        self.measure(embassy_time::Delay).await
+    }
+
+    /// Measure temperature and humidity with the given settings, waiting with
+    /// `embassy-time`'s timer rather than a delay you provide.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure_with_settings()`].
+    #[cfg(feature = "embassy-time")]
+    pub async fn measure_with_settings_with_timer(
+        &mut self,
+        reading_mode: ReadingMode,
+        delay_mode: DelayMode,
+    ) -> Result<Measurement, Error<I::Error>> {
+        self.measure_with_settings(embassy_time::Delay, reading_mode, delay_mode)
+            .await
+    }
+
+    /// Reset the sensor, waiting with `embassy-time`'s timer rather than a
+    /// delay you provide.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::soft_reset()`].
+    #[cfg(feature = "embassy-time")]
+    pub async fn soft_reset_with_timer(&mut self) -> Result<(), Error<I::Error>> {
+        self.soft_reset(embassy_time::Delay).await
+    }
+
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, reading the sensor's response into
+    /// `buffer` rather than the driver's internal buffer.
+    ///
+    /// This is for I2C implementations that use DMA, which may need the
+    /// transfer buffer to be in a particular memory region or suitably
+    /// aligned. The response is left in `buffer` after the measurement has
+    /// been decoded.
+    ///
+    /// Otherwise this measures as [`SHT4x::measure_with_settings()`] does,
+    /// with the config's settings, and is retried according to
+    /// [`Config::retry`]. Every read goes into `buffer`, including those of
+    /// retries, of [`DelayMode::Poll`] and of the measurement discarded by
+    /// [`Config::discard_first_measurement`]. The measurement is always a
+    /// new one rather than cached.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// #[repr(align(4))]
+    /// struct DmaBuffer([u8; 6]);
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let mut buffer = DmaBuffer([0; 6]);
+    /// let measurement = sensor.read_into(&mut delay, &mut buffer.0).await?;
+    /// assert_eq!(buffer.0, [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure_with_retry()`].
+    pub async fn read_into(
+        &mut self,
+        delay: impl DelayNs,
+        buffer: &mut [u8; 6],
+    ) -> Result<Measurement, Error<I::Error>> {
+        let now_us = self.read_clock();
+        let retry = self.config.retry;
+        self.measure_from_with_retry(Sensor::Configured, delay, retry, now_us, Some(buffer))
+            .await
+    }
+
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, returning the sensor's 6-byte
+    /// response untouched.
+    ///
+    /// This is for gateways that forward the sensor's frame and convert it
+    /// elsewhere. The frame holds the raw temperature and humidity readings,
+    /// each followed by its CRC, so calibration offsets are not applied.
+    /// With `validate`, the CRCs are checked before the frame is returned;
+    /// without, a frame that fails the check is returned anyway once any
+    /// retries in [`Config::retry`] are used up, though the failures are
+    /// still counted in the driver's statistics.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let frame = sensor.measure_raw(&mut delay, true).await?;
+    /// assert_eq!(frame, [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::read_into()`], except that CRC failures are only
+    /// returned with `validate`.
+    pub async fn measure_raw(
+        &mut self,
+        delay: impl DelayNs,
+        validate: bool,
+    ) -> Result<[u8; 6], Error<I::Error>> {
+        let mut frame = [0; 6];
+        match self.read_into(delay, &mut frame).await {
+            Ok(_) => Ok(frame),
+            Err(Error::CrcValidationFailed { .. }) if !validate => Ok(frame),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Measure temperature and humidity with the settings provided upon
+    /// construction of the sensor struct, lending the I2C bus back to the
+    /// caller while the sensor is busy.
+    ///
+    /// After the measurement command is sent, `window` is called with the
+    /// I2C interface and the length of the measurement delay in
+    /// microseconds, so that other devices on the bus can be used during
+    /// the delay. This is particularly useful with the heater reading modes,
+    /// which take up to 1.1 seconds. Once `window` returns, the driver waits
+    /// for whatever is left of the delay, as measured by `clock`, and reads
+    /// the measurement.
+    ///
+    /// `window` must not communicate with this sensor, which will reject any
+    /// commands until the measurement is complete.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # use core::cell::Cell;
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::write(0x40, vec![0xF3]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// #   let now_us = Cell::new(0);
+    /// #   let clock = || now_us.get();
+    /// use embedded_hal_async::i2c::I2c;
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// let measurement = sensor
+    ///     .measure_with_bus_window(&mut delay, &clock, async |bus, window_us| {
+    ///         // Talk to another device on the bus during the 6.9ms delay.
+    ///         bus.write(0x40, &[0xF3]).await.unwrap();
+    ///     })
+    ///     .await?;
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure_with_settings()`].
+    pub async fn measure_with_bus_window(
+        &mut self,
+        mut delay: impl DelayNs,
+        clock: &impl Clock,
+        window: impl AsyncFnOnce(&mut I, u32),
+    ) -> Result<Measurement, Error<I::Error>> {
+        let Config {
+            reading_mode,
+            delay_mode,
+            ..
+        } = self.config;
+        let us = self.config.delay_us(reading_mode, delay_mode);
+        self.finish_interrupted_measurement(&mut delay).await;
+        let now_us = self.note_time(clock);
+        let now_us = self.wait_for_rate_limit(&mut delay, Some(now_us)).await;
+        self.send_measurement_command(Sensor::Configured, reading_mode, now_us)
+            .await?;
+        let started_us = clock.now_us();
+        window(&mut self.i2c, us).await;
+        let elapsed_us = clock.now_us().saturating_sub(started_us);
+        let remaining_us = u64::from(us).saturating_sub(elapsed_us);
+        // Never more than `us`, so always fits.
+        delay.delay_us(remaining_us as u32).await;
+        self.read_measurement(Sensor::Configured).await
+    }
+
+    /// Send a single-byte command to the sensor.
+    ///
+    /// This is an escape hatch for commands the driver doesn't otherwise
+    /// support, such as undocumented or future commands. The driver doesn't
+    /// interpret the command, so waiting for the sensor to act on it and
+    /// reading any response with [`SHT4x::read_response()`] are up to you.
+    ///
+    /// The async driver does not track commands sent this way, so it won't
+    /// finish them if the future is dropped, as it does for measurements.
+    ///
+    /// ```rust
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// #   let expectations = [
+    /// #     Transaction::write(0x44, vec![0x89]),
+    /// #     Transaction::read(0x44, vec![0x01, 0x02, 0x17, 0x3, 0x4, 0x68]),
+    /// #   ];
+    /// #   let i2c = Mock::new(&expectations);
+    /// use sht4x_rjw::asynch::SHT4x;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// // Read the serial number by hand.
+    /// sensor.send_command(0x89).await?;
+    /// let mut response = [0; 6];
+    /// sensor.read_response(&mut response).await?;
+    /// assert_eq!(response[..2], [0x01, 0x02]);
+    /// #   sensor.destroy().done();
+    /// #   Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An error may be returned if there is a problem with the I2C interface.
+    pub async fn send_command(&mut self, command: u8) -> Result<(), Error<I::Error>> {
+        let result = self.i2c.write(self.address(), &[command]).await;
+        self.record(result)
+    }
+
+    /// Read a 6-byte response from the sensor into `response`, without
+    /// validating its CRCs.
+    ///
+    /// See [`SHT4x::send_command()`] for an example.
+    ///
+    /// # Errors
+    ///
+    /// An error may be returned if there is a problem with the I2C interface,
+    /// including the sensor not acknowledging the read because it is busy.
+    pub async fn read_response(&mut self, response: &mut [u8; 6]) -> Result<(), Error<I::Error>> {
+        let result = self.i2c.read(self.address(), response).await;
+        self.record(result)
+    }
+
+    /// Send the command to begin a measurement, without waiting for it to
+    /// complete.
+    ///
+    /// If the time `now_us` is known, a measurement from the config's sensor
+    /// that would break [`Config::rate_limit`] is refused.
+    async fn send_measurement_command(
+        &mut self,
+        sensor: Sensor,
+        reading_mode: ReadingMode,
+        now_us: Option<u64>,
+    ) -> Result<(), Error<I::Error>> {
+        self.check_heater_limits(sensor, reading_mode)?;
+        if sensor == Sensor::Configured
+            && let Some(now_us) = now_us
+        {
+            self.check_rate_limit(now_us)?;
+        }
+        // Mark the measurement as in flight before writing, as the command
+        // may reach the sensor even if this future is dropped mid-write.
+        self.in_flight = Some((reading_mode, sensor));
+        let result = self
+            .i2c
+            .write(sensor.address(&self.config), &[reading_mode.command_byte()])
+            .await
+            .map_err(|e| classify_nack(e, Error::NotPresent));
+        if result.is_err() {
+            self.in_flight = None;
+            if sensor == Sensor::Configured {
+                self.latest.record_failure();
+            }
+        }
+        self.record(result)?;
+        // The heater state is only kept for the config's sensor.
+        if sensor != Sensor::Configured {
+            return Ok(());
+        }
+        self.heated_reading = matches!(reading_mode, ReadingMode::HighPrecisionWithHeater(..));
+        if let ReadingMode::HighPrecisionWithHeater(power, duration) = reading_mode {
+            self.heater_stats.record(power, duration);
+            if let Some(now_us) = self.clock_us {
+                self.heater_pulse = Some((now_us, duration));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read and validate the result of a measurement begun with
+    /// [`SHT4x::send_measurement_command()`].
+    async fn read_measurement(&mut self, sensor: Sensor) -> Result<Measurement, Error<I::Error>> {
+        let mut buffer = self.read_buffer;
+        let result = self.read_measurement_into(sensor, &mut buffer).await;
+        self.read_buffer = buffer;
+        result
+    }
+
+    /// As [`SHT4x::read_measurement()`], but reading into `buffer` rather
+    /// than the internal buffer.
+    async fn read_measurement_into(
+        &mut self,
+        sensor: Sensor,
+        buffer: &mut [u8; 6],
+    ) -> Result<Measurement, Error<I::Error>> {
+        let result = self.i2c.read(sensor.address(&self.config), buffer).await;
+        self.in_flight = None;
+        self.decode_measurement(sensor, result, buffer)
+    }
+
+    /// Read the result of a measurement begun with
+    /// [`SHT4x::send_measurement_command()`] into `buffer`, polling the
+    /// sensor until it stops responding with a NACK, as described for
+    /// [`DelayMode::Poll`].
+    async fn poll_measurement(
+        &mut self,
+        sensor: Sensor,
+        mut delay: impl DelayNs,
+        reading_mode: ReadingMode,
+        interval_us: u32,
+        buffer: &mut [u8; 6],
+    ) -> Result<Measurement, Error<I::Error>> {
+        let (start_us, end_us) = DelayMode::poll_window_us(reading_mode);
+        delay.delay_us(start_us).await;
+        let mut waited_us = start_us;
+        loop {
+            let result = self.i2c.read(sensor.address(&self.config), buffer).await;
+            match result {
+                Err(e) if is_nack(&e) && waited_us < end_us => {
+                    let wait_us = interval_us.clamp(1, end_us - waited_us);
+                    delay.delay_us(wait_us).await;
+                    waited_us += wait_us;
+                }
+                result => {
+                    self.in_flight = None;
+                    return self.decode_measurement(sensor, result, buffer);
+                }
+            }
+        }
+    }
+
+    /// Validate the bytes read into `buffer`, given the `result` of the
+    /// read, and apply the configured offsets.
+    fn decode_measurement(
+        &mut self,
+        sensor: Sensor,
+        result: Result<(), I::Error>,
+        buffer: &[u8; 6],
+    ) -> Result<Measurement, Error<I::Error>> {
+        let measurement = self.validate_measurement(sensor, result, buffer);
+        if sensor == Sensor::Configured {
+            self.latest.record(&measurement);
+        }
+        measurement
+    }
+
+    /// Check the result of reading a measurement and validate its CRCs.
+    fn validate_measurement(
+        &mut self,
+        sensor: Sensor,
+        result: Result<(), I::Error>,
+        buffer: &[u8; 6],
+    ) -> Result<Measurement, Error<I::Error>> {
+        self.record(result.map_err(|e| classify_nack(e, Error::NotReady)))?;
+
+        #[cfg(feature = "defmt")]
         defmt::debug!(
-            "Bytes from sensor {=u8:#02X}: {=[u8; 6]:#02X}",
-            self.address,
-            self.read_buffer
+            "Bytes from sensor {}: {=[u8; 6]:#02X}",
+            self.sensor_id_for(sensor),
+            buffer
+        );
+
+        let mut measurement = self
+            .record(Measurement::from_read_bytes(Unvalidated::new(
+                *buffer,
+                self.config.validate_crc,
+            )))?
+            .with_offsets(
+                self.config.temperature_offset_raw,
+                self.config.humidity_offset_raw,
+            )
+            .with_humidity_clamp(self.config.humidity_clamp);
+        if self.config.humidity_clamp == HumidityClamp::ErrorOnOutOfRange
+            && !measurement.humidity_in_physical_range()
+        {
+            return self.record(Err(Error::HumidityOutOfRange {
+                raw_humidity: measurement.raw_humidity_reading(),
+            }));
+        }
+        // A heated reading is expected to jump away from the ambient readings
+        // around it, so it is neither checked against nor kept for the steps,
+        // and nor is a reading from a sensor other than the config's.
+        let previous = if self.heated_reading || sensor != Sensor::Configured {
+            None
+        } else {
+            self.last_reading.replace(measurement)
+        };
+        if let Some(plausibility) = self.config.plausibility
+            && !plausibility.accepts(&measurement, previous.as_ref())
+        {
+            match plausibility.action {
+                PlausibilityAction::Error => {
+                    return self.record(Err(Error::ImplausibleReading {
+                        raw_temp: measurement.raw_temperature_reading(),
+                        raw_humidity: measurement.raw_humidity_reading(),
+                    }));
+                }
+                PlausibilityAction::Flag => measurement = measurement.flag_implausible(),
+            }
+        }
+        self.stats.record_measurement();
+        Ok(measurement)
+    }
+
+    /// Flatten the result of an operation run with a timeout, recording
+    /// [`Error::Timeout`] if it expired.
+    #[cfg(feature = "embassy-time")]
+    fn timed_out<T>(
+        &mut self,
+        result: Result<Result<T, Error<I::Error>>, embassy_time::TimeoutError>,
+    ) -> Result<T, Error<I::Error>> {
+        match result {
+            Ok(result) => result,
+            Err(embassy_time::TimeoutError) => self.record(Err(Error::Timeout)),
+        }
+    }
+
+    /// Convert any error into our [`Error`] type and record it in the
+    /// driver statistics.
+    fn record<T>(
+        &mut self,
+        result: Result<T, impl Into<Error<I::Error>>>,
+    ) -> Result<T, Error<I::Error>> {
+        result.map_err(|e| {
+            let e = e.into();
+            #[cfg(feature = "defmt")]
+            defmt::warn!("Error from sensor {}: {}", self.sensor_id(), e.kind());
+            self.stats.record_error(&e);
+            e
+        })
+    }
+
+    /// How long after `now_us` a new measurement from the config's sensor
+    /// has to start to keep to [`Config::rate_limit`], or zero if it
+    /// already would.
+    fn rate_limit_remaining_us(&self, now_us: u64) -> u32 {
+        let (Some(limit), Some(last_us)) = (self.config.rate_limit, self.rate_limited_at_us) else {
+            return 0;
+        };
+        let elapsed_us = now_us.saturating_sub(last_us);
+        // No more than min_interval_us, so fits in a u32.
+        u64::from(limit.min_interval_us).saturating_sub(elapsed_us) as u32
+    }
+
+    /// How long a measurement starting at `now_us` has to wait for
+    /// [`Config::rate_limit`], if the limit is to wait rather than refuse.
+    fn rate_limit_wait_us(&self, now_us: u64) -> u32 {
+        match self.config.rate_limit {
+            Some(limit) if limit.action == RateLimitAction::Wait => {
+                self.rate_limit_remaining_us(now_us)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Wait, if [`Config::rate_limit`] says to, until a measurement starting
+    /// at `now_us` would keep to the limit, returning the time after
+    /// waiting. Without the time there is nothing to wait for.
+    async fn wait_for_rate_limit(
+        &self,
+        delay: &mut impl DelayNs,
+        now_us: Option<u64>,
+    ) -> Option<u64> {
+        let now_us = now_us?;
+        let wait_us = self.rate_limit_wait_us(now_us);
+        if wait_us > 0 {
+            delay.delay_us(wait_us).await;
+        }
+        Some(now_us + u64::from(wait_us))
+    }
+
+    /// Refuse a measurement starting at `now_us` that would break
+    /// [`Config::rate_limit`], or otherwise note when it started.
+    fn check_rate_limit(&mut self, now_us: u64) -> Result<(), Error<I::Error>> {
+        if self.rate_limit_remaining_us(now_us) > 0 {
+            return self.record(Err(Error::TooSoon));
+        }
+        self.rate_limited_at_us = Some(now_us);
+        Ok(())
+    }
+
+    /// Send the command to begin a measurement from the config's sensor,
+    /// keeping to [`Config::rate_limit`] if the driver has a clock of its own.
+    async fn send_limited_command(
+        &mut self,
+        delay: &mut impl DelayNs,
+        reading_mode: ReadingMode,
+    ) -> Result<(), Error<I::Error>> {
+        let now_us = self.read_clock();
+        let now_us = self.wait_for_rate_limit(delay, now_us).await;
+        self.send_measurement_command(Sensor::Configured, reading_mode, now_us)
+            .await
+    }
+
+    /// Read the time from `clock`, recording it for the heater duty cycle
+    /// limit.
+    pub(crate) fn note_time(&mut self, clock: &impl Clock) -> u64 {
+        let now_us = clock.now_us();
+        self.clock_us = Some(now_us);
+        now_us
+    }
+
+    /// Read the time from the driver's own clock, if it has one, recording
+    /// it as for [`SHT4x::note_time()`].
+    fn read_clock(&mut self) -> Option<u64> {
+        let clock = self.clock?;
+        Some(self.note_time(&clock))
+    }
+
+    /// Fail if a heater command with `reading_mode` would break one of the
+    /// config's heater limits.
+    fn check_heater_limits(
+        &mut self,
+        sensor: Sensor,
+        reading_mode: ReadingMode,
+    ) -> Result<(), Error<I::Error>> {
+        // Nothing is known of the readings or heater pulses of other sensors.
+        let (latest, last_pulse) = match sensor {
+            Sensor::Configured => (self.latest.measurement.as_ref(), self.heater_pulse),
+            Sensor::At(_) => (None, None),
+        };
+        let result =
+            self.config
+                .check_heater_limits(reading_mode, latest, self.clock_us, last_pulse);
+        self.record(result.map_err(Error::HeaterLimitExceeded))
+    }
+
+    /// The sensor's seven-bit I2C address, from the config.
+    fn address(&self) -> SevenBitAddress {
+        self.config.address.value()
+    }
+
+    /// Identifies the sensor in log messages.
+    #[cfg(feature = "defmt")]
+    fn sensor_id(&self) -> SensorId {
+        self.sensor_id_for(Sensor::Configured)
+    }
+
+    /// Identifies `sensor` in log messages. Only the config's sensor has a
+    /// label.
+    #[cfg(feature = "defmt")]
+    fn sensor_id_for(&self, sensor: Sensor) -> SensorId {
+        SensorId {
+            address: sensor.address(&self.config),
+            label: match sensor {
+                Sensor::Configured => self.label,
+                Sensor::At(_) => None,
+            },
+        }
+    }
+
+    /// Wait for a measurement whose future was dropped to complete, so that
+    /// the sensor will accept the next command.
+    ///
+    /// The stale measurement is first read and discarded. If the sensor
+    /// rejects the read, it is still converting, so wait for the maximum
+    /// duration of the interrupted measurement.
+    async fn finish_interrupted_measurement(&mut self, delay: &mut impl DelayNs) {
+        let Some((reading_mode, sensor)) = self.in_flight else {
+            return;
+        };
+
+        #[cfg(feature = "defmt")]
+        defmt::warn!(
+            "Finishing interrupted {} measurement on sensor {}",
+            reading_mode,
+            self.sensor_id_for(sensor)
+        );
+
+        if self
+            .i2c
+            .read(sensor.address(&self.config), &mut self.read_buffer)
+            .await
+            .is_err()
+        {
+            delay
+                .delay_us(DelayMode::Maximum.us_for_reading_mode(reading_mode))
+                .await;
+        }
+        self.in_flight = None;
+    }
+}
+
+/// Interleaved measurements from two sensors, for readings at twice the
+/// rate a single sensor can provide.
+///
+/// A sensor can't be asked for a new measurement until the previous one is
+/// complete, so a single sensor can provide at most one reading each
+/// measurement duration (6.9ms typically for high-precision readings). With
+/// two sensors (at different I2C addresses, so they can share a bus), the
+/// second sensor can be started halfway through the first sensor's
+/// measurement. `PingPong` handles this offset scheduling, and each call
+/// to [`PingPong::next_measurement()`] returns the next completed
+/// measurement, alternating between the two sensors, half a measurement
+/// duration after the previous one.
+///
+/// Both sensors use the same reading and delay modes, given when the helper
+/// is constructed, rather than the settings in their own configs. Each
+/// config's [`Config::delay_table`] and [`Config::delay_margin_percent`]
+/// still apply to the delays, and the longer of the two sensors' delays is
+/// used. Readings
+/// are only evenly spaced if you call `next_measurement()` again promptly.
+/// If you take longer than half a measurement duration between calls, the
+/// sensors will have finished, and you will get the next reading sooner.
+///
+/// If an error occurs, the next call waits for a full measurement duration,
+/// so that both sensors are idle, before starting the sequence again.
+///
+/// ```rust
+/// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+/// # #[pollster::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// #   let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+/// #   let first = Mock::new(&[
+/// #     Transaction::write(0x44, vec![0xFD]),
+/// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+/// #     Transaction::write(0x44, vec![0xFD]),
+/// #   ]);
+/// #   let second = Mock::new(&[
+/// #     Transaction::write(0x45, vec![0xFD]),
+/// #     Transaction::read(0x45, vec![0x12, 0x35, 0x06, 0x56, 0x76, 0x62]),
+/// #     Transaction::write(0x45, vec![0xFD]),
+/// #   ]);
+/// use sht4x_rjw::asynch::{PingPong, SHT4x};
+/// use sht4x_rjw::common::{Address, Config, DelayMode, PingPongSensor, ReadingMode};
+///
+/// let first = SHT4x::new(first, Default::default());
+/// let second = SHT4x::new(second, Config {
+///     address: Address::Alt0x45,
+///     ..Default::default()
+/// });
+///
+/// let mut sensors = PingPong::new(
+///     first,
+///     second,
+///     ReadingMode::HighPrecision,
+///     DelayMode::Typical,
+/// );
+/// let (sensor, _measurement) = sensors.next_measurement(&mut delay).await?;
+/// assert_eq!(sensor, PingPongSensor::First);
+/// let (sensor, _measurement) = sensors.next_measurement(&mut delay).await?;
+/// assert_eq!(sensor, PingPongSensor::Second);
+/// #   let (first, second) = sensors.destroy();
+/// #   first.destroy().done();
+/// #   second.destroy().done();
+/// #   Ok(())
+/// # }
+/// ```
+pub struct PingPong<I: I2c> {
+    first: SHT4x<I>,
+    second: SHT4x<I>,
+    reading_mode: ReadingMode,
+    delay_mode: DelayMode,
+    state: PingPongState,
+}
+
+impl<I: I2c> PingPong<I> {
+    /// Interleave measurements from two sensors, using the given reading
+    /// and delay modes for both.
+    ///
+    /// The sensors must be at different I2C addresses if they share a bus.
+    pub const fn new(
+        first: SHT4x<I>,
+        second: SHT4x<I>,
+        reading_mode: ReadingMode,
+        delay_mode: DelayMode,
+    ) -> Self {
+        Self {
+            first,
+            second,
+            reading_mode,
+            delay_mode,
+            state: PingPongState::Idle,
+        }
+    }
+
+    /// Drop the helper and return the two sensor drivers, in the order they
+    /// were passed to [`PingPong::new()`].
+    ///
+    /// Note that both sensors may still be busy with a measurement.
+    pub fn destroy(self) -> (SHT4x<I>, SHT4x<I>) {
+        (self.first, self.second)
+    }
+
+    /// Time in microseconds between consecutive readings, which is half of
+    /// the measurement duration for the reading and delay modes in use.
+    pub fn interval_us(&self) -> u32 {
+        self.measurement_us().div_ceil(2)
+    }
+
+    /// Time in microseconds for a measurement to complete on both sensors.
+    fn measurement_us(&self) -> u32 {
+        let delay_us =
+            |sensor: &SHT4x<I>| sensor.config.delay_us(self.reading_mode, self.delay_mode);
+        delay_us(&self.first).max(delay_us(&self.second))
+    }
+
+    /// Wait for and return the next measurement, along with which of the
+    /// two sensors it came from.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure_with_settings()`].
+    pub async fn next_measurement(
+        &mut self,
+        mut delay: impl DelayNs,
+    ) -> Result<(PingPongSensor, Measurement), Error<I::Error>> {
+        let interval = self.interval_us();
+        let next = match self.state {
+            PingPongState::Running { next } => next,
+            state => {
+                if let PingPongState::Restart = state {
+                    delay.delay_us(self.measurement_us()).await;
+                }
+                self.state = PingPongState::Restart;
+                self.first
+                    .send_limited_command(&mut delay, self.reading_mode)
+                    .await?;
+                delay.delay_us(interval).await;
+                self.second
+                    .send_limited_command(&mut delay, self.reading_mode)
+                    .await?;
+                PingPongSensor::First
+            }
+        };
+
+        // Leave the state as Restart until the sequence has completed, in
+        // case of errors or this future being dropped.
+        self.state = PingPongState::Restart;
+        delay.delay_us(interval).await;
+        let sensor = match next {
+            PingPongSensor::First => &mut self.first,
+            PingPongSensor::Second => &mut self.second,
+        };
+        let measurement = sensor.read_measurement(Sensor::Configured).await?;
+        sensor
+            .send_limited_command(&mut delay, self.reading_mode)
+            .await?;
+        self.state = PingPongState::Running { next: next.other() };
+        Ok((next, measurement))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Waker};
+    use std::vec;
+
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_async::delay::DelayNs;
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+    use super::SHT4x;
+    use crate::common::{Address, Config, DelayMode, RateLimit, RateLimitAction, ReadingMode};
+    use crate::error::{ConfigError, Error};
+
+    /// A delay that never completes, to stand in for a measurement future
+    /// losing a race against some other future.
+    struct NeverDelay;
+
+    impl DelayNs for NeverDelay {
+        async fn delay_ns(&mut self, _ns: u32) {
+            core::future::pending().await
+        }
+    }
+
+    /// Poll the future once and then drop it.
+    fn cancel_after_first_poll(future: impl Future) {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+    }
+
+    const MEASUREMENT: [u8; 6] = [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D];
+
+    #[test]
+    fn measurement_after_cancelled_measurement_still_busy() {
+        let expectations = [
+            // Cancelled measurement.
+            Transaction::write(0x44, vec![0xFD]),
+            // Sensor still busy with the cancelled measurement.
+            Transaction::read(0x44, vec![0; 6])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            // Successful measurement after waiting.
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+
+        cancel_after_first_poll(sensor.measure(NeverDelay));
+        let measurement = pollster::block_on(sensor.measure(NoopDelay::new()));
+        assert_eq!(measurement.unwrap().raw_temperature_reading(), 0x1234);
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn measurement_after_cancelled_measurement_completed() {
+        let expectations = [
+            // Cancelled measurement.
+            Transaction::write(0x44, vec![0xFD]),
+            // Stale measurement drained.
+            Transaction::read(0x44, vec![0; 6]),
+            // Successful measurement.
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+
+        cancel_after_first_poll(sensor.measure(NeverDelay));
+        let measurement = pollster::block_on(sensor.measure(NoopDelay::new()));
+        assert_eq!(measurement.unwrap().raw_temperature_reading(), 0x1234);
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn recover_drains_and_resets_after_cancellation() {
+        let expectations = [
+            // Cancelled measurement.
+            Transaction::write(0x44, vec![0xFD]),
+            // Stale measurement drained, then the sensor is reset.
+            Transaction::read(0x44, vec![0; 6]),
+            Transaction::write(0x44, vec![0x94]),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+
+        cancel_after_first_poll(sensor.measure(NeverDelay));
+        assert!(sensor.has_interrupted_measurement());
+        pollster::block_on(sensor.recover(NoopDelay::new())).unwrap();
+        assert!(!sensor.has_interrupted_measurement());
+        // Nothing to recover from.
+        pollster::block_on(sensor.recover(NoopDelay::new())).unwrap();
+        sensor.destroy().done();
+    }
+
+    #[cfg(feature = "embassy-time")]
+    #[test]
+    fn timer_measurement_waits_on_embassy_time() {
+        use core::task::Poll;
+        use embassy_time::{Duration, MockDriver};
+
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        {
+            let mut future = pin!(sensor.measure_with_timer());
+            let mut cx = Context::from_waker(Waker::noop());
+            // The mock time driver is shared between tests, so don't assume
+            // how many steps the measurement takes.
+            let measurement = loop {
+                if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+                    break result;
+                }
+                MockDriver::get().advance(Duration::from_millis(1));
+            };
+            assert_eq!(measurement.unwrap().raw_temperature_reading(), 0x1234);
+        }
+        sensor.destroy().done();
+    }
+
+    #[cfg(feature = "embassy-time")]
+    #[test]
+    fn timeout_abandons_hung_measurement() {
+        use core::task::Poll;
+        use embassy_time::{Duration, MockDriver};
+
+        use crate::error::Sht4xErrorKind;
+
+        let expectations = [Transaction::write(0x44, vec![0xFD])];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        {
+            let mut future =
+                pin!(sensor.measure_with_timeout(NeverDelay, Duration::from_millis(10)));
+            let mut cx = Context::from_waker(Waker::noop());
+            assert!(future.as_mut().poll(&mut cx).is_pending());
+            MockDriver::get().advance(Duration::from_millis(10));
+            assert!(matches!(
+                future.as_mut().poll(&mut cx),
+                Poll::Ready(Err(Error::Timeout))
+            ));
+        }
+        assert_eq!(
+            sensor.dump_state().last_error,
+            Some(Sht4xErrorKind::Timeout)
         );
+        sensor.destroy().done();
+    }
+
+    fn nack() -> Transaction {
+        Transaction::read(0x44, vec![0; 6])
+            .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+    }
+
+    #[test]
+    fn discards_first_measurement_after_reset() {
+        let first = [0x12, 0x36, 0x55, 0x56, 0x76, 0x62];
+        let expectations = [
+            // Discarded, then kept.
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, first.to_vec()),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+            // Not discarded.
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+            // Discarded after a reset.
+            Transaction::write(0x44, vec![0x94]),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, first.to_vec()),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let config = Config {
+            discard_first_measurement: true,
+            ..Default::default()
+        };
+        let mut sensor = SHT4x::new(Mock::new(&expectations), config);
+        pollster::block_on(async {
+            for _ in 0..2 {
+                let measurement = sensor.measure(NoopDelay::new()).await.unwrap();
+                assert_eq!(measurement.raw_temperature_reading(), 0x1234);
+            }
+            sensor.soft_reset(NoopDelay::new()).await.unwrap();
+            let measurement = sensor.measure(NoopDelay::new()).await.unwrap();
+            assert_eq!(measurement.raw_temperature_reading(), 0x1234);
+        });
+        sensor.destroy().done();
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn out_of_range_humidity_follows_clamp_policy() {
+        use crate::common::HumidityClamp;
+
+        // A humidity reading of zero is -6%RH.
+        let frame = [0x12, 0x34, 0x37, 0x00, 0x00, 0x81];
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, frame.to_vec()),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, frame.to_vec()),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, frame.to_vec()),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        pollster::block_on(async {
+            let measurement = sensor.measure(NoopDelay::new()).await.unwrap();
+            assert_eq!(measurement.humidity(), 0.0);
+            let config = Config {
+                humidity_clamp: HumidityClamp::DontClamp,
+                ..Default::default()
+            };
+            sensor.set_config(config).unwrap();
+            let measurement = sensor.measure(NoopDelay::new()).await.unwrap();
+            assert_eq!(measurement.humidity(), -6.0);
+            let config = Config {
+                humidity_clamp: HumidityClamp::ErrorOnOutOfRange,
+                ..Default::default()
+            };
+            sensor.set_config(config).unwrap();
+            let result = sensor.measure(NoopDelay::new()).await;
+            assert!(matches!(
+                result,
+                Err(Error::HumidityOutOfRange { raw_humidity: 0 })
+            ));
+        });
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn heater_commands_follow_heater_limits() {
+        use crate::common::{HeaterDuration, HeaterPower};
+        use crate::error::HeaterLimit;
+        use core::cell::Cell;
+
+        let heater = ReadingMode::HighPrecisionWithHeater(HeaterPower::High, HeaterDuration::Short);
+        // 25°C.
+        let frame = [0x66, 0x66, 0x93, 0x80, 0x00, 0xA2];
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, frame.to_vec()),
+            Transaction::write(0x44, vec![0x32]),
+            Transaction::read(0x44, frame.to_vec()),
+            Transaction::write(0x44, vec![0x32]),
+            Transaction::read(0x44, frame.to_vec()),
+        ];
+        let config = Config {
+            reading_mode: heater,
+            heater_max_ambient_celsius: Some(20),
+            ..Default::default()
+        };
+        let mut sensor = SHT4x::new(Mock::new(&expectations), config);
+        let now_us = Cell::new(0);
+        let clock = || now_us.get();
+        pollster::block_on(async {
+            sensor
+                .measure_with_settings(
+                    NoopDelay::new(),
+                    ReadingMode::HighPrecision,
+                    DelayMode::Typical,
+                )
+                .await
+                .unwrap();
+            let result = sensor.measure(NoopDelay::new()).await;
+            assert!(matches!(
+                result,
+                Err(Error::HeaterLimitExceeded(HeaterLimit::AmbientTemperature))
+            ));
+
+            // A 100ms pulse at most 10% of the time, so once a second.
+            let config = Config {
+                reading_mode: heater,
+                heater_max_duty_cycle_percent: Some(10),
+                ..Default::default()
+            };
+            sensor.set_config(config).unwrap();
+            sensor
+                .measure_timestamped(NoopDelay::new(), &clock)
+                .await
+                .unwrap();
+            now_us.set(500_000);
+            let result = sensor.measure_timestamped(NoopDelay::new(), &clock).await;
+            assert!(matches!(
+                result,
+                Err(Error::HeaterLimitExceeded(HeaterLimit::DutyCycle))
+            ));
+            now_us.set(1_000_000);
+            sensor
+                .measure_timestamped(NoopDelay::new(), &clock)
+                .await
+                .unwrap();
+        });
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn implausible_readings_follow_plausibility_action() {
+        use crate::common::{Plausibility, PlausibilityAction};
+
+        // -32.6°C, then 25°C.
+        let cold = [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D];
+        let warm = [0x66, 0x66, 0x93, 0x80, 0x00, 0xA2];
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, cold.to_vec()),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, warm.to_vec()),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, warm.to_vec()),
+        ];
+        let config = Config {
+            plausibility: Some(Plausibility {
+                min_celsius: -20,
+                action: PlausibilityAction::Flag,
+                ..Plausibility::SENSOR_RANGE
+            }),
+            ..Default::default()
+        };
+        let mut sensor = SHT4x::new(Mock::new(&expectations), config);
+        pollster::block_on(async {
+            let measurement = sensor.measure(NoopDelay::new()).await.unwrap();
+            assert!(!measurement.is_plausible());
+
+            let config = Config {
+                plausibility: Some(Plausibility {
+                    max_celsius_step: Some(5),
+                    ..Plausibility::SENSOR_RANGE
+                }),
+                ..Default::default()
+            };
+            sensor.set_config(config).unwrap();
+            let result = sensor.measure(NoopDelay::new()).await;
+            assert!(matches!(
+                result,
+                Err(Error::ImplausibleReading {
+                    raw_temp: 0x6666,
+                    raw_humidity: 0x8000
+                })
+            ));
+            let measurement = sensor.measure(NoopDelay::new()).await.unwrap();
+            assert!(measurement.is_plausible());
+        });
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn raw_frame_is_only_validated_on_request() {
+        let corrupt = [0x12, 0x34, 0x00, 0x56, 0x78, 0x7D];
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, corrupt.to_vec()),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, corrupt.to_vec()),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        pollster::block_on(async {
+            let frame = sensor.measure_raw(NoopDelay::new(), false).await;
+            assert_eq!(frame.unwrap(), corrupt);
+            let frame = sensor.measure_raw(NoopDelay::new(), true).await;
+            assert!(matches!(frame, Err(Error::CrcValidationFailed { .. })));
+        });
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn polling_reads_once_ready() {
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            nack(),
+            nack(),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        let measurement = pollster::block_on(sensor.measure_with_settings(
+            NoopDelay::new(),
+            ReadingMode::HighPrecision,
+            DelayMode::Poll { interval_us: 500 },
+        ));
+        assert_eq!(measurement.unwrap().raw_temperature_reading(), 0x1234);
+        assert_eq!(sensor.dump_state().errors, 0);
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn polling_gives_up_at_maximum_time() {
+        // High precision polls from 5.5ms to 8.3ms: at 5.5ms, 6.5ms, 7.5ms
+        // and 8.3ms.
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            nack(),
+            nack(),
+            nack(),
+            nack(),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        let result = pollster::block_on(sensor.measure_with_settings(
+            NoopDelay::new(),
+            ReadingMode::HighPrecision,
+            DelayMode::Poll { interval_us: 1_000 },
+        ));
+        assert!(matches!(result, Err(Error::NotReady)));
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn nacks_distinguish_absent_from_busy_sensor() {
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            Transaction::write(0x44, vec![0xFD]),
+            nack(),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        pollster::block_on(async {
+            let result = sensor.measure(NoopDelay::new()).await;
+            assert!(matches!(result, Err(Error::NotPresent)));
+            let result = sensor.measure(NoopDelay::new()).await;
+            assert!(matches!(result, Err(Error::NotReady)));
+        });
+        assert_eq!(
+            sensor.dump_state().last_error,
+            Some(crate::error::Sht4xErrorKind::NotReady)
+        );
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn invalid_addresses_are_rejected_without_bus_traffic() {
+        let config = Config {
+            address: Address::Custom(0x7F),
+            ..Default::default()
+        };
+        let mut i2c = Mock::new(&[]);
+        let result = SHT4x::try_new(i2c.clone(), config);
+        assert!(matches!(result, Err(ConfigError::InvalidAddress(0x7F))));
+
+        let mut sensor = SHT4x::new(i2c.clone(), Default::default());
+        let result = pollster::block_on(sensor.measure_at(Address::Custom(0x7F), NoopDelay::new()));
+        assert!(matches!(
+            result,
+            Err(Error::InvalidConfig(ConfigError::InvalidAddress(0x7F)))
+        ));
+        assert_eq!(sensor.config().address, Address::Default0x44);
+        i2c.done();
+    }
+
+    #[test]
+    fn measurement_after_cancelled_measure_at_completed() {
+        let expectations = [
+            // Cancelled measurement from the other sensor.
+            Transaction::write(0x45, vec![0xFD]),
+            // Stale measurement drained from that sensor.
+            Transaction::read(0x45, vec![0; 6]),
+            // Successful measurement from the configured sensor.
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+
+        cancel_after_first_poll(sensor.measure_at(Address::Alt0x45, NeverDelay));
+        assert_eq!(sensor.config().address, Address::Default0x44);
+        let measurement = pollster::block_on(sensor.measure(NoopDelay::new()));
+        assert_eq!(measurement.unwrap().raw_temperature_reading(), 0x1234);
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn rate_limit_applies_to_bus_window() {
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let config = Config {
+            rate_limit: Some(RateLimit {
+                min_interval_us: 1_000_000,
+                action: RateLimitAction::Reject,
+            }),
+            ..Default::default()
+        };
+        let mut sensor = SHT4x::new(Mock::new(&expectations), config);
+        let clock = || 0;
+        pollster::block_on(sensor.measure_with_clock(NoopDelay::new(), &clock)).unwrap();
 
-        Measurement::from_read_bytes(Unvalidated::new(self.read_buffer))
+        let result = pollster::block_on(sensor.measure_with_bus_window(
+            NoopDelay::new(),
+            &clock,
+            async |_, _| {},
+        ));
+        assert!(matches!(result, Err(Error::TooSoon)));
+        assert_eq!(sensor.dump_state().errors, 1);
+        sensor.destroy().done();
     }
 }
@@ -0,0 +1,155 @@
+//! Persistence of calibration offsets and heater use.
+//!
+//! Calibration offsets (see [`Config::temperature_offset_raw`]) are usually
+//! determined once per device, and heater use (see [`HeaterStats`]) should
+//! be counted over the sensor's whole lifetime, so both need to survive a
+//! reset. Implement [`CalibrationStore`] for your flash or EEPROM backend,
+//! then use `load_calibration()` at startup and `store_calibration()` when
+//! the calibration changes (or periodically, to save heater use) on either
+//! driver. [`Calibration::to_bytes()`] and [`Calibration::from_bytes()`]
+//! provide a compact byte encoding for the store to write.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::Mock;
+//! # let i2c = Mock::new(&[]);
+//! use core::convert::Infallible;
+//! use sht4x_rjw::blocking::SHT4x;
+//! use sht4x_rjw::calibration::{Calibration, CalibrationStore};
+//! use sht4x_rjw::common::Config;
+//!
+//! /// Stand-in for a page of EEPROM.
+//! struct Eeprom([u8; Calibration::ENCODED_LEN]);
+//!
+//! impl CalibrationStore for Eeprom {
+//!     type Error = Infallible;
+//!
+//!     fn load_calibration(&mut self) -> Result<Option<Calibration>, Infallible> {
+//!         Ok(Calibration::from_bytes(&self.0))
+//!     }
+//!
+//!     fn store_calibration(&mut self, calibration: &Calibration) -> Result<(), Infallible> {
+//!         self.0 = calibration.to_bytes();
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut eeprom = Eeprom([0xFF; Calibration::ENCODED_LEN]);
+//! let mut sensor = SHT4x::new(i2c, Default::default());
+//! // Nothing stored yet (erased EEPROM).
+//! assert_eq!(sensor.load_calibration(&mut eeprom), Ok(false));
+//!
+//! let config = Config {
+//!     temperature_offset_raw: -750, // About -2°C.
+//!     ..sensor.config()
+//! };
+//! sensor.set_config(config).unwrap();
+//! sensor.store_calibration(&mut eeprom).unwrap();
+//! # let i2c = sensor.destroy();
+//!
+//! // After a reset...
+//! let mut sensor = SHT4x::new(i2c, Default::default());
+//! assert_eq!(sensor.load_calibration(&mut eeprom), Ok(true));
+//! assert_eq!(sensor.config().temperature_offset_raw, -750);
+//! # sensor.destroy().done();
+//! ```
+//!
+//! [`Config::temperature_offset_raw`]: crate::common::Config::temperature_offset_raw
+use crate::common::HeaterStats;
+
+/// Calibration offsets and heater use, as persisted by a
+/// [`CalibrationStore`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Calibration {
+    /// Offset added to raw temperature readings, in raw sensor ticks.
+    pub temperature_offset_raw: i16,
+    /// Offset added to raw humidity readings, in raw sensor ticks.
+    pub humidity_offset_raw: i16,
+    /// Heater use over the sensor's lifetime.
+    pub heater_stats: HeaterStats,
+}
+
+/// Version byte at the start of the encoding, so that the format can be
+/// changed without misreading old data.
+const FORMAT_VERSION: u8 = 1;
+
+impl Calibration {
+    /// Length of the encoding produced by [`Calibration::to_bytes()`].
+    pub const ENCODED_LEN: usize = 25;
+
+    /// Encode the calibration as bytes, for storage.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let stats = &self.heater_stats;
+        let mut bytes = [0; Self::ENCODED_LEN];
+        bytes[0] = FORMAT_VERSION;
+        bytes[1..3].copy_from_slice(&self.temperature_offset_raw.to_le_bytes());
+        bytes[3..5].copy_from_slice(&self.humidity_offset_raw.to_le_bytes());
+        bytes[5..13].copy_from_slice(&stats.on_time_ms.to_le_bytes());
+        bytes[13..17].copy_from_slice(&stats.high_power_pulses.to_le_bytes());
+        bytes[17..21].copy_from_slice(&stats.medium_power_pulses.to_le_bytes());
+        bytes[21..25].copy_from_slice(&stats.low_power_pulses.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a calibration encoded by [`Calibration::to_bytes()`].
+    ///
+    /// Returns `None` if the bytes were not produced by `to_bytes()` (for
+    /// instance, if they are erased flash or EEPROM).
+    pub fn from_bytes(bytes: &[u8; Self::ENCODED_LEN]) -> Option<Self> {
+        if bytes[0] != FORMAT_VERSION {
+            return None;
+        }
+        let u32_at =
+            |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        let mut on_time = [0; 8];
+        on_time.copy_from_slice(&bytes[5..13]);
+        Some(Self {
+            temperature_offset_raw: i16::from_le_bytes([bytes[1], bytes[2]]),
+            humidity_offset_raw: i16::from_le_bytes([bytes[3], bytes[4]]),
+            heater_stats: HeaterStats {
+                on_time_ms: u64::from_le_bytes(on_time),
+                high_power_pulses: u32_at(13),
+                medium_power_pulses: u32_at(17),
+                low_power_pulses: u32_at(21),
+            },
+        })
+    }
+}
+
+/// Persistent storage for a [`Calibration`], such as flash or EEPROM.
+pub trait CalibrationStore {
+    /// Error returned by the storage backend.
+    type Error;
+
+    /// Load the stored calibration, or `None` if none has been stored.
+    fn load_calibration(&mut self) -> Result<Option<Calibration>, Self::Error>;
+
+    /// Store `calibration`, replacing any previously stored.
+    fn store_calibration(&mut self, calibration: &Calibration) -> Result<(), Self::Error>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::Calibration;
+    use crate::common::HeaterStats;
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let calibration = Calibration {
+            temperature_offset_raw: -1234,
+            humidity_offset_raw: 567,
+            heater_stats: HeaterStats {
+                on_time_ms: 0x0102_0304_0506,
+                high_power_pulses: 1,
+                medium_power_pulses: 70_000,
+                low_power_pulses: u32::MAX,
+            },
+        };
+        let bytes = calibration.to_bytes();
+        assert_eq!(Calibration::from_bytes(&bytes), Some(calibration));
+        assert_eq!(
+            Calibration::from_bytes(&[0; Calibration::ENCODED_LEN]),
+            None
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Time source for driver features that depend on elapsed time.
+//!
+//! The SHT4x drivers don't otherwise need to know the time, and the
+//! [`embedded-hal`] traits don't provide a clock, so methods that need to
+//! know how long ago something happened (such as
+//! [`SHT4x::measure_with_clock()`]) take an implementation of [`Clock`].
+//! A driver can also be given a clock of its own with
+//! [`SHT4x::set_clock()`], so that `measure()` knows the time too.
+//!
+//! `Clock` is implemented for any `Fn() -> u64` closure returning the current
+//! time in microseconds, so you can adapt whatever time source your platform
+//! provides without implementing the trait yourself. For example, with
+//! [`embassy-time`]:
+//!
+//! ```ignore
+//! let clock = || embassy_time::Instant::now().as_micros();
+//! let measurement = sensor.measure_with_clock(&mut delay, &clock)?;
+//! ```
+//!
+//! [`embedded-hal`]: https://docs.rs/embedded-hal/latest/embedded_hal/
+//! [`embassy-time`]: https://docs.rs/embassy-time/latest/embassy_time/
+//! [`SHT4x::measure_with_clock()`]: crate::blocking::SHT4x::measure_with_clock
+//! [`SHT4x::set_clock()`]: crate::blocking::SHT4x::set_clock
+
+/// A monotonic source of the current time, in microseconds.
+pub trait Clock {
+    /// The current time in microseconds since some arbitrary fixed point,
+    /// such as system start.
+    ///
+    /// The value must never decrease.
+    fn now_us(&self) -> u64;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> u64,
+{
+    fn now_us(&self) -> u64 {
+        self()
+    }
+}
@@ -0,0 +1,162 @@
+//! Classification of measurements by thermal comfort.
+//!
+//! Smart-home displays and controllers often want a friendly summary ("too
+//! dry", "comfortable") rather than raw numbers. A [`ComfortEnvelope`] gives
+//! the comfortable ranges of temperature and humidity, and
+//! [`ComfortEnvelope::classify()`] places a measurement inside or around it
+//! as a [`Comfort`] value.
+//!
+//! The default envelope is a simplification of the ASHRAE Standard 55
+//! comfort zone for typical indoor clothing and activity: 20°C to 26°C,
+//! and 30%RH to 60%RH. Adjust it to suit your climate and occupants.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # use sht4x_rjw::blocking::SHT4x;
+//! # fn main() -> anyhow::Result<()> {
+//! # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+//! # ];
+//! # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+//! use sht4x_rjw::comfort::{Comfort, ComfortEnvelope};
+//!
+//! // 25°C and 56.5%RH.
+//! let measurement = sensor.measure(&mut delay)?;
+//! assert_eq!(ComfortEnvelope::default().classify(&measurement), Comfort::Comfortable);
+//!
+//! let drier = ComfortEnvelope {
+//!     max_percent_rh: 50.0,
+//!     ..Default::default()
+//! };
+//! assert_eq!(drier.classify(&measurement), Comfort::TooHumid);
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+use crate::common::Measurement;
+
+/// Where a measurement lies relative to a [`ComfortEnvelope`].
+///
+/// Temperature and humidity are classified separately, so a measurement
+/// that is both too cold and too humid is [`Comfort::TooHumidCold`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Comfort {
+    /// Below both the temperature and humidity ranges.
+    TooDryCold,
+    /// Below the temperature range, with comfortable humidity.
+    TooCold,
+    /// Below the temperature range and above the humidity range.
+    TooHumidCold,
+    /// Below the humidity range, with comfortable temperature.
+    TooDry,
+    /// Within both ranges.
+    Comfortable,
+    /// Above the humidity range, with comfortable temperature.
+    TooHumid,
+    /// Above the temperature range and below the humidity range.
+    TooDryWarm,
+    /// Above the temperature range, with comfortable humidity.
+    TooWarm,
+    /// Above both the temperature and humidity ranges.
+    TooHumidWarm,
+}
+
+impl Comfort {
+    /// Whether the measurement was within the envelope.
+    pub fn is_comfortable(&self) -> bool {
+        matches!(self, Comfort::Comfortable)
+    }
+}
+
+/// Comfortable ranges of temperature and relative humidity, each inclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ComfortEnvelope {
+    /// Lowest comfortable temperature, in °C.
+    pub min_celsius: f32,
+    /// Highest comfortable temperature, in °C.
+    pub max_celsius: f32,
+    /// Lowest comfortable relative humidity, in %RH.
+    pub min_percent_rh: f32,
+    /// Highest comfortable relative humidity, in %RH.
+    pub max_percent_rh: f32,
+}
+
+impl Default for ComfortEnvelope {
+    /// 20°C to 26°C and 30%RH to 60%RH.
+    fn default() -> Self {
+        Self {
+            min_celsius: 20.0,
+            max_celsius: 26.0,
+            min_percent_rh: 30.0,
+            max_percent_rh: 60.0,
+        }
+    }
+}
+
+impl ComfortEnvelope {
+    /// Classify `measurement` against this envelope.
+    pub fn classify(&self, measurement: &Measurement) -> Comfort {
+        use core::cmp::Ordering::{Equal, Greater, Less};
+
+        let position = |value: f32, min: f32, max: f32| {
+            if value < min {
+                Less
+            } else if value > max {
+                Greater
+            } else {
+                Equal
+            }
+        };
+        let temperature = position(measurement.celsius(), self.min_celsius, self.max_celsius);
+        let humidity = position(
+            measurement.humidity(),
+            self.min_percent_rh,
+            self.max_percent_rh,
+        );
+        match (temperature, humidity) {
+            (Less, Less) => Comfort::TooDryCold,
+            (Less, Equal) => Comfort::TooCold,
+            (Less, Greater) => Comfort::TooHumidCold,
+            (Equal, Less) => Comfort::TooDry,
+            (Equal, Equal) => Comfort::Comfortable,
+            (Equal, Greater) => Comfort::TooHumid,
+            (Greater, Less) => Comfort::TooDryWarm,
+            (Greater, Equal) => Comfort::TooWarm,
+            (Greater, Greater) => Comfort::TooHumidWarm,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Comfort, ComfortEnvelope};
+    use crate::common::Measurement;
+
+    #[test]
+    fn classifies_around_envelope() {
+        let envelope = ComfortEnvelope::default();
+        let cases = [
+            (15.0, 20.0, Comfort::TooDryCold),
+            (15.0, 45.0, Comfort::TooCold),
+            (15.0, 80.0, Comfort::TooHumidCold),
+            (22.0, 20.0, Comfort::TooDry),
+            (22.0, 45.0, Comfort::Comfortable),
+            (22.0, 80.0, Comfort::TooHumid),
+            (30.0, 20.0, Comfort::TooDryWarm),
+            (30.0, 45.0, Comfort::TooWarm),
+            (30.0, 80.0, Comfort::TooHumidWarm),
+        ];
+        for (celsius, percent_rh, expected) in cases {
+            let measurement = Measurement::from_celsius_and_percent_rh(celsius, percent_rh);
+            assert_eq!(
+                envelope.classify(&measurement),
+                expected,
+                "{celsius}°C {percent_rh}%RH"
+            );
+        }
+    }
+}
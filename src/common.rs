@@ -2,14 +2,21 @@
 #[cfg(feature = "fixed")]
 use fixed::types::I16F16;
 
-use crate::error::{CrcFailureReason, Error};
+use crate::error::{
+    ConfigError, CrcFailureReason, Error, HeaterLimit, Sht4xErrorKind, UnknownCommand,
+};
+use crate::retry::{Backoff, RetryConfig};
 
 pub(crate) const READ_SERIAL_NUMBER_COMMAND: u8 = 0x89;
 pub(crate) const SOFT_RESET_COMMAND: u8 = 0x94;
 
 /// Internal wrapper around the 6 bytes read from the sensor, so that the
-/// 4 data bytes may only be accessed after passing CRC validation.
-pub(crate) struct Unvalidated([u8; 6]);
+/// 4 data bytes may only be accessed after passing CRC validation (unless
+/// validation is disabled by [`Config::validate_crc`]).
+pub(crate) struct Unvalidated {
+    bytes: [u8; 6],
+    validate: bool,
+}
 
 /// Wrap the checking of a CRC, and logging and returning any error
 macro_rules! check_crc {
@@ -31,13 +38,14 @@ macro_rules! check_crc {
 }
 
 impl Unvalidated {
-    pub(crate) fn new(bytes: [u8; 6]) -> Self {
-        Self(bytes)
+    pub(crate) fn new(bytes: [u8; 6], validate: bool) -> Self {
+        Self { bytes, validate }
     }
 
     /// Return the data bytes from the sensor if the CRC for each pair
     /// is valid, otherwise return an error with the appropriate description
-    /// of which bytes failed to validate.
+    /// of which bytes failed to validate. If validation is disabled, the
+    /// data bytes are returned whatever their CRCs.
     ///
     /// If we name the bytes read from the sensor `s0` through `s5`, the
     /// bytes returned from this method are `[s0, s1, s3, s4]`, as bytes
@@ -55,7 +63,10 @@ impl Unvalidated {
     where
         I: embedded_hal::i2c::Error,
     {
-        let [d0, d1, c0, d2, d3, c1] = self.0;
+        let [d0, d1, c0, d2, d3, c1] = self.bytes;
+        if !self.validate {
+            return Ok([d0, d1, d2, d3]);
+        }
         check_crc!(data: [d0, d1], received_crc: c0, failure_meaning: first_byte_pair_meaning);
         check_crc!(data: [d2, d3], received_crc: c1, failure_meaning: second_byte_pair_meaning);
         Ok([d0, d1, d2, d3])
@@ -71,8 +82,9 @@ impl Unvalidated {
 /// valid across the full −40°C to 125°C temperature range.
 ///
 /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeaterPower {
     /// 200mW nominal
     ///
@@ -102,8 +114,9 @@ pub enum HeaterPower {
 /// information.
 ///
 /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeaterDuration {
     /// 1 second
     Long,
@@ -111,6 +124,100 @@ pub enum HeaterDuration {
     Short,
 }
 
+impl HeaterDuration {
+    /// Nominal length of the heater pulse in milliseconds.
+    pub(crate) fn nominal_ms(&self) -> u32 {
+        match self {
+            HeaterDuration::Long => 1_000,
+            HeaterDuration::Short => 100,
+        }
+    }
+}
+
+/// Cumulative record of heater use by a driver instance.
+///
+/// The driver records a heater pulse each time a heater measurement command
+/// is successfully sent to the sensor, as the sensor starts heating as soon
+/// as it receives the command (whether or not the measurement is later read
+/// back). The on-time is calculated from the nominal pulse duration, so may
+/// differ from the true on-time by the ±10% tolerance given in section 3.2
+/// of the [datasheet].
+///
+/// The counts start from zero when the driver is constructed and are not
+/// stored on the sensor, so they only cover heater use through this driver
+/// instance. If you need to track heater wear across reboots you will need
+/// to persist these figures yourself.
+///
+/// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HeaterStats {
+    /// Total nominal heater on-time in milliseconds.
+    pub on_time_ms: u64,
+    /// Number of heater pulses at [`HeaterPower::High`].
+    pub high_power_pulses: u32,
+    /// Number of heater pulses at [`HeaterPower::Medium`].
+    pub medium_power_pulses: u32,
+    /// Number of heater pulses at [`HeaterPower::Low`].
+    pub low_power_pulses: u32,
+}
+
+impl HeaterStats {
+    /// Maximum heater duty cycle recommended by Sensirion, in percent.
+    ///
+    /// See section 4.9 of the [datasheet].
+    ///
+    /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+    pub const MAX_DUTY_CYCLE_PERCENT: u64 = 10;
+
+    /// Highest ambient temperature at which Sensirion recommend using the
+    /// heater, in degrees Celsius.
+    ///
+    /// See section 4.9 of the [datasheet].
+    ///
+    /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+    pub const MAX_AMBIENT_CELSIUS: i8 = 65;
+
+    /// No recorded heater use, as the `const` equivalent of `default()`.
+    pub(crate) const fn new() -> Self {
+        Self {
+            on_time_ms: 0,
+            high_power_pulses: 0,
+            medium_power_pulses: 0,
+            low_power_pulses: 0,
+        }
+    }
+
+    /// Total number of heater pulses at any power level.
+    pub fn total_pulses(&self) -> u32 {
+        self.high_power_pulses
+            .saturating_add(self.medium_power_pulses)
+            .saturating_add(self.low_power_pulses)
+    }
+
+    /// Estimated heater on-time remaining before the 10% duty cycle limit
+    /// is reached, given that the sensor has been in service for
+    /// `lifetime_ms` milliseconds.
+    ///
+    /// The driver has no notion of time, so you must supply the sensor's
+    /// time in service yourself. Zero is returned if the heater has already
+    /// been used beyond its duty budget.
+    pub fn remaining_duty_budget_ms(&self, lifetime_ms: u64) -> u64 {
+        (lifetime_ms / 100 * Self::MAX_DUTY_CYCLE_PERCENT).saturating_sub(self.on_time_ms)
+    }
+
+    /// Record a single heater pulse.
+    pub(crate) fn record(&mut self, power: HeaterPower, duration: HeaterDuration) {
+        self.on_time_ms = self.on_time_ms.saturating_add(duration.nominal_ms().into());
+        let pulses = match power {
+            HeaterPower::High => &mut self.high_power_pulses,
+            HeaterPower::Medium => &mut self.medium_power_pulses,
+            HeaterPower::Low => &mut self.low_power_pulses,
+        };
+        *pulses = pulses.saturating_add(1);
+    }
+}
+
 /// Level of precision with which to read the sensor.
 ///
 /// "Precision" or "accuracy" here refer to the repeatability of the measurement,
@@ -139,8 +246,9 @@ pub enum HeaterDuration {
 /// heater, as well as section 3 for electrical and timing information.
 ///
 /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReadingMode {
     /// High repeatability: 3σ of 0.04°C and 0.08%RH.
     HighPrecision,
@@ -153,13 +261,68 @@ pub enum ReadingMode {
 }
 
 impl ReadingMode {
+    /// Every reading mode, in the order used by [`ReadingMode::COMMAND_BYTES`],
+    /// [`DelayMode::TYPICAL_US`], [`DelayMode::MAXIMUM_US`] and
+    /// [`Config::delay_table`].
+    pub const ALL: [ReadingMode; 9] = [
+        ReadingMode::HighPrecision,
+        ReadingMode::MediumPrecision,
+        ReadingMode::LowPrecision,
+        ReadingMode::HighPrecisionWithHeater(HeaterPower::High, HeaterDuration::Long),
+        ReadingMode::HighPrecisionWithHeater(HeaterPower::High, HeaterDuration::Short),
+        ReadingMode::HighPrecisionWithHeater(HeaterPower::Medium, HeaterDuration::Long),
+        ReadingMode::HighPrecisionWithHeater(HeaterPower::Medium, HeaterDuration::Short),
+        ReadingMode::HighPrecisionWithHeater(HeaterPower::Low, HeaterDuration::Long),
+        ReadingMode::HighPrecisionWithHeater(HeaterPower::Low, HeaterDuration::Short),
+    ];
+
+    /// The command byte of each of [`ReadingMode::ALL`].
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::ReadingMode;
+    /// assert_eq!(ReadingMode::COMMAND_BYTES[0], 0xFD);
+    /// ```
+    pub const COMMAND_BYTES: [u8; 9] = {
+        let mut bytes = [0; 9];
+        let mut i = 0;
+        while i < bytes.len() {
+            bytes[i] = Self::ALL[i].command_byte();
+            i += 1;
+        }
+        bytes
+    };
+
+    /// Position of the reading mode in [`ReadingMode::ALL`].
+    pub(crate) const fn index(&self) -> usize {
+        use HeaterDuration::{Long, Short};
+        use HeaterPower::{High, Low, Medium};
+        match self {
+            ReadingMode::HighPrecision => 0,
+            ReadingMode::MediumPrecision => 1,
+            ReadingMode::LowPrecision => 2,
+            ReadingMode::HighPrecisionWithHeater(High, Long) => 3,
+            ReadingMode::HighPrecisionWithHeater(High, Short) => 4,
+            ReadingMode::HighPrecisionWithHeater(Medium, Long) => 5,
+            ReadingMode::HighPrecisionWithHeater(Medium, Short) => 6,
+            ReadingMode::HighPrecisionWithHeater(Low, Long) => 7,
+            ReadingMode::HighPrecisionWithHeater(Low, Short) => 8,
+        }
+    }
+
     /// I2C command byte for the given reading mode.
     ///
     /// The hexadecimal values of all commands are listed in section 4.5
-    /// of the [datasheet].
+    /// of the [datasheet]. This is a `const fn`, so command sequences can be
+    /// built at compile time, for example for DMA descriptors:
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::ReadingMode;
+    /// const COMMAND: [u8; 1] = [ReadingMode::LowPrecision.command_byte()];
+    /// assert_eq!(COMMAND, [0xE0]);
+    /// ```
     ///
     /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
-    pub(crate) fn command_byte(&self) -> u8 {
+    pub const fn command_byte(&self) -> u8 {
         match self {
             ReadingMode::HighPrecision => 0xFD,
             ReadingMode::MediumPrecision => 0xF6,
@@ -174,6 +337,289 @@ impl ReadingMode {
             },
         }
     }
+
+    /// The most precise (non-heater) reading mode that completes within
+    /// `max_us` microseconds with the given delay mode.
+    ///
+    /// This allows schedulers with a varying amount of time available to
+    /// pick a reading mode programmatically. `None` is returned if even
+    /// a low-precision reading would take longer than `max_us`.
+    ///
+    /// Only the delay between sending the command and reading the result is
+    /// considered, not the time taken by the I2C transfers themselves, so
+    /// you should leave some slack in your budget.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::{DelayMode, ReadingMode};
+    /// let mode = ReadingMode::for_latency_budget(5_000, DelayMode::Typical);
+    /// assert!(matches!(mode, Some(ReadingMode::MediumPrecision)));
+    /// ```
+    pub fn for_latency_budget(max_us: u32, delay_mode: DelayMode) -> Option<ReadingMode> {
+        [
+            ReadingMode::HighPrecision,
+            ReadingMode::MediumPrecision,
+            ReadingMode::LowPrecision,
+        ]
+        .into_iter()
+        .find(|&mode| delay_mode.us_for_reading_mode(mode) <= max_us)
+    }
+
+    /// Time from sending the command for a measurement in this mode until
+    /// it can be read, according to `delay_mode`.
+    ///
+    /// This is [`measurement_ready_after_us()`] as a [`Duration`], so that
+    /// application schedulers can budget time without duplicating the
+    /// driver's delay table. It is the datasheet's figure only: use
+    /// [`Config::measurement_duration()`] for the delay the driver actually
+    /// waits, including [`Config::delay_table`] and
+    /// [`Config::delay_margin_percent`].
+    ///
+    /// ```
+    /// # use core::time::Duration;
+    /// # use sht4x_rjw::common::{DelayMode, ReadingMode};
+    /// let duration = ReadingMode::MediumPrecision.measurement_duration(DelayMode::Typical);
+    /// assert_eq!(duration, Duration::from_micros(3_700));
+    /// ```
+    ///
+    /// [`Duration`]: core::time::Duration
+    pub fn measurement_duration(&self, delay_mode: DelayMode) -> core::time::Duration {
+        core::time::Duration::from_micros(delay_mode.us_for_reading_mode(*self).into())
+    }
+
+    /// Time from sending the command for a measurement in this mode until
+    /// it can be read, according to `delay_mode`, as a [`fugit`] duration.
+    ///
+    /// As with [`ReadingMode::measurement_duration()`], this is the
+    /// datasheet's figure only.
+    ///
+    /// [`fugit`]: https://docs.rs/fugit
+    #[cfg(feature = "fugit")]
+    pub fn measurement_duration_fugit(&self, delay_mode: DelayMode) -> fugit::MicrosDurationU32 {
+        fugit::MicrosDurationU32::from_ticks(delay_mode.us_for_reading_mode(*self))
+    }
+
+    /// The least precise (non-heater) reading mode whose repeatability (3σ)
+    /// is at least as good as `celsius` and `percent_rh`.
+    ///
+    /// Less precise readings complete sooner, so the sensor spends less
+    /// time powered up measuring, which saves power. `None` is returned if
+    /// even a high-precision reading is not repeatable enough.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::ReadingMode;
+    /// let mode = ReadingMode::for_repeatability(0.08, 0.2);
+    /// assert!(matches!(mode, Some(ReadingMode::MediumPrecision)));
+    /// ```
+    #[cfg(feature = "float")]
+    pub fn for_repeatability(celsius: f32, percent_rh: f32) -> Option<ReadingMode> {
+        [
+            ReadingMode::LowPrecision,
+            ReadingMode::MediumPrecision,
+            ReadingMode::HighPrecision,
+        ]
+        .into_iter()
+        .find(|mode| {
+            let (mode_celsius, mode_percent_rh) = mode.repeatability();
+            mode_celsius <= celsius && mode_percent_rh <= percent_rh
+        })
+    }
+
+    /// Repeatability (3σ) of readings taken in this mode, as `(°C, %RH)`.
+    ///
+    /// Heater readings are taken at high precision, so have the same
+    /// repeatability as [`ReadingMode::HighPrecision`].
+    #[cfg(feature = "float")]
+    pub fn repeatability(&self) -> (f32, f32) {
+        match self {
+            ReadingMode::HighPrecision | ReadingMode::HighPrecisionWithHeater(..) => (0.04, 0.08),
+            ReadingMode::MediumPrecision => (0.07, 0.15),
+            ReadingMode::LowPrecision => (0.1, 0.25),
+        }
+    }
+}
+
+impl TryFrom<u8> for ReadingMode {
+    type Error = UnknownCommand;
+
+    /// The reading mode whose command byte is `byte`.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::{HeaterDuration, HeaterPower, ReadingMode};
+    /// let mode = ReadingMode::try_from(0x24);
+    /// assert_eq!(
+    ///     mode,
+    ///     Ok(ReadingMode::HighPrecisionWithHeater(HeaterPower::Medium, HeaterDuration::Short)),
+    /// );
+    /// ```
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        let index = Self::COMMAND_BYTES
+            .iter()
+            .position(|&b| b == byte)
+            .ok_or(UnknownCommand(byte))?;
+        Ok(Self::ALL[index])
+    }
+}
+
+/// A command understood by the sensor.
+///
+/// The driver sends these itself, but mapping bytes back to commands is
+/// useful for bus sniffers, simulators and test assertions. All commands are
+/// a single byte, listed in section 4.5 of the [datasheet].
+///
+/// ```
+/// # use sht4x_rjw::common::{Command, ReadingMode};
+/// assert_eq!(Command::try_from(0x94), Ok(Command::SoftReset));
+/// assert_eq!(Command::Measure(ReadingMode::HighPrecision).byte(), 0xFD);
+///
+/// for command in Command::ALL {
+///     assert_eq!(Command::try_from(command.byte()), Ok(command));
+/// }
+/// assert!(Command::try_from(0x00).is_err());
+/// ```
+///
+/// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Command {
+    /// Take a measurement in the given reading mode.
+    Measure(ReadingMode),
+    /// Read the sensor's serial number.
+    ReadSerialNumber,
+    /// Reset the sensor.
+    SoftReset,
+}
+
+impl Command {
+    /// Every command, for iterating over the full command map.
+    pub const ALL: [Command; 11] = {
+        let mut commands = [Command::ReadSerialNumber; 11];
+        let mut i = 0;
+        while i < ReadingMode::ALL.len() {
+            commands[i] = Command::Measure(ReadingMode::ALL[i]);
+            i += 1;
+        }
+        commands[10] = Command::SoftReset;
+        commands
+    };
+
+    /// The I2C command byte.
+    pub const fn byte(&self) -> u8 {
+        match self {
+            Command::Measure(reading_mode) => reading_mode.command_byte(),
+            Command::ReadSerialNumber => READ_SERIAL_NUMBER_COMMAND,
+            Command::SoftReset => SOFT_RESET_COMMAND,
+        }
+    }
+}
+
+impl TryFrom<u8> for Command {
+    type Error = UnknownCommand;
+
+    /// The command whose byte is `byte`.
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            READ_SERIAL_NUMBER_COMMAND => Ok(Command::ReadSerialNumber),
+            SOFT_RESET_COMMAND => Ok(Command::SoftReset),
+            _ => ReadingMode::try_from(byte).map(Command::Measure),
+        }
+    }
+}
+
+impl From<Command> for u8 {
+    fn from(command: Command) -> Self {
+        command.byte()
+    }
+}
+
+/// A part in the SHT4x line.
+///
+/// The variants share an I2C interface but differ in accuracy. See section 2
+/// of the [datasheet] for each part's accuracy across its full range.
+///
+/// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SensorVariant {
+    /// Typical accuracy of ±0.2°C and ±1.8%RH.
+    Sht40,
+    /// Typical accuracy of ±0.2°C and ±1.8%RH.
+    Sht41,
+    /// Typical accuracy of ±0.2°C and ±1.8%RH.
+    Sht43,
+    /// Typical accuracy of ±0.1°C and ±1.0%RH.
+    Sht45,
+}
+
+impl SensorVariant {
+    /// Typical accuracy of the part, as `(°C, %RH)`.
+    ///
+    /// These are the best-case figures, from the middle of the sensor's
+    /// range. Accuracy is worse towards the extremes of temperature and
+    /// humidity.
+    #[cfg(feature = "float")]
+    pub fn typical_accuracy(&self) -> (f32, f32) {
+        match self {
+            SensorVariant::Sht40 | SensorVariant::Sht41 | SensorVariant::Sht43 => (0.2, 1.8),
+            SensorVariant::Sht45 => (0.1, 1.0),
+        }
+    }
+}
+
+/// A simple model of the sensor's self-heating, for compensating the
+/// temperature reading.
+///
+/// The sensor warms slightly while measuring, so sampling often biases the
+/// temperature upwards. The datasheet advises keeping the measurement duty
+/// cycle low to make this negligible; where that isn't possible, this model
+/// estimates the bias as proportional to the duty cycle, which is the
+/// typical measurement duration for the reading mode divided by the
+/// sampling interval.
+///
+/// The coefficient depends on the sensor's mounting and airflow, so it
+/// should be found for your hardware, for example by comparing readings
+/// taken rapidly with readings taken once a minute. Heater pulses are not
+/// modelled: readings taken with the heater are far hotter than this model
+/// accounts for.
+///
+/// ```rust
+/// use sht4x_rjw::common::{ReadingMode, SelfHeating};
+/// // 10°C of warming if the sensor were measuring continuously.
+/// let model = SelfHeating::new(10.0);
+/// // Measuring at high precision (6.9ms typical) every 100ms.
+/// let offset = model.offset_celsius(ReadingMode::HighPrecision, 100_000);
+/// assert!((offset - 0.69).abs() < 1e-6);
+/// ```
+#[cfg(feature = "float")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelfHeating {
+    /// Temperature rise, in °C, were the sensor measuring continuously.
+    pub celsius_at_full_duty: f32,
+}
+
+#[cfg(feature = "float")]
+impl SelfHeating {
+    /// Create a model with the given temperature rise at 100% duty cycle.
+    pub const fn new(celsius_at_full_duty: f32) -> Self {
+        Self {
+            celsius_at_full_duty,
+        }
+    }
+
+    /// Estimated temperature rise, in °C, when measuring in `reading_mode`
+    /// every `interval_us` microseconds.
+    ///
+    /// An interval shorter than the measurement (including zero) is treated
+    /// as measuring continuously.
+    pub fn offset_celsius(&self, reading_mode: ReadingMode, interval_us: u32) -> f32 {
+        let duration_us = DelayMode::Typical.us_for_reading_mode(reading_mode);
+        let duty = if interval_us <= duration_us {
+            1.0
+        } else {
+            duration_us as f32 / interval_us as f32
+        };
+        self.celsius_at_full_duty * duty
+    }
 }
 
 /// Length of delay before attempting to read from the sensor.
@@ -195,8 +641,9 @@ impl ReadingMode {
 /// - Heater, long: 100ms
 ///
 /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DelayMode {
     /// Use the typical delay times before attempting to read.
     ///
@@ -214,16 +661,68 @@ pub enum DelayMode {
     /// - Heater, short: 110ms
     /// - Heater, long: 1,100ms
     Maximum,
+    /// Start reading early, and poll the sensor until the measurement is
+    /// ready, waiting `interval_us` microseconds after each NACK.
+    ///
+    /// Reads start as far before the typical delay time as the maximum is
+    /// after it (so 5.5ms for high-precision reads), and the sensor is
+    /// polled until the maximum time, when the NACK is returned as
+    /// [`Error::NotReady`]. This reduces the average latency, at the cost of more bus
+    /// traffic.
+    ///
+    /// Only `measure_with_settings()`, and the methods that use it such as
+    /// `measure()`, poll the sensor. Elsewhere this mode is treated as
+    /// [`DelayMode::Maximum`].
+    Poll {
+        /// Delay between polls, in microseconds.
+        interval_us: u32,
+    },
+    /// Wait `delay_us` microseconds before reading, whatever the reading
+    /// mode.
+    ///
+    /// This is for sensors that need longer than the maximum delay (for
+    /// example at the edges of their operating conditions), or where a
+    /// shorter delay has proven reliable. The same delay is used for every
+    /// reading mode, including the heater modes, so choose it for the mode
+    /// you measure with.
+    Custom {
+        /// Delay before reading, in microseconds.
+        delay_us: u32,
+    },
 }
 
 impl DelayMode {
+    /// Typical delay in microseconds for each of [`ReadingMode::ALL`].
+    pub const TYPICAL_US: [u32; 9] = Self::table(DelayMode::Typical);
+
+    /// Maximum delay in microseconds for each of [`ReadingMode::ALL`].
+    pub const MAXIMUM_US: [u32; 9] = Self::table(DelayMode::Maximum);
+
+    /// Delay for each of [`ReadingMode::ALL`] with `delay_mode`.
+    const fn table(delay_mode: DelayMode) -> [u32; 9] {
+        let mut delays = [0; 9];
+        let mut i = 0;
+        while i < delays.len() {
+            delays[i] = delay_mode.us_for_reading_mode(ReadingMode::ALL[i]);
+            i += 1;
+        }
+        delays
+    }
+
     /// Microsecond delay for the current delay mode and the given reading mode.
     ///
     /// Attempting to read from the sensor before its operation has completed
-    /// will result in a NACK from the sensor (and so an error from the I2C
-    /// interface), so this delay is used to ensure we can successfully read
-    /// the measurement data over I2C.
-    pub(crate) fn us_for_reading_mode(&self, reading_mode: ReadingMode) -> u32 {
+    /// will result in a NACK from the sensor (reported as
+    /// [`Error::NotReady`]), so this delay is used to ensure we can successfully read
+    /// the measurement data over I2C. This is a `const fn`, so delays can be
+    /// computed at compile time:
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::{DelayMode, ReadingMode};
+    /// const DELAY_US: u32 = DelayMode::Maximum.us_for_reading_mode(ReadingMode::HighPrecision);
+    /// assert_eq!(DELAY_US, 8_300);
+    /// ```
+    pub const fn us_for_reading_mode(&self, reading_mode: ReadingMode) -> u32 {
         use DelayMode::{Maximum, Typical};
         use ReadingMode::{HighPrecision, HighPrecisionWithHeater, LowPrecision, MediumPrecision};
 
@@ -238,122 +737,2191 @@ impl DelayMode {
             (HighPrecisionWithHeater(_, HeaterDuration::Long), Maximum) => 1_100_000,
             (HighPrecisionWithHeater(_, HeaterDuration::Short), Typical) => 100_000,
             (HighPrecisionWithHeater(_, HeaterDuration::Short), Maximum) => 110_000,
+            (_, DelayMode::Poll { .. }) => Maximum.us_for_reading_mode(reading_mode),
+            (_, DelayMode::Custom { delay_us }) => *delay_us,
         }
     }
+
+    /// When to start and stop polling for a measurement with
+    /// [`DelayMode::Poll`], in microseconds after the command is sent.
+    pub(crate) fn poll_window_us(reading_mode: ReadingMode) -> (u32, u32) {
+        let typical = DelayMode::Typical.us_for_reading_mode(reading_mode);
+        let maximum = DelayMode::Maximum.us_for_reading_mode(reading_mode);
+        (typical - (maximum - typical), maximum)
+    }
 }
 
-/// Default settings for the sensor's reading and delay modes.
-///
-/// The settings provided in the `Config` when the sensor struct is created
-/// are used for the [`SHT4x::measure()`] method, which only requires the
-/// user to provide a delay implementation.
+/// Time in microseconds after sending the command for a measurement in
+/// `reading_mode` until it can be read, according to `delay_mode`.
 ///
-/// These settings may be changed at any time by mutating the `config` field
-/// of the sensor struct. Otherwise, the [`SHT4x::measure_with_settings()`]
-/// method allows the user to specify the reading and delay modes on each
-/// call.
+/// Schedulers can use this to sleep until the measurement is ready rather
+/// than delaying inside the driver. With [`DelayMode::Poll`], this is the
+/// maximum measurement time, after which the measurement is certain to be
+/// ready.
 ///
-/// [`SHT4x::measure()`]: crate::blocking::SHT4x::measure
-/// [`SHT4x::measure_with_settings()`]: crate::blocking::SHT4x::measure
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Config {
-    /// Default measurement precision or heater usage.
-    pub reading_mode: ReadingMode,
-    /// Default delay mode.
-    pub delay_mode: DelayMode,
+/// ```rust
+/// use sht4x_rjw::common::{measurement_ready_after_us, DelayMode, ReadingMode};
+/// let us = measurement_ready_after_us(ReadingMode::LowPrecision, DelayMode::Maximum);
+/// assert_eq!(us, 1_600);
+/// ```
+pub const fn measurement_ready_after_us(reading_mode: ReadingMode, delay_mode: DelayMode) -> u32 {
+    delay_mode.us_for_reading_mode(reading_mode)
 }
 
-impl Default for Config {
-    /// Construct a `Config` for high-precision readings and typical delays.
-    fn default() -> Self {
-        Self {
-            reading_mode: ReadingMode::HighPrecision,
-            delay_mode: DelayMode::Typical,
-        }
+/// Whether an I2C error is a NACK, as the sensor gives while it is busy.
+pub(crate) fn is_nack(error: &impl embedded_hal::i2c::Error) -> bool {
+    matches!(error.kind(), embedded_hal::i2c::ErrorKind::NoAcknowledge(_))
+}
+
+/// Convert an I2C error into our [`Error`], replacing a NACK with `nack`.
+pub(crate) fn classify_nack<E: embedded_hal::i2c::Error>(error: E, nack: Error<E>) -> Error<E> {
+    if is_nack(&error) {
+        nack
+    } else {
+        Error::I2c(error)
     }
 }
 
-/// A temperature and humidity measurement from the sensor.
+/// How to check that the sensor is responsive after a soft reset.
 ///
-/// Users should use this struct's methods to convert the raw readings into
-/// recognisable units. (These methods are just wrappers around the functions
-/// provided in [`sht4x_rjw::conversions`]).
+/// Used with `soft_reset_and_verify()` on either driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ResetVerification {
+    /// Read the sensor's serial number.
+    SerialNumber,
+    /// Take a low-precision measurement, which is discarded.
+    Measurement,
+}
+
+/// A minimum interval between measurements, to bound self-heating.
 ///
-/// The "raw" values are also available via methods. They have been
-/// reconstructed as `u16`s from the bytes read from the sensor, after
-/// passing CRC validation, but have otherwise not been converted.
+/// Each measurement warms the sensor slightly, and the datasheet advises
+/// keeping the duty cycle low (below about 10%) so that self-heating does
+/// not bias the temperature reading. Set [`Config::rate_limit`] to have the
+/// drivers enforce a minimum interval between the start of one measurement
+/// and the next.
 ///
-/// [`sht4x_rjw::conversions`]: crate::conversions
-#[derive(Debug, Clone, Copy)]
+/// The limit is checked as each measurement command is sent, whenever the
+/// driver knows the time: when it has a clock of its own, set with
+/// `set_clock()`, or is passed one, as by `measure_with_clock()`,
+/// `measure_with_bus_window()`, `start_measurement()`, the samplers and the
+/// `nb` driver. Measurements taken without a clock are not limited, and nor
+/// are retries, the measurement discarded by
+/// [`Config::discard_first_measurement`], the ambient measurement of
+/// `measure_heated_then_ambient()`, the measurement checking the sensor in
+/// `soft_reset_and_verify()`, or measurements from other sensors with
+/// `measure_at()`.
+///
+/// Methods that can't wait, such as `start_measurement()`, refuse a
+/// measurement that is too soon whatever the [`RateLimitAction`]. A refused
+/// measurement is counted as an error in the driver's statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Measurement {
-    /// The unconverted temperature value received from the sensor.
-    raw_temp: u16,
-    /// The unconverted humidity value received from the sensor.
-    raw_humidity: u16,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RateLimit {
+    /// Minimum time between the starts of consecutive measurements, in
+    /// microseconds.
+    pub min_interval_us: u32,
+    /// What to do when a measurement is requested too soon.
+    pub action: RateLimitAction,
 }
 
-impl Measurement {
-    pub(crate) fn from_read_bytes<I>(sensor_data: Unvalidated) -> Result<Self, Error<I>>
-    where
-        I: embedded_hal::i2c::Error,
-    {
-        let [t0, t1, h0, h1] = sensor_data.try_get_bytes(
-            CrcFailureReason::TemperatureBytes,
-            CrcFailureReason::HumidityBytes,
-        )?;
-        Ok(Measurement {
-            raw_temp: u16::from_be_bytes([t0, t1]),
-            raw_humidity: u16::from_be_bytes([h0, h1]),
-        })
-    }
+/// What a [`RateLimit`] does when a measurement is requested too soon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RateLimitAction {
+    /// Fail with [`Error::TooSoon`].
+    #[default]
+    Reject,
+    /// Wait, using the delay, until the minimum interval has passed.
+    Wait,
+}
 
-    /// Convert the raw humidity reading to percent relative humidity.
-    pub fn humidity(&self) -> f32 {
-        crate::conversions::humidity_reading_to_percent_rh(self.raw_humidity)
+/// I2C address of the sensor.
+///
+/// Most sensors are at `0x44`, but those with part numbers including `-B`
+/// and `-C` are at `0x45` and `0x46` respectively (see section 9 of the
+/// [datasheet]). Other addresses, such as those of sensors behind an address
+/// translator, can be given with [`Address::Custom`].
+///
+/// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Address {
+    /// `0x44`, the address of most parts.
+    #[default]
+    Default0x44,
+    /// `0x45`, the address of `-B` parts.
+    Alt0x45,
+    /// `0x46`, the address of `-C` parts.
+    Alt0x46,
+    /// Any other seven-bit address.
+    ///
+    /// Reserved addresses (`0x00` to `0x07` and `0x78` to `0x7F`) are
+    /// rejected by [`Config::validate()`].
+    Custom(u8),
+}
+
+impl Address {
+    /// The address for `address`, using the named variants for the
+    /// sensor's standard addresses.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::Address;
+    /// assert_eq!(Address::new(0x45), Ok(Address::Alt0x45));
+    /// assert!(Address::new(0x80).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::InvalidAddress`] if `address` is reserved or
+    /// does not fit in seven bits.
+    pub const fn new(address: u8) -> Result<Self, ConfigError> {
+        match address {
+            0x44 => Ok(Address::Default0x44),
+            0x45 => Ok(Address::Alt0x45),
+            0x46 => Ok(Address::Alt0x46),
+            0x08..=0x77 => Ok(Address::Custom(address)),
+            _ => Err(ConfigError::InvalidAddress(address)),
+        }
     }
 
-    /// Convert the raw temperature reading to celsius.
-    pub fn celsius(&self) -> f32 {
-        crate::conversions::temperature_reading_to_celsius(self.raw_temp)
+    /// The seven-bit address, as passed to the I2C interface.
+    pub const fn value(&self) -> u8 {
+        match self {
+            Address::Default0x44 => 0x44,
+            Address::Alt0x45 => 0x45,
+            Address::Alt0x46 => 0x46,
+            Address::Custom(address) => *address,
+        }
     }
 
-    /// Convert the raw temperature reading to fahrenheit.
-    pub fn fahrenheit(&self) -> f32 {
-        crate::conversions::temperature_reading_to_fahrenheit(self.raw_temp)
+    /// Whether the address is a usable seven-bit address.
+    pub(crate) const fn is_valid(&self) -> bool {
+        matches!(self.value(), 0x08..=0x77)
     }
+}
 
-    /// The unconverted temperature reading from the sensor as a 16-bit integer.
-    pub fn raw_temperature_reading(&self) -> u16 {
-        self.raw_temp
+impl From<Address> for u8 {
+    fn from(address: Address) -> Self {
+        address.value()
     }
+}
 
-    /// The unconverted humidity reading from the sensor as a 16-bit integer.
-    pub fn raw_humidity_reading(&self) -> u16 {
-        self.raw_humidity
+impl TryFrom<u8> for Address {
+    type Error = ConfigError;
+
+    /// As for [`Address::new()`].
+    fn try_from(address: u8) -> Result<Self, Self::Error> {
+        Self::new(address)
     }
 }
 
-/// Fixed-point conversion methods.
-#[cfg(feature = "fixed")]
-impl Measurement {
-    /// Convert the raw humidity reading to percent relative humidity.
-    pub fn humidity_fixed_point(&self) -> I16F16 {
-        crate::conversions::fixed_point::humidity_reading_to_percent_rh(self.raw_humidity)
+/// The sensor that a driver operation communicates with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) enum Sensor {
+    /// The sensor at the config's address, whose state the driver keeps.
+    Configured,
+    /// Another sensor on the bus, given to `measure_at()`, for which the
+    /// driver keeps no state.
+    At(Address),
+}
+
+impl Sensor {
+    /// The sensor's seven-bit I2C address, given the driver's `config`.
+    pub(crate) const fn address(self, config: &Config) -> u8 {
+        match self {
+            Sensor::Configured => config.address.value(),
+            Sensor::At(address) => address.value(),
+        }
     }
+}
 
-    /// Convert the raw temperature reading to celsius.
-    pub fn celsius_fixed_point(&self) -> I16F16 {
-        crate::conversions::fixed_point::temperature_reading_to_celsius(self.raw_temp)
+/// How a driver handles humidity readings outside the physical range of
+/// `0..=100`%RH.
+///
+/// Sensirion note that the sensor may produce such readings at the
+/// boundaries of its range, and recommend clamping them (see section 4.6 of
+/// the [datasheet]). Persistent out-of-range readings can, however, point
+/// to sensor drift or contamination, which clamping hides.
+///
+/// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HumidityClamp {
+    /// Clamp [`Measurement::humidity()`] to `0.0..=100.0`.
+    #[default]
+    Clamp,
+    /// Don't clamp [`Measurement::humidity()`], so it ranges from −6%RH to
+    /// 119%RH. The integer accessors, such as
+    /// [`Measurement::milli_percent_rh()`], are always clamped, as they are
+    /// unsigned, as are the derived quantities.
+    DontClamp,
+    /// Fail with [`Error::HumidityOutOfRange`] when the reading is out of
+    /// range.
+    ErrorOnOutOfRange,
+}
+
+/// Acceptable ranges for measurements, to catch corruption on the bus that
+/// happens to pass the CRC check.
+///
+/// A reading is implausible if it lies outside the temperature or humidity
+/// range, or if it has changed from the driver's previous reading by more
+/// than the maximum step. The previous reading counts even if it was itself
+/// implausible, so a single corrupted reading causes at most two to be
+/// rejected, and a genuine sudden change only one. The humidity is checked
+/// after the config's [`HumidityClamp`] has been applied, so a clamped
+/// reading never falls outside `0..=100`%RH.
+///
+/// Readings taken with the heater are only checked against the ranges, as
+/// the heater is expected to move them well beyond any sensible step. They
+/// don't count as the previous reading either, so the ambient reading after
+/// a heater pulse is compared with the ambient reading before it.
+///
+/// ```
+/// # use sht4x_rjw::common::{Config, Plausibility};
+/// let config = Config {
+///     plausibility: Some(Plausibility {
+///         max_celsius_step: Some(5),
+///         ..Plausibility::SENSOR_RANGE
+///     }),
+///     ..Default::default()
+/// };
+/// assert!(config.validate().is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plausibility {
+    /// Lowest plausible temperature, in degrees Celsius.
+    pub min_celsius: i8,
+    /// Highest plausible temperature, in degrees Celsius.
+    pub max_celsius: i8,
+    /// Lowest plausible relative humidity, in percent.
+    pub min_percent_rh: u8,
+    /// Highest plausible relative humidity, in percent.
+    pub max_percent_rh: u8,
+    /// Largest plausible change in temperature between consecutive
+    /// measurements, in degrees Celsius. With `None` any change is accepted.
+    pub max_celsius_step: Option<u8>,
+    /// Largest plausible change in relative humidity between consecutive
+    /// measurements, in percent. With `None` any change is accepted.
+    pub max_percent_rh_step: Option<u8>,
+    /// What the driver does with an implausible reading.
+    pub action: PlausibilityAction,
+}
+
+impl Plausibility {
+    /// The sensor's specified range of −40°C to 125°C and 0%RH to 100%RH,
+    /// with no limit on the change between measurements, failing with
+    /// [`Error::ImplausibleReading`].
+    pub const SENSOR_RANGE: Self = Self {
+        min_celsius: -40,
+        max_celsius: 125,
+        min_percent_rh: 0,
+        max_percent_rh: 100,
+        max_celsius_step: None,
+        max_percent_rh_step: None,
+        action: PlausibilityAction::Error,
+    };
+
+    /// Whether the ranges are the right way round.
+    const fn is_valid(&self) -> bool {
+        self.min_celsius <= self.max_celsius && self.min_percent_rh <= self.max_percent_rh
     }
 
-    /// Convert the raw temperature reading to fahrenheit.
-    pub fn fahrenheit_fixed_point(&self) -> I16F16 {
-        crate::conversions::fixed_point::temperature_reading_to_fahrenheit(self.raw_temp)
+    /// Whether `measurement` is plausible, following `previous`, if any.
+    ///
+    /// The bounds are converted to raw ticks, rounding down, so that the
+    /// check needs no floating-point arithmetic.
+    pub(crate) fn accepts(
+        &self,
+        measurement: &Measurement,
+        previous: Option<&Measurement>,
+    ) -> bool {
+        const fn temp_ticks(celsius: i32) -> i32 {
+            celsius * 65_535 / 175
+        }
+        const fn humidity_ticks(percent_rh: i32) -> i32 {
+            percent_rh * 65_535 / 125
+        }
+        let raw_temp = i32::from(measurement.raw_temp);
+        let raw_humidity = i32::from(measurement.clamped_raw_humidity());
+        let in_range = (temp_ticks(i32::from(self.min_celsius) + 45)
+            ..=temp_ticks(i32::from(self.max_celsius) + 45))
+            .contains(&raw_temp)
+            && (humidity_ticks(i32::from(self.min_percent_rh) + 6)
+                ..=humidity_ticks(i32::from(self.max_percent_rh) + 6))
+                .contains(&raw_humidity);
+        let Some(previous) = previous else {
+            return in_range;
+        };
+        let temp_step = (raw_temp - i32::from(previous.raw_temp)).abs();
+        let humidity_step = (raw_humidity - i32::from(previous.clamped_raw_humidity())).abs();
+        in_range
+            && self
+                .max_celsius_step
+                .is_none_or(|step| temp_step <= temp_ticks(step.into()))
+            && self
+                .max_percent_rh_step
+                .is_none_or(|step| humidity_step <= humidity_ticks(step.into()))
     }
 }
 
-/// Reconstruct the 32-bit serial number after validating the received CRCs.
+/// What a driver does with a reading outside its [`Plausibility`] ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlausibilityAction {
+    /// Fail with [`Error::ImplausibleReading`].
+    #[default]
+    Error,
+    /// Return the measurement, with [`Measurement::is_plausible()`]
+    /// returning `false`.
+    Flag,
+}
+
+/// Default settings for the sensor's reading and delay modes.
+///
+/// The settings provided in the `Config` when the sensor struct is created
+/// are used for the [`SHT4x::measure()`] method, which only requires the
+/// user to provide a delay implementation.
+///
+/// These settings may be changed at any time with the sensor struct's
+/// `set_config()` method, which validates them first. Otherwise, the [`SHT4x::measure_with_settings()`]
+/// method allows the user to specify the reading and delay modes on each
+/// call.
+///
+/// [`SHT4x::measure()`]: crate::blocking::SHT4x::measure
+/// [`SHT4x::measure_with_settings()`]: crate::blocking::SHT4x::measure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+    /// I2C address of the sensor. The default is [`Address::Default0x44`].
+    ///
+    /// Changing the address affects all subsequent I2C interactions.
+    pub address: Address,
+    /// Default measurement precision or heater usage.
+    pub reading_mode: ReadingMode,
+    /// Default delay mode.
+    pub delay_mode: DelayMode,
+    /// Percentage added to the typical delays, as a middle ground between
+    /// [`DelayMode::Typical`] and [`DelayMode::Maximum`]. The default is 0.
+    ///
+    /// Sensors at the edges of their operating conditions, such as in the
+    /// cold, can occasionally take longer than the typical time, so a small
+    /// margin (say 5%) avoids the resulting NACKs without waiting for the
+    /// maximum time. The margin applies wherever the driver waits with
+    /// [`DelayMode::Typical`], whether from this `Config` or passed to a
+    /// method; the other delay modes are unaffected.
+    pub delay_margin_percent: u8,
+    /// How long, in microseconds, a measurement taken by `measure()` may be
+    /// returned again by later calls, without communicating with the sensor.
+    ///
+    /// The driver can only tell a measurement's age when it knows the time,
+    /// from its own clock set with `set_clock()` or from the clock passed to
+    /// `measure_with_clock()`; otherwise every call takes a new
+    /// measurement. With `None` (the default) the cache is disabled.
+    pub cache_max_age_us: Option<u32>,
+    /// Calibration offset added to every raw temperature reading, in raw
+    /// sensor ticks (about 0.0027°C each).
+    ///
+    /// The offset is applied when the measurement is read from the sensor,
+    /// so it affects every conversion, including the raw accessors. The
+    /// default is 0. See the [`calibration`](crate::calibration) module for
+    /// persisting offsets, and [`Config::set_temperature_offset_millicelsius()`]
+    /// for setting the offset in degrees. The humidity reading is not
+    /// adjusted to match; use [`Measurement::humidity_compensated()`] for
+    /// the humidity at the corrected temperature.
+    pub temperature_offset_raw: i16,
+    /// Calibration offset added to every raw humidity reading, in raw sensor
+    /// ticks (about 0.0019%RH each). The default is 0.
+    pub humidity_offset_raw: i16,
+    /// Retry settings followed by `measure()` and `serial_number()`, and the
+    /// methods that use them. The default, [`RetryConfig::NONE`], makes a
+    /// single attempt.
+    ///
+    /// As `serial_number()` takes no delay, it retries immediately, without
+    /// the backoff. Methods that take a [`RetryPolicy`], such as
+    /// `measure_with_retry()`, follow that policy instead.
+    ///
+    /// [`RetryPolicy`]: crate::retry::RetryPolicy
+    pub retry: RetryConfig,
+    /// Minimum interval between measurements, when the driver knows the
+    /// time; see [`RateLimit`]. The default, `None`, imposes no limit.
+    pub rate_limit: Option<RateLimit>,
+    /// Whether to take and discard a measurement before the first one after
+    /// construction of the sensor struct or a soft reset, as the first
+    /// conversion after power-up can be slightly off. The default is
+    /// `false`.
+    ///
+    /// The extra measurement is taken with the same settings, by
+    /// `measure_with_settings()` and the methods that use it, such as
+    /// `measure()`.
+    pub discard_first_measurement: bool,
+    /// Serial number that `init()` expects the sensor to report, failing
+    /// with [`Error::SerialMismatch`] if it reports another. With `None`
+    /// (the default) any serial number is accepted.
+    ///
+    /// This detects sensors that have been replaced in the field, or wiring
+    /// that puts a different sensor at the expected address.
+    pub expected_serial: Option<u32>,
+    /// Whether to check the CRC of each pair of bytes read from the sensor.
+    /// The default is `true`.
+    ///
+    /// Disabling validation saves a little time on very constrained
+    /// targets, or where the bus is checked by other means, but corrupted
+    /// readings are then returned as if they were genuine. This applies to
+    /// every read of measurements and serial numbers, including those by
+    /// `measure_raw()`.
+    pub validate_crc: bool,
+    /// How humidity readings outside `0..=100`%RH are handled. The
+    /// default, [`HumidityClamp::Clamp`], clamps them.
+    pub humidity_clamp: HumidityClamp,
+    /// Maximum heater duty cycle, in percent, enforced before each heater
+    /// command. With `None` (the default) the heater is not limited.
+    ///
+    /// A heater command is refused with [`Error::HeaterLimitExceeded`]
+    /// until the previous heater pulse is no more than this share of the
+    /// time since it began. The driver has no notion of time of its own, so
+    /// it uses the time from the [`Clock`] most recently passed to it, such
+    /// as by `measure_timestamped()`; if no clock has been passed, the duty
+    /// cycle cannot be checked and only the temperature limit applies.
+    /// Sensirion recommend at most [`HeaterStats::MAX_DUTY_CYCLE_PERCENT`].
+    ///
+    /// [`Clock`]: crate::clock::Clock
+    pub heater_max_duty_cycle_percent: Option<u8>,
+    /// Highest ambient temperature, in degrees Celsius, at which the heater
+    /// may be used. With `None` (the default) the heater is not limited.
+    ///
+    /// A heater command is refused with [`Error::HeaterLimitExceeded`] if
+    /// the driver's most recent measurement is above this temperature.
+    /// Section 4.9 of the datasheet recommends against using the heater
+    /// above 65°C. As a heated measurement reflects the heater rather than
+    /// the surroundings, take a measurement without the heater to refresh
+    /// the ambient temperature after the sensor has cooled.
+    pub heater_max_ambient_celsius: Option<i8>,
+    /// Acceptable ranges for measurements. With `None` (the default) every
+    /// reading that passes the CRC check is accepted.
+    pub plausibility: Option<Plausibility>,
+    /// Delays in microseconds to use in place of the datasheet's typical
+    /// delays, one for each of [`ReadingMode::ALL`], in that order. With
+    /// `None` (the default) the datasheet's delays are used.
+    ///
+    /// This is for sensors whose timings have been characterised, so that
+    /// they can be read sooner than the datasheet allows. The table is used
+    /// wherever the driver waits with [`DelayMode::Typical`], and
+    /// [`Config::delay_margin_percent`] is added as usual; the other delay
+    /// modes are unaffected, so [`DelayMode::Maximum`] remains available as
+    /// a fallback.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::{Config, DelayMode};
+    /// let mut delays = DelayMode::TYPICAL_US;
+    /// delays[0] = 6_000;
+    /// let config = Config {
+    ///     delay_table: Some(delays),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub delay_table: Option<[u32; 9]>,
+}
+
+impl Default for Config {
+    /// Construct a `Config` for high-precision readings and typical delays,
+    /// with measurement caching disabled, no calibration offsets, no
+    /// retries and no rate limit.
+    fn default() -> Self {
+        Self::new(ReadingMode::HighPrecision, DelayMode::Typical)
+    }
+}
+
+impl Config {
+    /// Construct a `Config` with the given reading and delay modes,
+    /// measurement caching disabled, no calibration offsets, no retries and
+    /// no rate limit.
+    ///
+    /// This is a `const fn`, so it can be used to initialise `static`s and
+    /// `const`s:
+    ///
+    /// ```rust
+    /// use sht4x_rjw::common::{Config, DelayMode, ReadingMode};
+    /// static CONFIG: Config = Config::new(ReadingMode::MediumPrecision, DelayMode::Maximum);
+    /// ```
+    pub const fn new(reading_mode: ReadingMode, delay_mode: DelayMode) -> Self {
+        Self {
+            address: Address::Default0x44,
+            reading_mode,
+            delay_mode,
+            delay_margin_percent: 0,
+            cache_max_age_us: None,
+            temperature_offset_raw: 0,
+            humidity_offset_raw: 0,
+            retry: RetryConfig::NONE,
+            rate_limit: None,
+            discard_first_measurement: false,
+            expected_serial: None,
+            validate_crc: true,
+            humidity_clamp: HumidityClamp::Clamp,
+            heater_max_duty_cycle_percent: None,
+            heater_max_ambient_celsius: None,
+            plausibility: None,
+            delay_table: None,
+        }
+    }
+
+    /// Recommended sampling interval, in microseconds, for
+    /// [`Config::low_power()`]: one minute.
+    pub const LOW_POWER_INTERVAL_US: u32 = 60_000_000;
+
+    /// Recommended sampling interval, in microseconds, for
+    /// [`Config::balanced()`]: ten seconds.
+    pub const BALANCED_INTERVAL_US: u32 = 10_000_000;
+
+    /// Recommended sampling interval, in microseconds, for
+    /// [`Config::high_accuracy()`]: two seconds.
+    pub const HIGH_ACCURACY_INTERVAL_US: u32 = 2_000_000;
+
+    /// Construct a `Config` for battery-powered devices: low-precision
+    /// readings with typical delays, keeping the sensor and bus active for
+    /// as little time as possible.
+    ///
+    /// Sample every [`Config::LOW_POWER_INTERVAL_US`] to match, such as by
+    /// passing it to [`PeriodicSampler::new()`].
+    ///
+    /// [`PeriodicSampler::new()`]: crate::sampler::PeriodicSampler::new
+    pub const fn low_power() -> Self {
+        Self::new(ReadingMode::LowPrecision, DelayMode::Typical)
+    }
+
+    /// Construct a `Config` for general use: medium-precision readings with
+    /// a 10% margin on the typical delays.
+    ///
+    /// Sample every [`Config::BALANCED_INTERVAL_US`] to match.
+    pub const fn balanced() -> Self {
+        let mut config = Self::new(ReadingMode::MediumPrecision, DelayMode::Typical);
+        config.delay_margin_percent = 10;
+        config
+    }
+
+    /// Construct a `Config` for the best readings: high-precision readings
+    /// with the maximum delays, discarding the first measurement after
+    /// power-up or reset.
+    ///
+    /// Sample every [`Config::HIGH_ACCURACY_INTERVAL_US`] or less often, so
+    /// that self-heating stays negligible.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::{Config, DelayMode, ReadingMode};
+    /// let config = Config::high_accuracy();
+    /// assert_eq!(config.reading_mode, ReadingMode::HighPrecision);
+    /// assert_eq!(config.delay_mode, DelayMode::Maximum);
+    /// assert!(config.validate().is_ok());
+    /// ```
+    pub const fn high_accuracy() -> Self {
+        let mut config = Self::new(ReadingMode::HighPrecision, DelayMode::Maximum);
+        config.discard_first_measurement = true;
+        config
+    }
+
+    /// Construct a `Config` for the most precise (non-heater) readings that
+    /// complete within `max_us` microseconds with the given delay mode.
+    ///
+    /// See [`ReadingMode::for_latency_budget()`] for details. `None` is
+    /// returned if no reading mode fits within the budget.
+    pub fn for_latency_budget(max_us: u32, delay_mode: DelayMode) -> Option<Self> {
+        ReadingMode::for_latency_budget(max_us, delay_mode).map(|reading_mode| Self {
+            reading_mode,
+            delay_mode,
+            ..Default::default()
+        })
+    }
+
+    /// The temperature calibration offset, in thousandths of a degree
+    /// Celsius, rounded to the nearest.
+    ///
+    /// See [`Config::temperature_offset_raw`].
+    pub const fn temperature_offset_millicelsius(&self) -> i32 {
+        let milli = self.temperature_offset_raw as i64 * 175_000;
+        round_div(milli, 65_535) as i32
+    }
+
+    /// Set the temperature calibration offset in thousandths of a degree
+    /// Celsius, such as `-1_500` to correct a sensor that reads 1.5°C high
+    /// because it is mounted near a regulator.
+    ///
+    /// The offset is stored in raw sensor ticks, so it is rounded to the
+    /// nearest tick (about 0.0027°C), and clamped to the roughly ±87°C that
+    /// fits in [`Config::temperature_offset_raw`].
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::Config;
+    /// let mut config = Config::default();
+    /// config.set_temperature_offset_millicelsius(-1_500);
+    /// assert_eq!(config.temperature_offset_raw, -562);
+    /// assert_eq!(config.temperature_offset_millicelsius(), -1_501);
+    /// ```
+    pub const fn set_temperature_offset_millicelsius(&mut self, millicelsius: i32) {
+        let ticks = round_div(millicelsius as i64 * 65_535, 175_000);
+        self.temperature_offset_raw = if ticks > i16::MAX as i64 {
+            i16::MAX
+        } else if ticks < i16::MIN as i64 {
+            i16::MIN
+        } else {
+            ticks as i16
+        };
+    }
+
+    /// Start building a `Config`, from the defaults.
+    ///
+    /// The builder checks the finished `Config` with [`Config::validate()`],
+    /// and unlike a struct literal it keeps compiling as fields are added.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::{Address, Config, DelayMode, ReadingMode};
+    /// let config = Config::builder()
+    ///     .address(Address::Alt0x45)
+    ///     .reading_mode(ReadingMode::MediumPrecision)
+    ///     .delay_mode(DelayMode::Poll { interval_us: 500 })
+    ///     .build()?;
+    /// assert_eq!(config.reading_mode, ReadingMode::MediumPrecision);
+    /// # Ok::<(), sht4x_rjw::error::ConfigError>(())
+    /// ```
+    pub const fn builder() -> ConfigBuilder {
+        ConfigBuilder {
+            config: Self::new(ReadingMode::HighPrecision, DelayMode::Typical),
+        }
+    }
+
+    /// Check that the settings make sense together.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::InvalidAddress`] if a custom address is
+    /// reserved or does not fit in seven bits,
+    /// [`ConfigError::ZeroPollInterval`] if the delay mode polls without
+    /// waiting between polls, and [`ConfigError::InvalidBackoff`] if the
+    /// retry backoff's initial delay is longer than its maximum.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.address.is_valid() {
+            return Err(ConfigError::InvalidAddress(self.address.value()));
+        }
+        if let DelayMode::Poll { interval_us: 0 } = self.delay_mode {
+            return Err(ConfigError::ZeroPollInterval);
+        }
+        if let Backoff::Exponential {
+            initial_delay_us,
+            max_delay_us,
+        } = self.retry.backoff
+            && initial_delay_us > max_delay_us
+        {
+            return Err(ConfigError::InvalidBackoff);
+        }
+        if let Some(plausibility) = self.plausibility
+            && !plausibility.is_valid()
+        {
+            return Err(ConfigError::InvalidPlausibilityRange);
+        }
+        Ok(())
+    }
+
+    /// Check that the settings make sense together, and that the heater
+    /// settings respect the datasheet's constraints.
+    ///
+    /// As well as the checks made by [`Config::validate()`], this rejects
+    /// heater settings that can't work, or that would wear out the heater.
+    /// The constraints are the same for every part in the SHT4x line.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::*;
+    /// # use sht4x_rjw::error::ConfigError;
+    /// let heater = ReadingMode::HighPrecisionWithHeater(HeaterPower::High, HeaterDuration::Long);
+    /// let config = Config::builder()
+    ///     .reading_mode(heater)
+    ///     .rate_limit(Some(RateLimit {
+    ///         min_interval_us: 5_000_000,
+    ///         action: RateLimitAction::Wait,
+    ///     }))
+    ///     .build()?;
+    /// // One second of heating every five seconds is a 20% duty cycle.
+    /// assert_eq!(
+    ///     config.validate_heater(),
+    ///     Err(ConfigError::HeaterDutyCycleExceeded),
+    /// );
+    /// # Ok::<(), ConfigError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// As for [`Config::validate()`], as well as
+    /// [`ConfigError::HeaterAmbientLimitTooHigh`] if
+    /// [`Config::heater_max_ambient_celsius`] is above
+    /// [`HeaterStats::MAX_AMBIENT_CELSIUS`],
+    /// [`ConfigError::HeaterDutyCycleLimitTooHigh`] if
+    /// [`Config::heater_max_duty_cycle_percent`] is above
+    /// [`HeaterStats::MAX_DUTY_CYCLE_PERCENT`],
+    /// [`ConfigError::DelayShorterThanHeaterPulse`] if a heater reading mode
+    /// is used with a custom delay shorter than the heater pulse, and
+    /// [`ConfigError::HeaterDutyCycleExceeded`] if a heater reading mode is
+    /// used with a rate limit that allows the heater to run for more than
+    /// [`HeaterStats::MAX_DUTY_CYCLE_PERCENT`] of the time.
+    pub fn validate_heater(&self) -> Result<(), ConfigError> {
+        self.validate()?;
+        if let Some(celsius) = self.heater_max_ambient_celsius
+            && celsius > HeaterStats::MAX_AMBIENT_CELSIUS
+        {
+            return Err(ConfigError::HeaterAmbientLimitTooHigh(celsius));
+        }
+        if let Some(percent) = self.heater_max_duty_cycle_percent
+            && u64::from(percent) > HeaterStats::MAX_DUTY_CYCLE_PERCENT
+        {
+            return Err(ConfigError::HeaterDutyCycleLimitTooHigh(percent));
+        }
+        let ReadingMode::HighPrecisionWithHeater(_, duration) = self.reading_mode else {
+            return Ok(());
+        };
+        let pulse_us = duration.nominal_ms() * 1_000;
+        let delay_us = match (self.delay_mode, self.delay_table) {
+            (DelayMode::Custom { delay_us }, _) => Some(delay_us),
+            (DelayMode::Typical, Some(table)) => Some(table[self.reading_mode.index()]),
+            _ => None,
+        };
+        if delay_us.is_some_and(|delay_us| delay_us < pulse_us) {
+            return Err(ConfigError::DelayShorterThanHeaterPulse);
+        }
+        if let Some(limit) = self.rate_limit
+            && u64::from(limit.min_interval_us) * HeaterStats::MAX_DUTY_CYCLE_PERCENT
+                < u64::from(pulse_us) * 100
+        {
+            return Err(ConfigError::HeaterDutyCycleExceeded);
+        }
+        Ok(())
+    }
+
+    /// Length of the encoding produced by [`Config::to_bytes()`].
+    pub const ENCODED_LEN: usize = 97;
+
+    /// Encode the config as bytes, for storage in EEPROM or flash.
+    ///
+    /// ```
+    /// # use sht4x_rjw::common::{Config, DelayMode, ReadingMode};
+    /// let config = Config::new(ReadingMode::LowPrecision, DelayMode::Poll { interval_us: 200 });
+    /// let bytes = config.to_bytes();
+    /// assert_eq!(Config::from_bytes(&bytes), Some(config));
+    /// ```
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0; Self::ENCODED_LEN];
+        let (address_tag, address) = match self.address {
+            Address::Default0x44 => (0, 0x44),
+            Address::Alt0x45 => (1, 0x45),
+            Address::Alt0x46 => (2, 0x46),
+            Address::Custom(address) => (3, address),
+        };
+        let (delay_tag, delay_us) = match self.delay_mode {
+            DelayMode::Typical => (0, 0),
+            DelayMode::Maximum => (1, 0),
+            DelayMode::Poll { interval_us } => (2, interval_us),
+            DelayMode::Custom { delay_us } => (3, delay_us),
+        };
+        let (backoff_tag, first_us, second_us) = match self.retry.backoff {
+            Backoff::Fixed { delay_us } => (0, delay_us, 0),
+            Backoff::Exponential {
+                initial_delay_us,
+                max_delay_us,
+            } => (1, initial_delay_us, max_delay_us),
+        };
+        let (rate_limited, min_interval_us, action) = match self.rate_limit {
+            Some(RateLimit {
+                min_interval_us,
+                action,
+            }) => (true, min_interval_us, action as u8),
+            None => (false, 0, 0),
+        };
+        bytes[0] = CONFIG_FORMAT_VERSION;
+        bytes[1] = address_tag;
+        bytes[2] = address;
+        bytes[3] = self.reading_mode.command_byte();
+        bytes[4] = delay_tag;
+        bytes[5..9].copy_from_slice(&delay_us.to_le_bytes());
+        bytes[9] = self.delay_margin_percent;
+        bytes[10] = self.cache_max_age_us.is_some().into();
+        bytes[11..15].copy_from_slice(&self.cache_max_age_us.unwrap_or(0).to_le_bytes());
+        bytes[15..17].copy_from_slice(&self.temperature_offset_raw.to_le_bytes());
+        bytes[17..19].copy_from_slice(&self.humidity_offset_raw.to_le_bytes());
+        bytes[19..23].copy_from_slice(&self.retry.max_attempts.to_le_bytes());
+        bytes[23] = backoff_tag;
+        bytes[24..28].copy_from_slice(&first_us.to_le_bytes());
+        bytes[28..32].copy_from_slice(&second_us.to_le_bytes());
+        bytes[32] = rate_limited.into();
+        bytes[33..37].copy_from_slice(&min_interval_us.to_le_bytes());
+        bytes[37] = action;
+        bytes[38] = self.discard_first_measurement.into();
+        bytes[39] = self.expected_serial.is_some().into();
+        bytes[40..44].copy_from_slice(&self.expected_serial.unwrap_or(0).to_le_bytes());
+        bytes[44] = self.validate_crc.into();
+        bytes[45] = self.humidity_clamp as u8;
+        bytes[46] = self.heater_max_duty_cycle_percent.is_some().into();
+        bytes[47] = self.heater_max_duty_cycle_percent.unwrap_or(0);
+        bytes[48] = self.heater_max_ambient_celsius.is_some().into();
+        bytes[49] = self.heater_max_ambient_celsius.unwrap_or(0).to_le_bytes()[0];
+        if let Some(plausibility) = self.plausibility {
+            bytes[50] = 1;
+            bytes[51] = plausibility.min_celsius.to_le_bytes()[0];
+            bytes[52] = plausibility.max_celsius.to_le_bytes()[0];
+            bytes[53] = plausibility.min_percent_rh;
+            bytes[54] = plausibility.max_percent_rh;
+            bytes[55] = plausibility.max_celsius_step.is_some().into();
+            bytes[56] = plausibility.max_celsius_step.unwrap_or(0);
+            bytes[57] = plausibility.max_percent_rh_step.is_some().into();
+            bytes[58] = plausibility.max_percent_rh_step.unwrap_or(0);
+            bytes[59] = plausibility.action as u8;
+        }
+        if let Some(table) = self.delay_table {
+            bytes[60] = 1;
+            for (chunk, delay_us) in bytes[61..97].chunks_exact_mut(4).zip(table) {
+                chunk.copy_from_slice(&delay_us.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Decode a config encoded by [`Config::to_bytes()`].
+    ///
+    /// Returns `None` if the bytes were not produced by `to_bytes()` (for
+    /// instance, if they are erased flash or EEPROM). The decoded config is
+    /// not validated; use [`Config::validate()`] before applying it if the
+    /// storage may have been written by something else.
+    pub fn from_bytes(bytes: &[u8; Self::ENCODED_LEN]) -> Option<Self> {
+        if bytes[0] != CONFIG_FORMAT_VERSION {
+            return None;
+        }
+        let u32_at =
+            |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        let bool_at = |i: usize| match bytes[i] {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        };
+        let address = match (bytes[1], bytes[2]) {
+            (0, 0x44) => Address::Default0x44,
+            (1, 0x45) => Address::Alt0x45,
+            (2, 0x46) => Address::Alt0x46,
+            (3, address) => Address::Custom(address),
+            _ => return None,
+        };
+        let delay_mode = match bytes[4] {
+            0 => DelayMode::Typical,
+            1 => DelayMode::Maximum,
+            2 => DelayMode::Poll {
+                interval_us: u32_at(5),
+            },
+            3 => DelayMode::Custom {
+                delay_us: u32_at(5),
+            },
+            _ => return None,
+        };
+        let backoff = match bytes[23] {
+            0 => Backoff::Fixed {
+                delay_us: u32_at(24),
+            },
+            1 => Backoff::Exponential {
+                initial_delay_us: u32_at(24),
+                max_delay_us: u32_at(28),
+            },
+            _ => return None,
+        };
+        let action = match bytes[37] {
+            0 => RateLimitAction::Reject,
+            1 => RateLimitAction::Wait,
+            _ => return None,
+        };
+        let plausibility_action = match bytes[59] {
+            0 => PlausibilityAction::Error,
+            1 => PlausibilityAction::Flag,
+            _ => return None,
+        };
+        let max_celsius_step = bool_at(55)?.then_some(bytes[56]);
+        let max_percent_rh_step = bool_at(57)?.then_some(bytes[58]);
+        let plausibility = bool_at(50)?.then_some(Plausibility {
+            min_celsius: i8::from_le_bytes([bytes[51]]),
+            max_celsius: i8::from_le_bytes([bytes[52]]),
+            min_percent_rh: bytes[53],
+            max_percent_rh: bytes[54],
+            max_celsius_step,
+            max_percent_rh_step,
+            action: plausibility_action,
+        });
+        let mut delay_table = [0; 9];
+        for (i, delay_us) in delay_table.iter_mut().enumerate() {
+            *delay_us = u32_at(61 + i * 4);
+        }
+        let humidity_clamp = match bytes[45] {
+            0 => HumidityClamp::Clamp,
+            1 => HumidityClamp::DontClamp,
+            2 => HumidityClamp::ErrorOnOutOfRange,
+            _ => return None,
+        };
+        Some(Self {
+            address,
+            reading_mode: ReadingMode::try_from(bytes[3]).ok()?,
+            delay_mode,
+            delay_margin_percent: bytes[9],
+            cache_max_age_us: bool_at(10)?.then(|| u32_at(11)),
+            temperature_offset_raw: i16::from_le_bytes([bytes[15], bytes[16]]),
+            humidity_offset_raw: i16::from_le_bytes([bytes[17], bytes[18]]),
+            retry: RetryConfig::new(u32_at(19), backoff),
+            rate_limit: bool_at(32)?.then(|| RateLimit {
+                min_interval_us: u32_at(33),
+                action,
+            }),
+            discard_first_measurement: bool_at(38)?,
+            expected_serial: bool_at(39)?.then(|| u32_at(40)),
+            validate_crc: bool_at(44)?,
+            humidity_clamp,
+            heater_max_duty_cycle_percent: bool_at(46)?.then_some(bytes[47]),
+            heater_max_ambient_celsius: bool_at(48)?.then_some(i8::from_le_bytes([bytes[49]])),
+            plausibility,
+            delay_table: bool_at(60)?.then_some(delay_table),
+        })
+    }
+
+    /// Check the heater limits before a heater pulse of `reading_mode`.
+    ///
+    /// `latest` is the driver's most recent measurement, `now_us` the time
+    /// from the last clock passed to the driver, and `last_pulse` the time
+    /// and duration of the previous heater pulse.
+    pub(crate) fn check_heater_limits(
+        &self,
+        reading_mode: ReadingMode,
+        latest: Option<&Measurement>,
+        now_us: Option<u64>,
+        last_pulse: Option<(u64, HeaterDuration)>,
+    ) -> Result<(), HeaterLimit> {
+        if !matches!(reading_mode, ReadingMode::HighPrecisionWithHeater(..)) {
+            return Ok(());
+        }
+        if let (Some(max_celsius), Some(latest)) = (self.heater_max_ambient_celsius, latest) {
+            // raw = (°C + 45) * 65535 / 175, rounded down so that a reading
+            // of exactly the limit is allowed.
+            let max_raw = (i32::from(max_celsius) + 45) * 65_535 / 175;
+            if i32::from(latest.raw_temperature_reading()) > max_raw {
+                return Err(HeaterLimit::AmbientTemperature);
+            }
+        }
+        if let Some(percent) = self.heater_max_duty_cycle_percent {
+            if percent == 0 {
+                return Err(HeaterLimit::DutyCycle);
+            }
+            if let (Some(now_us), Some((pulse_at_us, duration))) = (now_us, last_pulse) {
+                let period_us = u64::from(duration.nominal_ms()) * 1_000 * 100 / u64::from(percent);
+                if now_us.saturating_sub(pulse_at_us) < period_us {
+                    return Err(HeaterLimit::DutyCycle);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Time the driver waits from sending the command for a measurement
+    /// until reading it, with the config's reading and delay modes.
+    ///
+    /// Unlike [`ReadingMode::measurement_duration()`], this includes
+    /// [`Config::delay_table`] and [`Config::delay_margin_percent`].
+    ///
+    /// ```
+    /// # use core::time::Duration;
+    /// # use sht4x_rjw::common::{Config, ReadingMode};
+    /// let config = Config::builder()
+    ///     .reading_mode(ReadingMode::MediumPrecision)
+    ///     .delay_margin_percent(10)
+    ///     .build()?;
+    /// assert_eq!(config.measurement_duration(), Duration::from_micros(4_070));
+    /// # Ok::<(), sht4x_rjw::error::ConfigError>(())
+    /// ```
+    pub fn measurement_duration(&self) -> core::time::Duration {
+        let us = self.delay_us(self.reading_mode, self.delay_mode);
+        core::time::Duration::from_micros(us.into())
+    }
+
+    /// Microsecond delay for `reading_mode` with `delay_mode`, including
+    /// [`Config::delay_table`] and [`Config::delay_margin_percent`] for
+    /// typical delays.
+    pub(crate) fn delay_us(&self, reading_mode: ReadingMode, delay_mode: DelayMode) -> u32 {
+        let us = delay_mode.us_for_reading_mode(reading_mode);
+        match delay_mode {
+            DelayMode::Typical => {
+                let us = match self.delay_table {
+                    Some(table) => table[reading_mode.index()],
+                    None => us,
+                };
+                let margin_us = u64::from(us) * u64::from(self.delay_margin_percent) / 100;
+                us.saturating_add(u32::try_from(margin_us).unwrap_or(u32::MAX))
+            }
+            _ => us,
+        }
+    }
+}
+
+/// Divide `n` by the positive `d`, rounding halves away from zero.
+const fn round_div(n: i64, d: i64) -> i64 {
+    if n < 0 {
+        (n - d / 2) / d
+    } else {
+        (n + d / 2) / d
+    }
+}
+
+/// Version byte at the start of [`Config::to_bytes()`], so that the format
+/// can be changed without misreading old data.
+const CONFIG_FORMAT_VERSION: u8 = 2;
+
+/// Builder for a [`Config`], created by [`Config::builder()`].
+///
+/// Each method sets the `Config` field of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Set [`Config::address`].
+    pub const fn address(mut self, address: Address) -> Self {
+        self.config.address = address;
+        self
+    }
+
+    /// Set [`Config::reading_mode`].
+    pub const fn reading_mode(mut self, reading_mode: ReadingMode) -> Self {
+        self.config.reading_mode = reading_mode;
+        self
+    }
+
+    /// Set [`Config::delay_mode`].
+    pub const fn delay_mode(mut self, delay_mode: DelayMode) -> Self {
+        self.config.delay_mode = delay_mode;
+        self
+    }
+
+    /// Set [`Config::delay_margin_percent`].
+    pub const fn delay_margin_percent(mut self, percent: u8) -> Self {
+        self.config.delay_margin_percent = percent;
+        self
+    }
+
+    /// Set [`Config::cache_max_age_us`].
+    pub const fn cache_max_age_us(mut self, max_age_us: Option<u32>) -> Self {
+        self.config.cache_max_age_us = max_age_us;
+        self
+    }
+
+    /// Set [`Config::temperature_offset_raw`].
+    pub const fn temperature_offset_raw(mut self, offset: i16) -> Self {
+        self.config.temperature_offset_raw = offset;
+        self
+    }
+
+    /// Set the temperature calibration offset in thousandths of a degree
+    /// Celsius, as for [`Config::set_temperature_offset_millicelsius()`].
+    pub const fn temperature_offset_millicelsius(mut self, millicelsius: i32) -> Self {
+        self.config
+            .set_temperature_offset_millicelsius(millicelsius);
+        self
+    }
+
+    /// Set [`Config::humidity_offset_raw`].
+    pub const fn humidity_offset_raw(mut self, offset: i16) -> Self {
+        self.config.humidity_offset_raw = offset;
+        self
+    }
+
+    /// Set [`Config::retry`].
+    pub const fn retry(mut self, retry: RetryConfig) -> Self {
+        self.config.retry = retry;
+        self
+    }
+
+    /// Set [`Config::rate_limit`].
+    pub const fn rate_limit(mut self, rate_limit: Option<RateLimit>) -> Self {
+        self.config.rate_limit = rate_limit;
+        self
+    }
+
+    /// Set [`Config::discard_first_measurement`].
+    pub const fn discard_first_measurement(mut self, discard: bool) -> Self {
+        self.config.discard_first_measurement = discard;
+        self
+    }
+
+    /// Set [`Config::expected_serial`].
+    pub const fn expected_serial(mut self, serial: Option<u32>) -> Self {
+        self.config.expected_serial = serial;
+        self
+    }
+
+    /// Set [`Config::validate_crc`].
+    pub const fn validate_crc(mut self, validate: bool) -> Self {
+        self.config.validate_crc = validate;
+        self
+    }
+
+    /// Set [`Config::humidity_clamp`].
+    pub const fn humidity_clamp(mut self, humidity_clamp: HumidityClamp) -> Self {
+        self.config.humidity_clamp = humidity_clamp;
+        self
+    }
+
+    /// Set [`Config::heater_max_duty_cycle_percent`].
+    pub const fn heater_max_duty_cycle_percent(mut self, percent: Option<u8>) -> Self {
+        self.config.heater_max_duty_cycle_percent = percent;
+        self
+    }
+
+    /// Set [`Config::heater_max_ambient_celsius`].
+    pub const fn heater_max_ambient_celsius(mut self, celsius: Option<i8>) -> Self {
+        self.config.heater_max_ambient_celsius = celsius;
+        self
+    }
+
+    /// Set [`Config::plausibility`].
+    pub const fn plausibility(mut self, plausibility: Option<Plausibility>) -> Self {
+        self.config.plausibility = plausibility;
+        self
+    }
+
+    /// Set [`Config::delay_table`].
+    pub const fn delay_table(mut self, delay_table: Option<[u32; 9]>) -> Self {
+        self.config.delay_table = delay_table;
+        self
+    }
+
+    /// Finish building, returning the `Config` if it is valid.
+    ///
+    /// # Errors
+    ///
+    /// As for [`Config::validate()`].
+    pub fn build(self) -> Result<Config, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Identifies one of the two sensors driven by a `PingPong` helper.
+///
+/// See [`blocking::PingPong`] or [`asynch::PingPong`].
+///
+/// [`blocking::PingPong`]: crate::blocking::PingPong
+/// [`asynch::PingPong`]: crate::asynch::PingPong
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PingPongSensor {
+    /// The first sensor passed to `PingPong::new()`.
+    First,
+    /// The second sensor passed to `PingPong::new()`.
+    Second,
+}
+
+impl PingPongSensor {
+    pub(crate) fn other(&self) -> Self {
+        match self {
+            PingPongSensor::First => PingPongSensor::Second,
+            PingPongSensor::Second => PingPongSensor::First,
+        }
+    }
+}
+
+/// Progress of a `PingPong` helper through its measurement sequence.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) enum PingPongState {
+    /// Neither sensor has been asked to measure.
+    Idle,
+    /// A previous sequence was interrupted, so either sensor may be busy.
+    Restart,
+    /// Both sensors are measuring, and `next` will be read first.
+    Running { next: PingPongSensor },
+}
+
+/// Snapshot of a driver's state, for debugging.
+///
+/// Returned by `dump_state()` on either driver, this gathers the driver's
+/// settings and the statistics it has collected since construction. It
+/// implements [`Display`](core::fmt::Display) and (with the `defmt` feature)
+/// `defmt::Format`, so it can be logged in one go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DriverState {
+    /// I2C address of the sensor.
+    pub address: u8,
+    /// The label given to the driver, if any.
+    pub label: Option<&'static str>,
+    /// The driver's current default settings.
+    pub config: Config,
+    /// The serial number last read from the sensor, if any.
+    pub serial_number: Option<u32>,
+    /// Number of measurements successfully read from the sensor.
+    pub measurements: u32,
+    /// Number of errors returned by driver methods.
+    pub errors: u32,
+    /// Heater use recorded by the driver.
+    pub heater_stats: HeaterStats,
+    /// The most recent error returned by a driver method, if any.
+    pub last_error: Option<Sht4xErrorKind>,
+}
+
+impl core::fmt::Display for DriverState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.label {
+            Some(label) => write!(f, "SHT4x {label} at {:#04X}: ", self.address)?,
+            None => write!(f, "SHT4x at {:#04X}: ", self.address)?,
+        }
+        match self.serial_number {
+            Some(serial) => write!(f, "serial {serial:#010X}, ")?,
+            None => write!(f, "serial unknown, ")?,
+        }
+        write!(
+            f,
+            "{:?} with {:?} delay, {} measurements, {} errors, {} heater pulses ({}ms)",
+            self.config.reading_mode,
+            self.config.delay_mode,
+            self.measurements,
+            self.errors,
+            self.heater_stats.total_pulses(),
+            self.heater_stats.on_time_ms,
+        )?;
+        match self.last_error {
+            Some(error) => write!(f, ", last error: {error}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Identifies a sensor in log messages by its label (if any) and address.
+#[cfg(feature = "defmt")]
+pub(crate) struct SensorId {
+    pub(crate) address: u8,
+    pub(crate) label: Option<&'static str>,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for SensorId {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self.label {
+            Some(label) => defmt::write!(fmt, "{=str} ({=u8:#02X})", label, self.address),
+            None => defmt::write!(fmt, "{=u8:#02X}", self.address),
+        }
+    }
+}
+
+/// Counters of driver activity, used to build a [`DriverState`].
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct DriverStats {
+    pub(crate) serial_number: Option<u32>,
+    pub(crate) measurements: u32,
+    pub(crate) errors: u32,
+    pub(crate) last_error: Option<Sht4xErrorKind>,
+}
+
+impl DriverStats {
+    /// Empty counters, as the `const` equivalent of `default()`.
+    pub(crate) const fn new() -> Self {
+        Self {
+            serial_number: None,
+            measurements: 0,
+            errors: 0,
+            last_error: None,
+        }
+    }
+
+    pub(crate) fn record_error<I>(&mut self, error: &Error<I>)
+    where
+        I: embedded_hal::i2c::Error,
+    {
+        self.errors = self.errors.saturating_add(1);
+        self.last_error = Some(error.kind());
+    }
+
+    pub(crate) fn record_measurement(&mut self) {
+        self.measurements = self.measurements.saturating_add(1);
+    }
+}
+
+/// The most recent measurement read by a driver, with the number of failed
+/// measurement attempts since.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct LatestMeasurement {
+    pub(crate) measurement: Option<Measurement>,
+    pub(crate) failures_since: u32,
+}
+
+impl LatestMeasurement {
+    pub(crate) const fn new() -> Self {
+        Self {
+            measurement: None,
+            failures_since: 0,
+        }
+    }
+
+    /// Record the outcome of reading a measurement.
+    pub(crate) fn record<E>(&mut self, result: &Result<Measurement, E>) {
+        match result {
+            Ok(measurement) => {
+                self.measurement = Some(*measurement);
+                self.failures_since = 0;
+            }
+            Err(_) => self.record_failure(),
+        }
+    }
+
+    /// Record a measurement attempt that failed before the result was read.
+    pub(crate) fn record_failure(&mut self) {
+        self.failures_since = self.failures_since.saturating_add(1);
+    }
+}
+
+/// A measurement retained by the driver for reuse, with the time at which
+/// it was taken.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) struct CachedMeasurement {
+    pub(crate) measurement: Measurement,
+    pub(crate) taken_at_us: u64,
+}
+
+impl CachedMeasurement {
+    /// Return the cached measurement if it is no older than `max_age_us`.
+    pub(crate) fn fresh(&self, now_us: u64, max_age_us: u32) -> Option<Measurement> {
+        let age = now_us.saturating_sub(self.taken_at_us);
+        (age <= u64::from(max_age_us)).then_some(self.measurement)
+    }
+}
+
+/// A temperature and humidity measurement from the sensor.
+///
+/// Users should use this struct's methods to convert the raw readings into
+/// recognisable units. (These methods are just wrappers around the functions
+/// provided in [`sht4x_rjw::conversions`]).
+///
+/// The "raw" values are also available via methods. They have been
+/// reconstructed as `u16`s from the bytes read from the sensor, after
+/// passing CRC validation, but have otherwise not been converted.
+///
+/// [`sht4x_rjw::conversions`]: crate::conversions
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Measurement {
+    /// The unconverted temperature value received from the sensor.
+    raw_temp: u16,
+    /// The unconverted humidity value received from the sensor.
+    raw_humidity: u16,
+    /// Whether [`Measurement::humidity()`] clamps, following
+    /// [`Config::humidity_clamp`]. Only read with the `float` feature.
+    #[cfg_attr(not(feature = "float"), allow(dead_code))]
+    clamp_humidity: bool,
+    /// Whether the reading was outside the config's [`Plausibility`] ranges.
+    implausible: bool,
+}
+
+impl Measurement {
+    pub(crate) fn from_raw(raw_temp: u16, raw_humidity: u16) -> Self {
+        Self {
+            raw_temp,
+            raw_humidity,
+            clamp_humidity: true,
+            implausible: false,
+        }
+    }
+
+    /// A measurement of `celsius` and `percent_rh`, for tests.
+    #[cfg(all(test, feature = "float"))]
+    pub(crate) fn from_celsius_and_percent_rh(celsius: f32, percent_rh: f32) -> Self {
+        Self::from_raw(
+            crate::conversions::celsius_to_raw(celsius),
+            crate::conversions::percent_rh_to_raw(percent_rh),
+        )
+    }
+
+    /// A measurement of `raw_temp` and `raw_humidity` derived from `like`,
+    /// such as by filtering or averaging, which keeps its humidity clamp
+    /// and plausibility flags.
+    pub(crate) fn from_raw_like(like: &Measurement, raw_temp: u16, raw_humidity: u16) -> Self {
+        Self {
+            raw_temp,
+            raw_humidity,
+            ..*like
+        }
+    }
+
+    /// Flag the measurement as implausible if `other` is, for a measurement
+    /// derived from both.
+    pub(crate) fn merge_flags(self, other: &Measurement) -> Self {
+        Self {
+            implausible: self.implausible || other.implausible,
+            ..self
+        }
+    }
+
+    /// Mark the measurement as outside the config's [`Plausibility`] ranges.
+    pub(crate) fn flag_implausible(self) -> Self {
+        Self {
+            implausible: true,
+            ..self
+        }
+    }
+
+    /// The raw humidity reading, limited to `0..=100`%RH if
+    /// [`Measurement::humidity()`] clamps.
+    fn clamped_raw_humidity(&self) -> u16 {
+        if self.clamp_humidity {
+            self.raw_humidity.clamp(3_146, 55_573)
+        } else {
+            self.raw_humidity
+        }
+    }
+
+    /// Follow `policy` in [`Measurement::humidity()`].
+    pub(crate) fn with_humidity_clamp(self, policy: HumidityClamp) -> Self {
+        Self {
+            clamp_humidity: policy != HumidityClamp::DontClamp,
+            ..self
+        }
+    }
+
+    /// Whether the raw humidity reading lies within `0..=100`%RH, checked
+    /// without floating-point arithmetic.
+    pub(crate) fn humidity_in_physical_range(&self) -> bool {
+        (3_146..=55_573).contains(&self.raw_humidity)
+    }
+
+    /// Add calibration offsets to the raw readings, saturating at the
+    /// limits of the sensor's range.
+    pub(crate) fn with_offsets(self, temp_offset: i16, humidity_offset: i16) -> Self {
+        Self::from_raw_like(
+            &self,
+            self.raw_temp.saturating_add_signed(temp_offset),
+            self.raw_humidity.saturating_add_signed(humidity_offset),
+        )
+    }
+
+    pub(crate) fn from_read_bytes<I>(sensor_data: Unvalidated) -> Result<Self, Error<I>>
+    where
+        I: embedded_hal::i2c::Error,
+    {
+        let [t0, t1, h0, h1] = sensor_data.try_get_bytes(
+            CrcFailureReason::TemperatureBytes,
+            CrcFailureReason::HumidityBytes,
+        )?;
+        Ok(Measurement::from_raw(
+            u16::from_be_bytes([t0, t1]),
+            u16::from_be_bytes([h0, h1]),
+        ))
+    }
+
+    /// Linearly interpolate between this measurement and `other`.
+    ///
+    /// `t` is the fraction of the way from `self` to `other`, and is clamped
+    /// to the range `0.0..=1.0`. The interpolation is performed on the raw
+    /// sensor readings, so the result can be converted with any of the
+    /// usual methods.
+    ///
+    /// ```
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # use sht4x_rjw::blocking::SHT4x;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// # let expectations = [
+    /// #   Transaction::write(0x44, vec![0xFD]),
+    /// #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   Transaction::write(0x44, vec![0xFD]),
+    /// #   Transaction::read(0x44, vec![0x12, 0x36, 0x55, 0x56, 0x76, 0x62]),
+    /// # ];
+    /// # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+    /// let before = sensor.measure(&mut delay)?;
+    /// let after = sensor.measure(&mut delay)?;
+    /// let midpoint = before.lerp(&after, 0.5);
+    /// assert_eq!(midpoint.raw_temperature_reading(), 0x1235);
+    /// assert_eq!(midpoint.raw_humidity_reading(), 0x5677);
+    /// # sensor.destroy().done();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "float")]
+    pub fn lerp(&self, other: &Measurement, t: f32) -> Measurement {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: u16, b: u16| {
+            let (a, b) = (f32::from(a), f32::from(b));
+            // The result lies between two u16 values, so the cast can't saturate.
+            (a + (b - a) * t + 0.5) as u16
+        };
+        Measurement::from_raw_like(
+            &self.merge_flags(other),
+            lerp(self.raw_temp, other.raw_temp),
+            lerp(self.raw_humidity, other.raw_humidity),
+        )
+    }
+
+    /// Linearly interpolate between this measurement and `other`, at
+    /// `numerator / denominator` of the way from `self` to `other`, using
+    /// only integer arithmetic.
+    ///
+    /// `numerator` must not be greater than `denominator`, which must not
+    /// be zero.
+    pub(crate) fn lerp_ratio(
+        &self,
+        other: &Measurement,
+        numerator: u64,
+        denominator: u64,
+    ) -> Measurement {
+        let lerp = |a: u16, b: u16| {
+            let delta = (i128::from(b) - i128::from(a)) * i128::from(numerator);
+            let offset = delta / i128::from(denominator);
+            (i128::from(a) + offset) as u16
+        };
+        Measurement::from_raw_like(
+            &self.merge_flags(other),
+            lerp(self.raw_temp, other.raw_temp),
+            lerp(self.raw_humidity, other.raw_humidity),
+        )
+    }
+
+    /// Attach the repeatability of `reading_mode` and the typical accuracy
+    /// of `variant` to this measurement.
+    ///
+    /// ```
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # use sht4x_rjw::blocking::SHT4x;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// # let expectations = [
+    /// #   Transaction::write(0x44, vec![0xE0]),
+    /// #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// # ];
+    /// # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+    /// use sht4x_rjw::common::{DelayMode, ReadingMode, SensorVariant};
+    ///
+    /// let mode = ReadingMode::LowPrecision;
+    /// let measurement = sensor
+    ///     .measure_with_settings(&mut delay, mode, DelayMode::Typical)?
+    ///     .with_uncertainty(mode, SensorVariant::Sht45);
+    /// assert_eq!(measurement.humidity_repeatability, 0.25);
+    /// assert_eq!(measurement.temperature_accuracy, 0.1);
+    /// # sensor.destroy().done();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "float")]
+    pub fn with_uncertainty(
+        self,
+        reading_mode: ReadingMode,
+        variant: SensorVariant,
+    ) -> MeasurementWithUncertainty {
+        let (temperature_repeatability, humidity_repeatability) = reading_mode.repeatability();
+        let (temperature_accuracy, humidity_accuracy) = variant.typical_accuracy();
+        MeasurementWithUncertainty {
+            measurement: self,
+            temperature_repeatability,
+            humidity_repeatability,
+            temperature_accuracy,
+            humidity_accuracy,
+        }
+    }
+
+    /// Convert the raw humidity reading to percent relative humidity.
+    ///
+    /// The result is clamped to `0.0..=100.0` unless the measurement was
+    /// read by a driver configured with [`HumidityClamp::DontClamp`].
+    #[cfg(feature = "float")]
+    pub fn humidity(&self) -> f32 {
+        if self.clamp_humidity {
+            crate::conversions::humidity_reading_to_percent_rh(self.raw_humidity)
+        } else {
+            self.humidity_unclamped()
+        }
+    }
+
+    /// Convert the raw humidity reading to percent relative humidity at the
+    /// ambient temperature, for a measurement read with the temperature
+    /// calibration offset `temperature_offset_raw` (normally
+    /// [`Config::temperature_offset_raw`]).
+    ///
+    /// Use this rather than [`Measurement::humidity()`] when the offset
+    /// corrects for the sensor being warmed by nearby components. See
+    /// [`humidity_compensated_percent_rh()`] for details.
+    ///
+    /// [`humidity_compensated_percent_rh()`]: crate::conversions::humidity_compensated_percent_rh
+    #[cfg(feature = "float")]
+    pub fn humidity_compensated(&self, temperature_offset_raw: i16) -> f32 {
+        crate::conversions::humidity_compensated_percent_rh(
+            self.raw_temp,
+            self.raw_humidity,
+            temperature_offset_raw,
+            Default::default(),
+        )
+    }
+
+    /// Convert the raw humidity reading to percent relative humidity,
+    /// without clamping to `0.0..=100.0`.
+    ///
+    /// See [`humidity_reading_to_percent_rh_unclamped()`] for details.
+    ///
+    /// [`humidity_reading_to_percent_rh_unclamped()`]: crate::conversions::humidity_reading_to_percent_rh_unclamped
+    #[cfg(feature = "float")]
+    pub fn humidity_unclamped(&self) -> f32 {
+        crate::conversions::humidity_reading_to_percent_rh_unclamped(self.raw_humidity)
+    }
+
+    /// Whether the humidity reading is outside the physical range of 0%RH to
+    /// 100%RH, and so has been clamped by [`Measurement::humidity()`].
+    ///
+    /// Occasional out-of-range readings are expected at the extremes, but
+    /// persistent ones may indicate sensor drift.
+    #[cfg(feature = "float")]
+    pub fn is_out_of_physical_range(&self) -> bool {
+        !(0.0..=100.0).contains(&self.humidity_unclamped())
+    }
+
+    /// Convert the raw temperature reading to celsius.
+    #[cfg(feature = "float")]
+    pub fn celsius(&self) -> f32 {
+        crate::conversions::temperature_reading_to_celsius(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to celsius, less the self-heating
+    /// estimated by `model` for measurements taken in `reading_mode` every
+    /// `interval_us` microseconds.
+    ///
+    /// See [`SelfHeating`] for the model and its limitations.
+    #[cfg(feature = "float")]
+    pub fn celsius_compensated(
+        &self,
+        model: &SelfHeating,
+        reading_mode: ReadingMode,
+        interval_us: u32,
+    ) -> f32 {
+        self.celsius() - model.offset_celsius(reading_mode, interval_us)
+    }
+
+    /// Convert the raw temperature reading to fahrenheit.
+    #[cfg(feature = "float")]
+    pub fn fahrenheit(&self) -> f32 {
+        crate::conversions::temperature_reading_to_fahrenheit(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to kelvin.
+    #[cfg(feature = "float")]
+    pub fn kelvin(&self) -> f32 {
+        crate::conversions::temperature_reading_to_kelvin(self.raw_temp)
+    }
+
+    /// Calculate the absolute humidity in grams of water vapour per cubic
+    /// metre of air.
+    ///
+    /// See [`absolute_humidity_g_m3()`](crate::conversions::absolute_humidity_g_m3)
+    /// for details.
+    #[cfg(feature = "float")]
+    pub fn absolute_humidity_g_m3(&self) -> f32 {
+        crate::conversions::absolute_humidity_g_m3(
+            self.raw_temp,
+            self.raw_humidity,
+            Default::default(),
+        )
+    }
+
+    /// Convert the raw temperature reading to thousandths of a degree
+    /// celsius, using only integer arithmetic.
+    ///
+    /// See [`temperature_reading_to_millicelsius()`] for details.
+    ///
+    /// [`temperature_reading_to_millicelsius()`]: crate::conversions::temperature_reading_to_millicelsius
+    pub fn millicelsius(&self) -> i32 {
+        crate::conversions::temperature_reading_to_millicelsius(self.raw_temp)
+    }
+
+    /// Convert the raw humidity reading to thousandths of a percent relative
+    /// humidity, using only integer arithmetic.
+    ///
+    /// See [`humidity_reading_to_milli_percent_rh()`] for details.
+    ///
+    /// [`humidity_reading_to_milli_percent_rh()`]: crate::conversions::humidity_reading_to_milli_percent_rh
+    pub fn milli_percent_rh(&self) -> u32 {
+        crate::conversions::humidity_reading_to_milli_percent_rh(self.raw_humidity)
+    }
+
+    /// Convert the raw temperature reading to tenths of a degree celsius,
+    /// rounded to the nearest tenth, for displays and register maps.
+    pub fn decicelsius(&self) -> i16 {
+        crate::conversions::temperature_reading_to_decicelsius(self.raw_temp)
+    }
+
+    /// Convert the raw humidity reading to tenths of a percent relative
+    /// humidity, rounded to the nearest tenth, for displays and register maps.
+    pub fn deci_percent_rh(&self) -> u16 {
+        crate::conversions::humidity_reading_to_deci_percent_rh(self.raw_humidity)
+    }
+
+    /// Calculate the dew point in thousandths of a degree celsius from a
+    /// lookup table, using only integer arithmetic.
+    ///
+    /// See the [`lookup`](crate::conversions::lookup) module for details.
+    pub fn dew_point_millicelsius<const N: usize>(
+        &self,
+        table: &crate::conversions::lookup::DewPointTable<N>,
+    ) -> Option<i32> {
+        table.dew_point_millicelsius(self.raw_temp, self.raw_humidity)
+    }
+
+    /// Calculate the dew point in celsius.
+    ///
+    /// Returns `None` if the relative humidity is zero. See
+    /// [`dew_point_celsius()`](crate::conversions::dew_point_celsius) for
+    /// details.
+    #[cfg(feature = "float")]
+    pub fn dew_point(&self) -> Option<f32> {
+        crate::conversions::dew_point_celsius(self.raw_temp, self.raw_humidity, Default::default())
+    }
+
+    /// How far `surface_celsius` is above the dew point, in °C.
+    ///
+    /// A surface at or below the dew point of the surrounding air collects
+    /// condensation, so a negative or zero margin means condensation is
+    /// forming. Returns `None` if the relative humidity is zero, where
+    /// condensation can't form.
+    #[cfg(feature = "float")]
+    pub fn condensation_margin(&self, surface_celsius: f32) -> Option<f32> {
+        self.dew_point()
+            .map(|dew_point| surface_celsius - dew_point)
+    }
+
+    /// Whether a surface at `surface_celsius` is within `safety_margin` °C
+    /// of the dew point, and so at risk of condensation.
+    ///
+    /// A safety margin of 2°C to 3°C allows for sensor accuracy and for
+    /// the surface being colder in places than where it is measured.
+    ///
+    /// ```
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # use sht4x_rjw::blocking::SHT4x;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// # let expectations = [
+    /// #   Transaction::write(0x44, vec![0xFD]),
+    /// #   Transaction::read(0x44, vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+    /// # ];
+    /// # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+    /// // 25°C and 56.5%RH, so a dew point of about 15.7°C.
+    /// let measurement = sensor.measure(&mut delay)?;
+    /// let window_celsius = 17.0;
+    /// assert!(measurement.condensation_risk(window_celsius, 2.0));
+    /// assert!(!measurement.condensation_risk(window_celsius, 0.5));
+    /// # sensor.destroy().done();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "float")]
+    pub fn condensation_risk(&self, surface_celsius: f32, safety_margin: f32) -> bool {
+        self.condensation_margin(surface_celsius)
+            .is_some_and(|margin| margin < safety_margin)
+    }
+
+    /// Calculate the humidex, as used by Environment Canada.
+    ///
+    /// See [`humidex()`](crate::conversions::humidex) for details.
+    #[cfg(feature = "float")]
+    pub fn humidex(&self) -> f32 {
+        crate::conversions::humidex(self.raw_temp, self.raw_humidity, Default::default())
+    }
+
+    /// Calculate the Australian apparent temperature in celsius, given the
+    /// wind speed in metres per second, or `None` for still air.
+    ///
+    /// See [`apparent_temperature_celsius()`] for details.
+    ///
+    /// [`apparent_temperature_celsius()`]: crate::conversions::apparent_temperature_celsius
+    #[cfg(feature = "float")]
+    pub fn apparent_temperature_celsius(&self, wind_m_s: Option<f32>) -> f32 {
+        crate::conversions::apparent_temperature_celsius(self.raw_temp, self.raw_humidity, wind_m_s)
+    }
+
+    /// Estimate the indoor wet-bulb globe temperature (WBGT) in celsius, for
+    /// heat-stress monitoring.
+    ///
+    /// See [`wbgt_indoor_celsius()`](crate::conversions::wbgt_indoor_celsius)
+    /// for details and limitations.
+    #[cfg(feature = "float")]
+    pub fn wbgt_indoor_celsius(&self) -> f32 {
+        crate::conversions::wbgt_indoor_celsius(self.raw_temp, self.raw_humidity)
+    }
+
+    /// Calculate the vapour pressure deficit (VPD) in kilopascals.
+    ///
+    /// See [`vapour_pressure_deficit_kpa()`](crate::conversions::vapour_pressure_deficit_kpa)
+    /// for details.
+    #[cfg(feature = "float")]
+    pub fn vpd_kpa(&self) -> f32 {
+        crate::conversions::vapour_pressure_deficit_kpa(
+            self.raw_temp,
+            self.raw_humidity,
+            Default::default(),
+        )
+    }
+
+    /// Calculate the mixing ratio in kg/kg, given the air pressure in
+    /// pascals.
+    ///
+    /// See [`mixing_ratio()`](crate::conversions::mixing_ratio) for details.
+    #[cfg(feature = "float")]
+    pub fn mixing_ratio(&self, pressure_pa: f32) -> f32 {
+        crate::conversions::mixing_ratio(
+            self.raw_temp,
+            self.raw_humidity,
+            pressure_pa,
+            Default::default(),
+        )
+    }
+
+    /// Calculate the specific humidity in kg/kg, given the air pressure in
+    /// pascals.
+    ///
+    /// See [`specific_humidity()`](crate::conversions::specific_humidity)
+    /// for details.
+    #[cfg(feature = "float")]
+    pub fn specific_humidity(&self, pressure_pa: f32) -> f32 {
+        crate::conversions::specific_humidity(
+            self.raw_temp,
+            self.raw_humidity,
+            pressure_pa,
+            Default::default(),
+        )
+    }
+
+    /// The unconverted temperature reading from the sensor as a 16-bit integer.
+    pub fn raw_temperature_reading(&self) -> u16 {
+        self.raw_temp
+    }
+
+    /// The unconverted humidity reading from the sensor as a 16-bit integer.
+    pub fn raw_humidity_reading(&self) -> u16 {
+        self.raw_humidity
+    }
+
+    /// Whether the reading was within the config's [`Plausibility`] ranges
+    /// when it was taken.
+    ///
+    /// This is always `true` unless the ranges are set with
+    /// [`PlausibilityAction::Flag`], as otherwise implausible readings are
+    /// returned as errors.
+    pub fn is_plausible(&self) -> bool {
+        !self.implausible
+    }
+}
+
+/// Double-precision conversion methods.
+#[cfg(feature = "float")]
+impl Measurement {
+    /// Convert the raw humidity reading to percent relative humidity.
+    pub fn humidity_f64(&self) -> f64 {
+        crate::conversions::f64::humidity_reading_to_percent_rh(self.raw_humidity)
+    }
+
+    /// Convert the raw temperature reading to celsius.
+    pub fn celsius_f64(&self) -> f64 {
+        crate::conversions::f64::temperature_reading_to_celsius(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to fahrenheit.
+    pub fn fahrenheit_f64(&self) -> f64 {
+        crate::conversions::f64::temperature_reading_to_fahrenheit(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to kelvin.
+    pub fn kelvin_f64(&self) -> f64 {
+        crate::conversions::f64::temperature_reading_to_kelvin(self.raw_temp)
+    }
+}
+
+/// Half-precision conversion methods.
+#[cfg(feature = "half")]
+impl Measurement {
+    /// Convert the raw humidity reading to percent relative humidity.
+    pub fn humidity_f16(&self) -> half::f16 {
+        crate::conversions::f16::humidity_reading_to_percent_rh(self.raw_humidity)
+    }
+
+    /// Convert the raw temperature reading to celsius.
+    pub fn celsius_f16(&self) -> half::f16 {
+        crate::conversions::f16::temperature_reading_to_celsius(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to fahrenheit.
+    pub fn fahrenheit_f16(&self) -> half::f16 {
+        crate::conversions::f16::temperature_reading_to_fahrenheit(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to kelvin.
+    pub fn kelvin_f16(&self) -> half::f16 {
+        crate::conversions::f16::temperature_reading_to_kelvin(self.raw_temp)
+    }
+}
+
+/// Unit-safe conversion methods, using [`uom`](https://docs.rs/uom).
+#[cfg(feature = "uom")]
+impl Measurement {
+    /// The temperature as a `uom` quantity.
+    ///
+    /// ```
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # use sht4x_rjw::blocking::SHT4x;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// # let expectations = [
+    /// #   Transaction::write(0x44, vec![0xFD]),
+    /// #   Transaction::read(0x44, vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+    /// # ];
+    /// # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+    /// use uom::si::ratio::percent;
+    /// use uom::si::thermodynamic_temperature::{degree_celsius, degree_fahrenheit};
+    ///
+    /// let measurement = sensor.measure(&mut delay)?;
+    /// let temperature = measurement.temperature();
+    /// assert!((temperature.get::<degree_celsius>() - 25.0).abs() < 0.01);
+    /// assert!((temperature.get::<degree_fahrenheit>() - 77.0).abs() < 0.01);
+    /// assert!((measurement.relative_humidity().get::<percent>() - 56.5).abs() < 0.01);
+    /// # sensor.destroy().done();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn temperature(&self) -> uom::si::f32::ThermodynamicTemperature {
+        use uom::si::thermodynamic_temperature::kelvin;
+        uom::si::f32::ThermodynamicTemperature::new::<kelvin>(self.kelvin())
+    }
+
+    /// The relative humidity as a `uom` ratio.
+    pub fn relative_humidity(&self) -> uom::si::f32::Ratio {
+        use uom::si::ratio::percent;
+        uom::si::f32::Ratio::new::<percent>(self.humidity())
+    }
+}
+
+/// Fixed-point conversion methods.
+#[cfg(feature = "fixed")]
+impl Measurement {
+    /// Convert the raw humidity reading to percent relative humidity.
+    pub fn humidity_fixed_point(&self) -> I16F16 {
+        crate::conversions::fixed_point::humidity_reading_to_percent_rh(self.raw_humidity)
+    }
+
+    /// Convert the raw temperature reading to celsius.
+    pub fn celsius_fixed_point(&self) -> I16F16 {
+        crate::conversions::fixed_point::temperature_reading_to_celsius(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to fahrenheit.
+    pub fn fahrenheit_fixed_point(&self) -> I16F16 {
+        crate::conversions::fixed_point::temperature_reading_to_fahrenheit(self.raw_temp)
+    }
+
+    /// Convert the raw temperature reading to kelvin.
+    pub fn kelvin_fixed_point(&self) -> I16F16 {
+        crate::conversions::fixed_point::temperature_reading_to_kelvin(self.raw_temp)
+    }
+
+    /// Calculate the dew point in celsius, without floating-point arithmetic.
+    ///
+    /// Returns `None` if the relative humidity is zero. See
+    /// [`dew_point_celsius()`](crate::conversions::fixed_point::dew_point_celsius)
+    /// for details.
+    pub fn dew_point_fixed_point(&self) -> Option<I16F16> {
+        crate::conversions::fixed_point::dew_point_celsius(self.raw_temp, self.raw_humidity)
+    }
+}
+
+/// A [`Measurement`] with the sensor's noise and accuracy figures attached.
+///
+/// Created by [`Measurement::with_uncertainty()`], this gives downstream
+/// filters and analytics the measurement noise for the reading mode and
+/// sensor variant used, rather than guessed values. All uncertainties are
+/// in °C and %RH.
+#[cfg(feature = "float")]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MeasurementWithUncertainty {
+    /// The measurement itself.
+    pub measurement: Measurement,
+    /// Repeatability (3σ) of the temperature reading, in °C.
+    pub temperature_repeatability: f32,
+    /// Repeatability (3σ) of the humidity reading, in %RH.
+    pub humidity_repeatability: f32,
+    /// Typical accuracy of the temperature reading, in ±°C.
+    pub temperature_accuracy: f32,
+    /// Typical accuracy of the humidity reading, in ±%RH.
+    pub humidity_accuracy: f32,
+}
+
+#[cfg(feature = "float")]
+impl MeasurementWithUncertainty {
+    /// Standard deviation (1σ) of the temperature reading noise, in °C.
+    ///
+    /// Square this for the measurement noise variance of a Kalman filter.
+    pub fn temperature_std_dev(&self) -> f32 {
+        self.temperature_repeatability / 3.0
+    }
+
+    /// Standard deviation (1σ) of the humidity reading noise, in %RH.
+    pub fn humidity_std_dev(&self) -> f32 {
+        self.humidity_repeatability / 3.0
+    }
+}
+
+/// A measurement with the time at which it was taken.
+///
+/// Created by `measure_timestamped()` on either driver. It converts into a
+/// `(timestamp, measurement)` pair, as used by the
+/// [`resample`](crate::resample) module.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimedMeasurement {
+    /// The measurement.
+    pub measurement: Measurement,
+    /// The time at which the measurement was read from the sensor, in
+    /// microseconds, as given by the [`Clock`](crate::clock::Clock).
+    pub taken_at_us: u64,
+}
+
+impl From<TimedMeasurement> for (u64, Measurement) {
+    fn from(timed: TimedMeasurement) -> Self {
+        (timed.taken_at_us, timed.measurement)
+    }
+}
+
+/// A heater measurement followed by an ambient one.
+///
+/// Created by `measure_heated_then_ambient()` on either driver.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HeaterCycle {
+    /// The measurement taken at the end of the heater pulse. Its temperature
+    /// reflects the heater, not the surroundings.
+    pub heated: Measurement,
+    /// The high-precision measurement taken after the settle period.
+    pub ambient: Measurement,
+}
+
+/// The mean of several consecutive measurements, with their spread.
+///
+/// Created by `measure_averaged()` on either driver. Averaging reduces the
+/// measurement noise, improving the effective resolution for signals that
+/// change slowly compared with the time taken to measure.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AveragedMeasurement {
+    /// The mean of the measurements, rounded to the nearest raw reading.
+    pub measurement: Measurement,
+    /// The number of measurements averaged.
+    pub samples: u16,
+    /// Difference between the highest and lowest raw temperature readings.
+    pub raw_temperature_spread: u16,
+    /// Difference between the highest and lowest raw humidity readings.
+    pub raw_humidity_spread: u16,
+}
+
+/// The minimum, maximum and mean of a burst of measurements.
+///
+/// Created by `measure_summary()` on either driver, for dataloggers that
+/// record a summary of each measurement period rather than every reading.
+///
+/// Temperature and humidity are summarised separately, so `min` holds the
+/// lowest temperature and the lowest humidity, which may come from
+/// different measurements. The same is true of `max`.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MeasurementSummary {
+    /// The lowest temperature and humidity readings.
+    pub min: Measurement,
+    /// The highest temperature and humidity readings.
+    pub max: Measurement,
+    /// The mean of the measurements, rounded to the nearest raw reading.
+    pub mean: Measurement,
+    /// The number of measurements summarised.
+    pub samples: u16,
+}
+
+/// Accumulates a burst of measurements in integer space.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct BurstAccumulator {
+    temp_sum: u32,
+    humidity_sum: u32,
+    samples: u16,
+    min: Option<(u16, u16)>,
+    max: Option<(u16, u16)>,
+    /// The most recent measurement, flagged implausible if any added was.
+    last: Option<Measurement>,
+}
+
+impl BurstAccumulator {
+    /// Add a measurement. At most `u16::MAX` may be added, so that the sums
+    /// can't overflow.
+    pub(crate) fn add(&mut self, measurement: Measurement) {
+        let (temp, humidity) = (measurement.raw_temp, measurement.raw_humidity);
+        self.temp_sum += u32::from(temp);
+        self.humidity_sum += u32::from(humidity);
+        self.samples += 1;
+        let (min_temp, min_humidity) = self.min.unwrap_or((temp, humidity));
+        self.min = Some((min_temp.min(temp), min_humidity.min(humidity)));
+        let (max_temp, max_humidity) = self.max.unwrap_or((temp, humidity));
+        self.max = Some((max_temp.max(temp), max_humidity.max(humidity)));
+        self.last = Some(match self.last {
+            Some(last) => measurement.merge_flags(&last),
+            None => measurement,
+        });
+    }
+
+    /// The mean, minimum and maximum of the measurements added, or `None`
+    /// if there were none.
+    pub(crate) fn finish(&self) -> Option<(Measurement, Measurement, Measurement)> {
+        let count = u32::from(self.samples);
+        let mean = |sum: u32| ((sum + count / 2) / count) as u16;
+        let (min, max, last) = (self.min?, self.max?, self.last?);
+        Some((
+            Measurement::from_raw_like(&last, mean(self.temp_sum), mean(self.humidity_sum)),
+            Measurement::from_raw_like(&last, min.0, min.1),
+            Measurement::from_raw_like(&last, max.0, max.1),
+        ))
+    }
+
+    /// The minimum, maximum and mean of the measurements added, or `None`
+    /// if there were none.
+    pub(crate) fn summary(&self) -> Option<MeasurementSummary> {
+        let (mean, min, max) = self.finish()?;
+        Some(MeasurementSummary {
+            min,
+            max,
+            mean,
+            samples: self.samples,
+        })
+    }
+
+    /// The mean and spread of the measurements added, or `None` if there
+    /// were none.
+    pub(crate) fn averaged(&self) -> Option<AveragedMeasurement> {
+        let (measurement, min, max) = self.finish()?;
+        Some(AveragedMeasurement {
+            measurement,
+            samples: self.samples,
+            raw_temperature_spread: max.raw_temp - min.raw_temp,
+            raw_humidity_spread: max.raw_humidity - min.raw_humidity,
+        })
+    }
+}
+
+/// Reconstruct the 32-bit serial number after validating the received CRCs.
 pub(crate) fn serial_number_from_read_bytes<I>(sensor_data: Unvalidated) -> Result<u32, Error<I>>
 where
     I: embedded_hal::i2c::Error,
@@ -364,3 +2932,46 @@ where
     )?;
     Ok(u32::from_be_bytes(bytes))
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Config, HeaterDuration, HeaterPower, Measurement, ReadingMode};
+    use crate::error::ConfigError;
+
+    #[test]
+    fn offsets_saturate() {
+        let m = Measurement::from_raw(10, 65_530).with_offsets(-20, 20);
+        assert_eq!(m.raw_temperature_reading(), 0);
+        assert_eq!(m.raw_humidity_reading(), u16::MAX);
+    }
+
+    #[test]
+    fn validate_heater_rejects_limits_beyond_the_datasheet() {
+        let heater = ReadingMode::HighPrecisionWithHeater(HeaterPower::Low, HeaterDuration::Short);
+        let config = Config::builder()
+            .reading_mode(heater)
+            .heater_max_ambient_celsius(Some(65))
+            .heater_max_duty_cycle_percent(Some(10))
+            .build()
+            .unwrap();
+        assert_eq!(config.validate_heater(), Ok(()));
+
+        let too_hot = Config {
+            heater_max_ambient_celsius: Some(66),
+            ..config
+        };
+        assert_eq!(
+            too_hot.validate_heater(),
+            Err(ConfigError::HeaterAmbientLimitTooHigh(66))
+        );
+
+        let too_often = Config {
+            heater_max_duty_cycle_percent: Some(11),
+            ..config
+        };
+        assert_eq!(
+            too_often.validate_heater(),
+            Err(ConfigError::HeaterDutyCycleLimitTooHigh(11))
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Compact encoding of measurement series for constrained links.
+//!
+//! Consecutive measurements from the sensor usually differ by only a few
+//! ticks, so storing each full 4-byte raw reading wastes most of the bits.
+//! The [`Encoder`] instead stores the difference between each reading and
+//! the one before it, [zig-zag encoded] so that small negative changes are
+//! small positive numbers, and then writes each difference as a [LEB128]
+//! variable-length integer.
+//!
+//! A slowly-changing series typically encodes to 2 bytes per measurement
+//! (one for each of temperature and humidity), against the 4 bytes needed
+//! for the raw readings or the 6 bytes read from the sensor. The worst case
+//! is 6 bytes per measurement, for a change of more than 8,191 ticks in
+//! both temperature and humidity.
+//!
+//! The first measurement is encoded as its difference from a raw reading of
+//! zero, so a complete series can be decoded without any extra header. Note
+//! that the encoded bytes do not record the number of measurements, so if
+//! you are packing several series into one transmission you will need to
+//! frame them yourself.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # use sht4x_rjw::blocking::SHT4x;
+//! # fn main() -> anyhow::Result<()> {
+//! # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x35, 0x06, 0x56, 0x76, 0x62]),
+//! # ];
+//! # let i2c = Mock::new(&expectations);
+//! use sht4x_rjw::compression::{Decoder, Encoder};
+//!
+//! let mut sensor = SHT4x::new(i2c, Default::default());
+//! let mut buffer = [0u8; 32];
+//! let mut encoder = Encoder::new(&mut buffer);
+//! encoder.push(&sensor.measure(&mut delay)?)?;
+//! encoder.push(&sensor.measure(&mut delay)?)?;
+//! // The first measurement is stored in full, the second as a small change.
+//! let encoded = encoder.finish();
+//! assert_eq!(encoded.len(), 7);
+//!
+//! let decoded: Result<Vec<_>, _> = Decoder::new(encoded).collect();
+//! assert_eq!(decoded?[1].raw_temperature_reading(), 0x1235);
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [zig-zag encoded]: https://protobuf.dev/programming-guides/encoding/#signed-ints
+//! [LEB128]: https://en.wikipedia.org/wiki/LEB128
+use crate::common::Measurement;
+
+/// The largest number of bytes a single encoded measurement may occupy.
+///
+/// Each 16-bit difference needs at most three 7-bit groups.
+pub const MAX_ENCODED_MEASUREMENT_LEN: usize = 6;
+
+/// Errors that may occur when encoding or decoding a measurement series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CompressionError {
+    /// The output buffer has no room for the next measurement.
+    ///
+    /// When encoding, the buffer is left containing all of the previously
+    /// pushed measurements, so it is safe to transmit what has been encoded
+    /// so far and start a new series.
+    BufferTooSmall,
+    /// The encoded input ended partway through a measurement, or contained
+    /// a value too large to be a 16-bit difference.
+    MalformedInput,
+}
+
+impl core::fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::BufferTooSmall => write!(f, "buffer too small for encoded measurement"),
+            Self::MalformedInput => write!(f, "malformed encoded measurement series"),
+        }
+    }
+}
+
+impl core::error::Error for CompressionError {}
+
+/// Delta encoder that writes measurements into a caller-provided buffer.
+///
+/// See the [module documentation](crate::compression) for details of the
+/// encoding.
+pub struct Encoder<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+    previous: (u16, u16),
+}
+
+impl<'a> Encoder<'a> {
+    /// Start a new series, to be written to the start of `buffer`.
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            position: 0,
+            previous: (0, 0),
+        }
+    }
+
+    /// Append a measurement to the series.
+    ///
+    /// # Errors
+    ///
+    /// [`CompressionError::BufferTooSmall`] is returned if the encoded
+    /// measurement does not fit in the remaining space in the buffer. In
+    /// this case nothing is written and the encoder is left unchanged.
+    pub fn push(&mut self, measurement: &Measurement) -> Result<(), CompressionError> {
+        let temp = measurement.raw_temperature_reading();
+        let humidity = measurement.raw_humidity_reading();
+
+        let mut scratch = [0u8; MAX_ENCODED_MEASUREMENT_LEN];
+        let mut len = write_varint(&mut scratch, zigzag(temp.wrapping_sub(self.previous.0)));
+        len += write_varint(
+            &mut scratch[len..],
+            zigzag(humidity.wrapping_sub(self.previous.1)),
+        );
+
+        let destination = self
+            .buffer
+            .get_mut(self.position..self.position + len)
+            .ok_or(CompressionError::BufferTooSmall)?;
+        destination.copy_from_slice(&scratch[..len]);
+        self.position += len;
+        self.previous = (temp, humidity);
+        Ok(())
+    }
+
+    /// Number of bytes written to the buffer so far.
+    pub fn len(&self) -> usize {
+        self.position
+    }
+
+    /// Whether no measurements have been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.position == 0
+    }
+
+    /// Finish the series and return the encoded bytes.
+    pub fn finish(self) -> &'a [u8] {
+        &self.buffer[..self.position]
+    }
+}
+
+/// Decoder for a series written by [`Encoder`].
+///
+/// The decoder is an iterator over the measurements in the series, which
+/// stops after the last complete measurement or after the first error.
+pub struct Decoder<'a> {
+    input: &'a [u8],
+    previous: (u16, u16),
+    failed: bool,
+}
+
+impl<'a> Decoder<'a> {
+    /// Decode the series contained in `input`.
+    pub fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            previous: (0, 0),
+            failed: false,
+        }
+    }
+
+    /// Decode the whole series into `output`, returning the number of
+    /// measurements written.
+    ///
+    /// # Errors
+    ///
+    /// [`CompressionError::BufferTooSmall`] is returned if `output` cannot
+    /// hold every measurement in the series, and
+    /// [`CompressionError::MalformedInput`] if the series is corrupt.
+    pub fn decode_into(self, output: &mut [Measurement]) -> Result<usize, CompressionError> {
+        let mut count = 0;
+        for measurement in self {
+            let slot = output
+                .get_mut(count)
+                .ok_or(CompressionError::BufferTooSmall)?;
+            *slot = measurement?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn read_delta(&mut self) -> Result<u16, CompressionError> {
+        let (value, len) = read_varint(self.input)?;
+        self.input = &self.input[len..];
+        Ok(unzigzag(value))
+    }
+}
+
+impl Iterator for Decoder<'_> {
+    type Item = Result<Measurement, CompressionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() || self.failed {
+            return None;
+        }
+        let deltas = self
+            .read_delta()
+            .and_then(|temp| Ok((temp, self.read_delta()?)));
+        match deltas {
+            Ok((temp, humidity)) => {
+                let temp = self.previous.0.wrapping_add(temp);
+                let humidity = self.previous.1.wrapping_add(humidity);
+                self.previous = (temp, humidity);
+                Some(Ok(Measurement::from_raw(temp, humidity)))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Map a wrapping 16-bit difference onto an unsigned value so that small
+/// changes in either direction produce small numbers.
+fn zigzag(delta: u16) -> u16 {
+    let delta = delta as i16;
+    ((delta << 1) ^ (delta >> 15)) as u16
+}
+
+/// Reverse [`zigzag`].
+fn unzigzag(value: u16) -> u16 {
+    (value >> 1) ^ (value & 1).wrapping_neg()
+}
+
+/// Write `value` as LEB128 into `out`, returning the number of bytes used.
+///
+/// `out` must have space for at least 3 bytes.
+fn write_varint(out: &mut [u8], mut value: u16) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[len] = byte;
+            return len + 1;
+        }
+        out[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Read a LEB128 value from the start of `input`, returning the value and
+/// the number of bytes consumed.
+fn read_varint(input: &[u8]) -> Result<(u16, usize), CompressionError> {
+    let mut value: u32 = 0;
+    for (index, byte) in input.iter().take(3).enumerate() {
+        value |= u32::from(byte & 0x7F) << (7 * index);
+        if byte & 0x80 == 0 {
+            let value = u16::try_from(value).map_err(|_| CompressionError::MalformedInput)?;
+            return Ok((value, index + 1));
+        }
+    }
+    Err(CompressionError::MalformedInput)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CompressionError, Decoder, Encoder};
+    use crate::common::Measurement;
+
+    #[test]
+    fn round_trip_extremes() {
+        let series = [
+            Measurement::from_raw(0x6666, 0x8000),
+            Measurement::from_raw(0x6665, 0x8003),
+            Measurement::from_raw(0xFFFF, 0x0000),
+            Measurement::from_raw(0x0000, 0xFFFF),
+        ];
+        let mut buffer = [0u8; 4 * super::MAX_ENCODED_MEASUREMENT_LEN];
+        let mut encoder = Encoder::new(&mut buffer);
+        for m in &series {
+            encoder.push(m).unwrap();
+        }
+        let encoded = encoder.finish();
+
+        let mut decoded = [Measurement::from_raw(0, 0); 4];
+        assert_eq!(Decoder::new(encoded).decode_into(&mut decoded), Ok(4));
+        for (original, decoded) in series.iter().zip(decoded) {
+            assert_eq!(
+                original.raw_temperature_reading(),
+                decoded.raw_temperature_reading()
+            );
+            assert_eq!(
+                original.raw_humidity_reading(),
+                decoded.raw_humidity_reading()
+            );
+        }
+    }
+
+    #[test]
+    fn small_changes_take_two_bytes() {
+        let mut buffer = [0u8; 16];
+        let mut encoder = Encoder::new(&mut buffer);
+        encoder
+            .push(&Measurement::from_raw(0x6666, 0x8000))
+            .unwrap();
+        let first = encoder.len();
+        encoder
+            .push(&Measurement::from_raw(0x6660, 0x8010))
+            .unwrap();
+        assert_eq!(encoder.len() - first, 2);
+    }
+
+    #[test]
+    fn full_buffer_is_left_intact() {
+        let mut buffer = [0u8; 3];
+        let mut encoder = Encoder::new(&mut buffer);
+        encoder.push(&Measurement::from_raw(1, 2)).unwrap();
+        assert_eq!(
+            encoder.push(&Measurement::from_raw(0xFFFF, 0xFFFF)),
+            Err(CompressionError::BufferTooSmall)
+        );
+        assert_eq!(encoder.finish(), &[2, 4]);
+    }
+
+    #[test]
+    fn truncated_input_is_rejected() {
+        let mut decoder = Decoder::new(&[0x02, 0x80]);
+        assert_eq!(
+            decoder.next().map(|r| r.err()),
+            Some(Some(CompressionError::MalformedInput))
+        );
+        assert!(decoder.next().is_none());
+    }
+}
@@ -1,4 +1,5 @@
-//! Sensor readings to celsius, fahrenheit and percent relative humidity.
+//! Sensor readings to celsius, fahrenheit, kelvin and percent relative
+//! humidity, and the quantities derived from them.
 //!
 //! These functions are used by the methods on [`Measurement`] but are
 //! provided here should you need to use them. (Note that the raw sensor
@@ -7,11 +8,28 @@
 //! All conversion formulas can be found in section 4.6 of the [datasheet].
 //!
 //! The conversions in the root of this module work with and return `f32`s.
+//! Double-precision versions are in the [`f64`](mod@f64) submodule.
+//! Half-precision versions are in the `f16` submodule, with the `half`
+//! feature, and versions generic over the float type are in the `generic`
+//! submodule, with the `num-traits` feature.
 //! If you prefer to work with fixed- rather than floating-point numbers,
 //! use the `fixed` feature, the [`fixed_point`] submodule, and the
 //! corresponding `*_fixed_point` methods on [`Measurement`].
 //!
+//! The derived quantities (dew point, humidex, vapour pressure deficit and
+//! so on) need exponentials and logarithms, which `core` doesn't provide.
+//! By default these are computed with small built-in approximations, accurate
+//! to about one part in a million, so the crate builds on any `no_std`
+//! target without a maths library. With the `libm` feature, the [`libm`]
+//! crate is used instead, for results that match other software exactly.
+//! With the `micromath` feature, the [`micromath`] crate's faster and
+//! smaller approximations are used. These are much less accurate: the dew
+//! point, for instance, may be out by as much as 1.5°C. If both features
+//! are enabled, `libm` is used.
+//!
 //! [`Measurement`]: crate::common::Measurement
+//! [`libm`]: https://docs.rs/libm
+//! [`micromath`]: https://docs.rs/micromath
 //! [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
 
 /// Convert the raw humidity reading to percent relative humidity.
@@ -19,24 +37,586 @@
 /// The output value is clamped to the range `0.0..=100.0`, as suggested
 /// in the first note in section 4.6 of the datasheet. ("Non-physical"
 /// humidity values may be produced "at the measurement boundaries".)
+#[cfg(feature = "float")]
 pub fn humidity_reading_to_percent_rh(reading: u16) -> f32 {
+    humidity_reading_to_percent_rh_unclamped(reading).clamp(0.0, 100.0)
+}
+
+/// Convert the raw humidity reading to percent relative humidity, without
+/// clamping.
+///
+/// The result ranges from −6%RH to 119%RH. Values outside `0.0..=100.0`
+/// are not physically possible, but Sensirion note that they may be
+/// produced at the measurement boundaries, and persistent out-of-range
+/// values can indicate sensor drift or contamination.
+#[cfg(feature = "float")]
+pub fn humidity_reading_to_percent_rh_unclamped(reading: u16) -> f32 {
     let s_rh: f32 = reading.into();
-    let converted = -6.0 + 125.0 * (s_rh / 65_535.0);
-    converted.clamp(0.0, 100.0)
+    -6.0 + 125.0 * (s_rh / 65_535.0)
 }
 
 /// Convert the raw temperature reading to celsius.
+#[cfg(feature = "float")]
 pub fn temperature_reading_to_celsius(reading: u16) -> f32 {
     let s_t: f32 = reading.into();
     -45.0 + 175.0 * (s_t / 65_535.0)
 }
 
 /// Convert the raw temperature reading to fahrenheit.
+#[cfg(feature = "float")]
 pub fn temperature_reading_to_fahrenheit(reading: u16) -> f32 {
     let s_t: f32 = reading.into();
     -49.0 + 315.0 * (s_t / 65_535.0)
 }
 
+/// Convert the raw temperature reading to kelvin.
+#[cfg(feature = "float")]
+pub fn temperature_reading_to_kelvin(reading: u16) -> f32 {
+    let s_t: f32 = reading.into();
+    228.15 + 175.0 * (s_t / 65_535.0)
+}
+
+/// Convert a temperature in celsius to the nearest raw sensor reading.
+///
+/// This is the inverse of [`temperature_reading_to_celsius()`], for building
+/// simulated sensor responses or comparing against thresholds in the raw
+/// integer domain. Temperatures outside the sensor's range of −45°C to
+/// 130°C saturate at the ends of the range.
+#[cfg(feature = "float")]
+pub fn celsius_to_raw(celsius: f32) -> u16 {
+    // Float-to-int casts saturate, so out-of-range values are clamped.
+    ((celsius + 45.0) / 175.0 * 65_535.0 + 0.5) as u16
+}
+
+/// Convert a relative humidity in percent to the nearest raw sensor
+/// reading.
+///
+/// This is the inverse of [`humidity_reading_to_percent_rh()`], except
+/// that values outside `0.0..=100.0` are not clamped, as the sensor's raw
+/// range extends from −6%RH to 119%RH. Values outside that saturate at the
+/// ends of the range.
+#[cfg(feature = "float")]
+pub fn percent_rh_to_raw(percent_rh: f32) -> u16 {
+    // Float-to-int casts saturate, so out-of-range values are clamped.
+    ((percent_rh + 6.0) / 125.0 * 65_535.0 + 0.5) as u16
+}
+
+/// Convert the raw temperature reading to thousandths of a degree celsius,
+/// using only integer arithmetic.
+///
+/// This gives the same results as Sensirion's reference embedded driver,
+/// which approximates `175 / 65535` as `21875 / 8192`.
+pub fn temperature_reading_to_millicelsius(reading: u16) -> i32 {
+    ((21_875 * i32::from(reading)) >> 13) - 45_000
+}
+
+/// Convert the raw humidity reading to thousandths of a percent relative
+/// humidity, using only integer arithmetic.
+///
+/// This gives the same results as Sensirion's reference embedded driver,
+/// which approximates `125 / 65535` as `15625 / 8192`, except that the
+/// output is clamped to the range `0..=100_000`, as with
+/// [`humidity_reading_to_percent_rh()`].
+pub fn humidity_reading_to_milli_percent_rh(reading: u16) -> u32 {
+    let converted = ((15_625 * i32::from(reading)) >> 13) - 6_000;
+    // The clamp ensures the cast can't wrap.
+    converted.clamp(0, 100_000) as u32
+}
+
+/// Convert the raw temperature reading to tenths of a degree celsius,
+/// rounded to the nearest tenth, using only integer arithmetic.
+pub fn temperature_reading_to_decicelsius(reading: u16) -> i16 {
+    let milli = temperature_reading_to_millicelsius(reading);
+    // Division truncates towards zero, so round halves away from zero.
+    let rounded = if milli < 0 { milli - 50 } else { milli + 50 } / 100;
+    // The result lies within -450..=1300.
+    rounded as i16
+}
+
+/// Convert the raw humidity reading to tenths of a percent relative
+/// humidity, rounded to the nearest tenth, using only integer arithmetic.
+///
+/// The output is clamped to the range `0..=1000`.
+pub fn humidity_reading_to_deci_percent_rh(reading: u16) -> u16 {
+    let milli = humidity_reading_to_milli_percent_rh(reading);
+    // The result lies within 0..=1000.
+    ((milli + 50) / 100) as u16
+}
+
+/// Calculate the absolute humidity in grams of water vapour per cubic metre
+/// of air from raw temperature and humidity readings.
+///
+/// The saturation vapour pressure is found with `formula`, and converted
+/// to a density with the ideal gas law. Both readings are converted here
+/// rather than passed in already converted, to avoid rounding them twice.
+#[cfg(feature = "float")]
+pub fn absolute_humidity_g_m3(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    formula: SaturationFormula,
+) -> f32 {
+    let t = temperature_reading_to_celsius(temperature_reading);
+    let rh = humidity_reading_to_percent_rh(humidity_reading);
+    let saturation = formula.saturation_vapour_pressure_hpa(t);
+    // 216.7 is the ratio of the molar mass of water to the gas constant,
+    // scaled for hPa and g/m³.
+    216.7 * (rh / 100.0) * saturation / (273.15 + t)
+}
+
+/// Calculate the dew point in celsius from raw temperature and humidity
+/// readings.
+///
+/// This is the temperature at which the air's vapour pressure would be the
+/// saturation vapour pressure given by `formula`. Returns `None` if the
+/// relative humidity is zero, where the dew point is undefined.
+#[cfg(feature = "float")]
+pub fn dew_point_celsius(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    formula: SaturationFormula,
+) -> Option<f32> {
+    let e = vapour_pressure_pa(temperature_reading, humidity_reading, formula) / 100.0;
+    (e > 0.0).then(|| formula.saturation_temperature(e))
+}
+
+/// Calculate the relative humidity at the ambient temperature from raw
+/// temperature and humidity readings taken with the temperature calibration
+/// offset `temperature_offset_raw` already applied.
+///
+/// Relative humidity is relative to the saturation vapour pressure at the
+/// sensor's own temperature. When the sensor is warmed by nearby components,
+/// and its temperature is corrected with an offset, the humidity must be
+/// corrected too: the vapour pressure is the same, but the saturation vapour
+/// pressure is that at the corrected temperature, found with `formula`.
+/// This follows Sensirion's application note on temperature compensation.
+/// The result is clamped to `0.0..=100.0`.
+///
+/// ```
+/// # use sht4x_rjw::conversions::*;
+/// let (t, h) = (celsius_to_raw(22.0), percent_rh_to_raw(40.0));
+/// // The sensor was 3°C warmer than the air, so the air is more humid.
+/// let offset = -1_123;
+/// let humidity = humidity_compensated_percent_rh(t, h, offset, SaturationFormula::Magnus);
+/// assert!(humidity > 47.0 && humidity < 48.0);
+/// ```
+#[cfg(feature = "float")]
+pub fn humidity_compensated_percent_rh(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    temperature_offset_raw: i16,
+    formula: SaturationFormula,
+) -> f32 {
+    let sensor_reading = (i32::from(temperature_reading) - i32::from(temperature_offset_raw))
+        .clamp(0, i32::from(u16::MAX)) as u16;
+    let sensor =
+        formula.saturation_vapour_pressure_hpa(temperature_reading_to_celsius(sensor_reading));
+    let ambient =
+        formula.saturation_vapour_pressure_hpa(temperature_reading_to_celsius(temperature_reading));
+    let rh = humidity_reading_to_percent_rh_unclamped(humidity_reading);
+    (rh * sensor / ambient).clamp(0.0, 100.0)
+}
+
+/// Calculate the humidex from raw temperature and humidity readings.
+///
+/// This follows the Environment Canada formula, which finds the vapour
+/// pressure from the dew point:
+///
+/// > H = T + 0.5555 × (6.11 × e^(5417.7530 × (1/273.16 − 1/(273.15 + Td))) − 10)
+///
+/// `formula` is used to find the dew point. The humidex is a dimensionless
+/// number, but is reported as if it were a temperature in celsius. When the
+/// relative humidity is zero, the vapour pressure is taken to be zero.
+#[cfg(feature = "float")]
+pub fn humidex(temperature_reading: u16, humidity_reading: u16, formula: SaturationFormula) -> f32 {
+    let t = temperature_reading_to_celsius(temperature_reading);
+    let vapour_pressure = dew_point_celsius(temperature_reading, humidity_reading, formula)
+        .map_or(0.0, |td| {
+            6.11 * exp(5417.753 * (1.0 / 273.16 - 1.0 / (273.15 + td)))
+        });
+    t + 0.5555 * (vapour_pressure - 10.0)
+}
+
+/// Calculate the apparent temperature in celsius from raw temperature and
+/// humidity readings and an optional wind speed, in metres per second.
+///
+/// This is the Australian Bureau of Meteorology's apparent temperature
+/// (without solar radiation), from Steadman (1994):
+///
+/// > AT = T + 0.33 × e − 0.70 × ws − 4.00
+///
+/// where `e` is the vapour pressure in hPa, found with the Bureau's own
+/// saturation formula. A wind speed of `None` is treated as still air,
+/// which suits indoor use.
+#[cfg(feature = "float")]
+pub fn apparent_temperature_celsius(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    wind_m_s: Option<f32>,
+) -> f32 {
+    let t = temperature_reading_to_celsius(temperature_reading);
+    let e = bureau_vapour_pressure_hpa(temperature_reading, humidity_reading);
+    t + 0.33 * e - 0.70 * wind_m_s.unwrap_or(0.0) - 4.00
+}
+
+/// Estimate the wet-bulb globe temperature (WBGT) in celsius from raw
+/// temperature and humidity readings.
+///
+/// This is the Australian Bureau of Meteorology's approximation for
+/// moderate indoor conditions, with no direct sun and light air movement:
+///
+/// > WBGT = 0.567 × T + 0.393 × e + 3.94
+///
+/// where `e` is the vapour pressure in hPa. It is only an estimate, as a
+/// true WBGT needs globe and natural wet-bulb thermometers, so shouldn't be
+/// relied on alone where heat stress is a safety concern.
+#[cfg(feature = "float")]
+pub fn wbgt_indoor_celsius(temperature_reading: u16, humidity_reading: u16) -> f32 {
+    let t = temperature_reading_to_celsius(temperature_reading);
+    let e = bureau_vapour_pressure_hpa(temperature_reading, humidity_reading);
+    0.567 * t + 0.393 * e + 3.94
+}
+
+/// Vapour pressure in hPa, using the saturation formula of the Australian
+/// Bureau of Meteorology's apparent temperature and WBGT formulas.
+#[cfg(feature = "float")]
+fn bureau_vapour_pressure_hpa(temperature_reading: u16, humidity_reading: u16) -> f32 {
+    let t = temperature_reading_to_celsius(temperature_reading);
+    let rh = humidity_reading_to_percent_rh(humidity_reading);
+    rh / 100.0 * 6.105 * exp(17.27 * t / (237.7 + t))
+}
+
+/// Calculate the vapour pressure deficit in kilopascals from raw temperature
+/// and humidity readings.
+///
+/// This is the difference between the saturation vapour pressure (from
+/// `formula`) and the actual vapour pressure of the air, and is the usual
+/// control variable for greenhouse humidity.
+#[cfg(feature = "float")]
+pub fn vapour_pressure_deficit_kpa(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    formula: SaturationFormula,
+) -> f32 {
+    let t = temperature_reading_to_celsius(temperature_reading);
+    let rh = humidity_reading_to_percent_rh(humidity_reading);
+    // Divide by 10 to convert hPa to kPa.
+    formula.saturation_vapour_pressure_hpa(t) * (1.0 - rh / 100.0) / 10.0
+}
+
+/// Calculate the mixing ratio, in kilograms of water vapour per kilogram of
+/// dry air, from raw temperature and humidity readings and the air pressure
+/// in pascals.
+///
+/// The SHT4x doesn't measure pressure, so `pressure_pa` must come from
+/// another sensor. The vapour pressure is found with `formula`.
+#[cfg(feature = "float")]
+pub fn mixing_ratio(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    pressure_pa: f32,
+    formula: SaturationFormula,
+) -> f32 {
+    let e = vapour_pressure_pa(temperature_reading, humidity_reading, formula);
+    EPSILON * e / (pressure_pa - e)
+}
+
+/// Calculate the specific humidity, in kilograms of water vapour per
+/// kilogram of moist air, from raw temperature and humidity readings and
+/// the air pressure in pascals.
+///
+/// The SHT4x doesn't measure pressure, so `pressure_pa` must come from
+/// another sensor. The vapour pressure is found with `formula`.
+#[cfg(feature = "float")]
+pub fn specific_humidity(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    pressure_pa: f32,
+    formula: SaturationFormula,
+) -> f32 {
+    let e = vapour_pressure_pa(temperature_reading, humidity_reading, formula);
+    EPSILON * e / (pressure_pa - (1.0 - EPSILON) * e)
+}
+
+/// Ratio of the molar masses of water and dry air.
+#[cfg(feature = "float")]
+const EPSILON: f32 = 0.622;
+
+/// Actual vapour pressure of the air in pascals.
+#[cfg(feature = "float")]
+fn vapour_pressure_pa(
+    temperature_reading: u16,
+    humidity_reading: u16,
+    formula: SaturationFormula,
+) -> f32 {
+    let t = temperature_reading_to_celsius(temperature_reading);
+    let rh = humidity_reading_to_percent_rh(humidity_reading);
+    // rh is in percent and the saturation pressure in hPa, so the factors of
+    // 100 cancel out.
+    rh * formula.saturation_vapour_pressure_hpa(t)
+}
+
+/// Formula for the saturation vapour pressure over water, used by the
+/// derived-quantity conversions.
+///
+/// The formulas agree to within about 0.3% between 0°C and 50°C, but differ
+/// more at the extremes of the sensor's range. Pick the one used by your
+/// reference instruments so that results can be compared directly. The
+/// `Measurement` methods for derived quantities use the default, Magnus.
+#[cfg(feature = "float")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SaturationFormula {
+    /// The Magnus formula with the coefficients used by Sensirion:
+    /// 6.112 × e^(17.62 × T / (243.12 + T)).
+    #[default]
+    Magnus,
+    /// The Arden Buck equation (1996):
+    /// 6.1121 × e^((18.678 − T / 234.5) × T / (257.14 + T)).
+    ArdenBuck,
+    /// The Tetens equation: 6.1078 × e^(17.27 × T / (237.3 + T)).
+    Tetens,
+}
+
+#[cfg(feature = "float")]
+impl SaturationFormula {
+    /// Saturation vapour pressure over water in hPa at `celsius`.
+    pub fn saturation_vapour_pressure_hpa(&self, celsius: f32) -> f32 {
+        let t = celsius;
+        match self {
+            SaturationFormula::Magnus => 6.112 * exp(17.62 * t / (243.12 + t)),
+            SaturationFormula::ArdenBuck => 6.1121 * exp((18.678 - t / 234.5) * t / (257.14 + t)),
+            SaturationFormula::Tetens => 6.1078 * exp(17.27 * t / (237.3 + t)),
+        }
+    }
+
+    /// The temperature in celsius at which the saturation vapour pressure is
+    /// `hpa`, the inverse of [`saturation_vapour_pressure_hpa()`].
+    ///
+    /// [`saturation_vapour_pressure_hpa()`]: SaturationFormula::saturation_vapour_pressure_hpa
+    fn saturation_temperature(&self, hpa: f32) -> f32 {
+        // The Magnus-form equations invert directly.
+        let magnus = |a: f32, b: f32, c: f32| {
+            let gamma = ln(hpa / a);
+            c * gamma / (b - gamma)
+        };
+        match self {
+            SaturationFormula::Magnus => magnus(6.112, 17.62, 243.12),
+            SaturationFormula::Tetens => magnus(6.1078, 17.27, 237.3),
+            SaturationFormula::ArdenBuck => {
+                // Solve for the exponent with Newton's method, starting from
+                // the Magnus result, which is already close.
+                let target = ln(hpa / 6.1121);
+                let mut t = magnus(6.112, 17.62, 243.12);
+                for _ in 0..3 {
+                    let d = 257.14 + t;
+                    let f = (18.678 - t / 234.5) * t / d - target;
+                    let slope =
+                        ((18.678 - 2.0 * t / 234.5) * d - (18.678 - t / 234.5) * t) / (d * d);
+                    t -= f / slope;
+                }
+                t
+            }
+        }
+    }
+}
+
+/// Natural logarithm of a positive, normal `x`, from `libm`.
+#[cfg(feature = "libm")]
+pub(crate) fn ln(x: f32) -> f32 {
+    libm::logf(x)
+}
+
+/// `e^x`, from `libm`.
+#[cfg(feature = "libm")]
+pub(crate) fn exp(x: f32) -> f32 {
+    libm::expf(x)
+}
+
+/// Natural logarithm of a positive, normal `x`, from `micromath`.
+#[cfg(all(feature = "micromath", not(feature = "libm")))]
+pub(crate) fn ln(x: f32) -> f32 {
+    micromath::F32(x).ln().0
+}
+
+/// `e^x`, from `micromath`.
+#[cfg(all(feature = "micromath", not(feature = "libm")))]
+pub(crate) fn exp(x: f32) -> f32 {
+    micromath::F32(x).exp().0
+}
+
+/// Natural logarithm of a positive, normal `x`, as `core` doesn't provide
+/// `f32::ln()` without `std`.
+///
+/// `x` is split into `m × 2^k` with `m` in `1..2` using its bits, and
+/// `ln(m)` computed from the series `2 × atanh((m - 1) / (m + 1))`.
+#[cfg(all(feature = "float", not(any(feature = "libm", feature = "micromath"))))]
+pub(crate) fn ln(x: f32) -> f32 {
+    let bits = x.to_bits();
+    let k = ((bits >> 23) & 0xFF) as i32 - 127;
+    let m = f32::from_bits((bits & 0x007F_FFFF) | 0x3F80_0000);
+    let z = (m - 1.0) / (m + 1.0);
+    let z2 = z * z;
+    let series = [11.0, 9.0, 7.0, 5.0, 3.0]
+        .into_iter()
+        .fold(0.0, |acc, n| (acc + 1.0 / n) * z2);
+    k as f32 * core::f32::consts::LN_2 + 2.0 * z * (1.0 + series)
+}
+
+/// `e^x` for the moderate values of `x` needed by the conversions here,
+/// as `core` doesn't provide `f32::exp()` without `std`.
+///
+/// `x` is split into `k × ln 2 + r` with `|r| ≤ ln 2 / 2`, so the result is
+/// `2^k × e^r`, with `e^r` from its Taylor series.
+#[cfg(all(feature = "float", not(any(feature = "libm", feature = "micromath"))))]
+pub(crate) fn exp(x: f32) -> f32 {
+    let scaled = x * core::f32::consts::LOG2_E;
+    let k = if scaled < 0.0 {
+        scaled - 0.5
+    } else {
+        scaled + 0.5
+    } as i32;
+    let r = x - k as f32 * core::f32::consts::LN_2;
+    let e_r = [6.0, 5.0, 4.0, 3.0, 2.0, 1.0]
+        .into_iter()
+        .fold(1.0, |acc, n| 1.0 + r * acc / n);
+    // 2^k, built directly from the exponent bits.
+    let two_k = f32::from_bits(((k + 127) as u32) << 23);
+    two_k * e_r
+}
+
+/// Double-precision conversions from sensor readings.
+///
+/// The functions in this module are the same as those in the parent
+/// `conversions` module, except that they return `f64`s. These are intended
+/// for host-side use, where double precision costs nothing and the results
+/// are passed on to scientific tooling. On microcontrollers without a
+/// double-precision FPU, prefer the `f32` conversions.
+#[cfg(feature = "float")]
+pub mod f64 {
+    /// Convert the raw humidity reading to percent relative humidity.
+    ///
+    /// The output value is clamped to the range `0.0..=100.0`, as suggested
+    /// in the first note in section 4.6 of the datasheet.
+    pub fn humidity_reading_to_percent_rh(reading: u16) -> f64 {
+        let s_rh: f64 = reading.into();
+        let converted = -6.0 + 125.0 * (s_rh / 65_535.0);
+        converted.clamp(0.0, 100.0)
+    }
+
+    /// Convert the raw temperature reading to celsius.
+    pub fn temperature_reading_to_celsius(reading: u16) -> f64 {
+        let s_t: f64 = reading.into();
+        -45.0 + 175.0 * (s_t / 65_535.0)
+    }
+
+    /// Convert the raw temperature reading to fahrenheit.
+    pub fn temperature_reading_to_fahrenheit(reading: u16) -> f64 {
+        let s_t: f64 = reading.into();
+        -49.0 + 315.0 * (s_t / 65_535.0)
+    }
+
+    /// Convert the raw temperature reading to kelvin.
+    pub fn temperature_reading_to_kelvin(reading: u16) -> f64 {
+        let s_t: f64 = reading.into();
+        228.15 + 175.0 * (s_t / 65_535.0)
+    }
+}
+
+/// Half-precision conversions from sensor readings.
+///
+/// The functions in this module are the same as those in the parent
+/// `conversions` module, except that they return [`half::f16`]s, for
+/// storing long histories of measurements compactly or for models that use
+/// 16-bit floats natively. The conversion is performed in `f32` and rounded
+/// to `f16` at the end, so near room temperature the result is within about
+/// 0.008°C or 0.03%RH of the `f32` conversion.
+///
+/// [`half::f16`]: ::half::f16
+#[cfg(feature = "half")]
+pub mod f16 {
+    use ::half::f16;
+
+    /// Convert the raw humidity reading to percent relative humidity.
+    ///
+    /// The output value is clamped to the range `0.0..=100.0`, as suggested
+    /// in the first note in section 4.6 of the datasheet.
+    pub fn humidity_reading_to_percent_rh(reading: u16) -> f16 {
+        f16::from_f32(super::humidity_reading_to_percent_rh(reading))
+    }
+
+    /// Convert the raw temperature reading to celsius.
+    pub fn temperature_reading_to_celsius(reading: u16) -> f16 {
+        f16::from_f32(super::temperature_reading_to_celsius(reading))
+    }
+
+    /// Convert the raw temperature reading to fahrenheit.
+    pub fn temperature_reading_to_fahrenheit(reading: u16) -> f16 {
+        f16::from_f32(super::temperature_reading_to_fahrenheit(reading))
+    }
+
+    /// Convert the raw temperature reading to kelvin.
+    pub fn temperature_reading_to_kelvin(reading: u16) -> f16 {
+        f16::from_f32(super::temperature_reading_to_kelvin(reading))
+    }
+}
+
+/// Conversions generic over the floating-point type.
+///
+/// The functions in this module are the same as those in the parent
+/// `conversions` module, except that they return any type implementing
+/// [`num_traits::Float`], such as `f32`, `f64` or a software-float type, so
+/// that the output type can be chosen uniformly.
+///
+/// ```
+/// use sht4x_rjw::conversions::generic::temperature_reading_to_celsius;
+///
+/// let single: f32 = temperature_reading_to_celsius(0x6666);
+/// let double: f64 = temperature_reading_to_celsius(0x6666);
+/// assert!((f64::from(single) - double).abs() < 1e-5);
+/// ```
+#[cfg(feature = "num-traits")]
+pub mod generic {
+    use num_traits::Float;
+
+    /// Convert `value` to `F`.
+    ///
+    /// All values passed are small and exactly representable in any float
+    /// type, so the conversion can't fail.
+    fn float<F: Float>(value: impl num_traits::ToPrimitive) -> F {
+        F::from(value).expect("value is representable")
+    }
+
+    /// The raw reading as a fraction of `u16::MAX`, between 0 and 1.
+    fn fraction<F: Float>(reading: u16) -> F {
+        float::<F>(reading) / float(u16::MAX)
+    }
+
+    /// Convert the raw humidity reading to percent relative humidity.
+    ///
+    /// The output value is clamped to the range `0..=100`, as suggested in
+    /// the first note in section 4.6 of the datasheet.
+    pub fn humidity_reading_to_percent_rh<F: Float>(reading: u16) -> F {
+        let converted = float::<F>(-6) + float::<F>(125) * fraction(reading);
+        converted.max(F::zero()).min(float(100))
+    }
+
+    /// Convert the raw temperature reading to celsius.
+    pub fn temperature_reading_to_celsius<F: Float>(reading: u16) -> F {
+        float::<F>(-45) + float::<F>(175) * fraction(reading)
+    }
+
+    /// Convert the raw temperature reading to fahrenheit.
+    pub fn temperature_reading_to_fahrenheit<F: Float>(reading: u16) -> F {
+        float::<F>(-49) + float::<F>(315) * fraction(reading)
+    }
+
+    /// Convert the raw temperature reading to kelvin.
+    pub fn temperature_reading_to_kelvin<F: Float>(reading: u16) -> F {
+        // 228.15 isn't exactly representable, so build it from integers.
+        float::<F>(22_815) / float(100) + float::<F>(175) * fraction(reading)
+    }
+}
+
 /// Fixed-point numeric conversions from sensor readings.
 ///
 /// The functions in this module are the same as those in the parent
@@ -50,7 +630,7 @@ pub fn temperature_reading_to_fahrenheit(reading: u16) -> f32 {
 /// with `f32`s.
 #[cfg(feature = "fixed")]
 pub mod fixed_point {
-    use fixed::types::{I16F16, U16F16};
+    use fixed::types::{I16F16, I32F32, U16F16};
 
     /// Convert the raw humidity reading to percent relative humidity.
     ///
@@ -81,4 +661,508 @@ pub mod fixed_point {
         let fraction: U16F16 = U16F16::from_num(reading) / U16F16::from_num(u16::MAX);
         I16F16::from_num(-49) + I16F16::from_num(315) * I16F16::from_num(fraction)
     }
+
+    /// Convert the raw temperature reading to kelvin.
+    pub fn temperature_reading_to_kelvin(reading: u16) -> I16F16 {
+        // Convert u16 reading into a fraction 0..=1
+        let fraction: U16F16 = U16F16::from_num(reading) / U16F16::from_num(u16::MAX);
+        I16F16::from_num(228.15) + I16F16::from_num(175) * I16F16::from_num(fraction)
+    }
+
+    /// Calculate the dew point in celsius from raw temperature and humidity
+    /// readings, without floating-point arithmetic or `libm`.
+    ///
+    /// This uses the Magnus formula with Sensirion's coefficients (β =
+    /// 17.62, λ = 243.12°C), computing the natural logarithm with a short
+    /// series, so is accurate to within about 0.01°C of the formula across
+    /// the sensor's range. Intermediate values use 32 fractional bits.
+    ///
+    /// Returns `None` if the humidity reading is 0%RH or below, where the
+    /// dew point is undefined.
+    pub fn dew_point_celsius(temperature_reading: u16, humidity_reading: u16) -> Option<I16F16> {
+        let beta = I32F32::from_num(17.62);
+        let lambda = I32F32::from_num(243.12);
+
+        let max = I32F32::from_num(u16::MAX);
+        let t = I32F32::from_num(-45)
+            + I32F32::from_num(175) * I32F32::from_num(temperature_reading) / max;
+        let rh_fraction = ((I32F32::from_num(125) * I32F32::from_num(humidity_reading) / max
+            - I32F32::from_num(6))
+            / 100)
+            .min(I32F32::ONE);
+        if rh_fraction <= I32F32::ZERO {
+            return None;
+        }
+
+        let gamma = ln(rh_fraction) + beta * t / (lambda + t);
+        Some(I16F16::from_num(lambda * gamma / (beta - gamma)))
+    }
+
+    /// Natural logarithm of a positive number.
+    ///
+    /// `x` is reduced to `m × 2^k` with `m` in `1..2`, and `ln(m)` computed
+    /// from the series `2 × atanh((m - 1) / (m + 1))`, which converges quickly
+    /// as its argument is at most 1/3.
+    fn ln(x: I32F32) -> I32F32 {
+        let k = x.int_log2();
+        let m = if k >= 0 { x >> k } else { x << -k };
+        let z = (m - I32F32::ONE) / (m + I32F32::ONE);
+        let z2 = z * z;
+        let series = [9, 7, 5, 3]
+            .into_iter()
+            .fold(I32F32::ZERO, |acc, n| (acc + I32F32::ONE / n) * z2);
+        I32F32::from_num(k) * I32F32::LN_2 + 2 * z * (I32F32::ONE + series)
+    }
+
+    #[cfg(test)]
+    mod test {
+        extern crate std;
+
+        use super::dew_point_celsius;
+
+        /// The Magnus formula in `f64`, for comparison.
+        fn reference(temperature_reading: u16, humidity_reading: u16) -> f64 {
+            let t = -45.0 + 175.0 * f64::from(temperature_reading) / 65_535.0;
+            let rh = (-6.0 + 125.0 * f64::from(humidity_reading) / 65_535.0).min(100.0);
+            let gamma = (rh / 100.0).ln() + 17.62 * t / (243.12 + t);
+            243.12 * gamma / (17.62 - gamma)
+        }
+
+        #[test]
+        fn matches_floating_point_formula() {
+            for t in (0..=u16::MAX).step_by(4_093) {
+                for h in (3_200..=u16::MAX).step_by(2_047) {
+                    let fixed = dew_point_celsius(t, h).unwrap().to_num::<f64>();
+                    let expected = reference(t, h);
+                    assert!(
+                        (fixed - expected).abs() < 0.01,
+                        "{t} {h}: {fixed} != {expected}"
+                    );
+                }
+            }
+        }
+
+        #[test]
+        fn undefined_when_dry() {
+            assert_eq!(dew_point_celsius(30_000, 0), None);
+            assert_eq!(dew_point_celsius(30_000, 3_145), None);
+        }
+    }
+}
+
+/// Integer-only dew point calculation from a lookup table.
+///
+/// The [`fixed_point`] dew point still needs a logarithm, which is slow on
+/// the smallest microcontrollers. A [`DewPointTable`] instead holds the
+/// saturation vapour pressure at evenly-spaced temperatures, computed at
+/// compile time, and finds the dew point by interpolating in the table.
+/// Finer steps give a more accurate result at the cost of a larger table:
+///
+/// | Step   | Length | Size    | Accuracy  |
+/// |--------|--------|---------|-----------|
+/// | 0.5°C  | 351    | 1404 B  | ±0.02°C   |
+/// | 1°C    | 176    | 704 B   | ±0.025°C  |
+/// | 5°C    | 36     | 144 B   | ±0.3°C    |
+///
+/// The accuracy is relative to the Magnus formula with Sensirion's
+/// coefficients, as used by the floating-point [`dew_point_celsius()`], for
+/// humidity from 5%RH. With finer steps than these, the error is dominated
+/// by rounding the humidity to thousandths of a percent, which also makes
+/// the dew point less accurate in very dry air.
+///
+/// ```
+/// use sht4x_rjw::conversions::lookup::{DewPointTable, table_len};
+///
+/// // Half-degree steps across the sensor's full range.
+/// static DEW_POINT: DewPointTable<{ table_len(5) }> = DewPointTable::new(5);
+///
+/// // 25°C and 56.5%RH.
+/// let dew_point = DEW_POINT.dew_point_millicelsius(0x6666, 0x8000);
+/// assert_eq!(dew_point, Some(15_745));
+/// ```
+///
+/// [`fixed_point`]: crate::conversions::fixed_point
+/// [`DewPointTable`]: lookup::DewPointTable
+/// [`dew_point_celsius()`]: crate::conversions::dew_point_celsius
+pub mod lookup {
+    use super::{humidity_reading_to_milli_percent_rh, temperature_reading_to_millicelsius};
+
+    /// Lowest temperature in a table, the bottom of the sensor's range.
+    const MIN_MILLICELSIUS: i32 = -45_000;
+
+    /// Length of a table covering the sensor's full range, from -45°C to
+    /// 130°C, in steps of `step_decicelsius` tenths of a degree.
+    pub const fn table_len(step_decicelsius: u16) -> usize {
+        let step = step_decicelsius as usize;
+        1_750_usize.div_ceil(step) + 1
+    }
+
+    /// Saturation vapour pressures at `N` temperatures from -45°C, for
+    /// finding the dew point without floating-point arithmetic.
+    ///
+    /// See the [module documentation](crate::conversions::lookup) for an example.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct DewPointTable<const N: usize> {
+        step_millicelsius: i32,
+        /// Saturation vapour pressure in hundredths of a pascal.
+        pressures: [u32; N],
+    }
+
+    impl<const N: usize> DewPointTable<N> {
+        /// Build a table with entries every `step_decicelsius` tenths of a
+        /// degree, starting at -45°C.
+        ///
+        /// Use [`table_len()`] for `N` to cover the sensor's full range.
+        /// Dew points can't be calculated for temperatures above the end of
+        /// a shorter table.
+        ///
+        /// This is intended to be evaluated at compile time, in a `const` or
+        /// `static`. It panics if `step_decicelsius` is zero.
+        pub const fn new(step_decicelsius: u16) -> Self {
+            assert!(step_decicelsius > 0, "table step must be non-zero");
+            let mut pressures = [0; N];
+            let mut i = 0;
+            while i < N {
+                let decicelsius = -450 + i as i128 * step_decicelsius as i128;
+                pressures[i] = saturation_vapour_pressure_centipascals(decicelsius);
+                i += 1;
+            }
+            Self {
+                step_millicelsius: step_decicelsius as i32 * 100,
+                pressures,
+            }
+        }
+
+        /// Calculate the dew point in thousandths of a degree celsius from
+        /// raw temperature and humidity readings, using only integer
+        /// arithmetic.
+        ///
+        /// Returns `None` if the relative humidity is zero, where the dew
+        /// point is undefined, if the dew point is below -45°C, or if the
+        /// temperature is beyond the end of the table.
+        pub fn dew_point_millicelsius(
+            &self,
+            temperature_reading: u16,
+            humidity_reading: u16,
+        ) -> Option<i32> {
+            let rh = humidity_reading_to_milli_percent_rh(humidity_reading);
+            if rh == 0 {
+                return None;
+            }
+            let saturation =
+                self.saturation_at(temperature_reading_to_millicelsius(temperature_reading))?;
+            // Both factors fit in 32 bits, so the product can't overflow.
+            let vapour_pressure = (u64::from(saturation) * u64::from(rh) / 100_000) as u32;
+            self.temperature_at(vapour_pressure)
+        }
+
+        /// Interpolate the saturation vapour pressure at `millicelsius`.
+        fn saturation_at(&self, millicelsius: i32) -> Option<u32> {
+            let offset = millicelsius - MIN_MILLICELSIUS;
+            let index = (offset / self.step_millicelsius) as usize;
+            let remainder = offset % self.step_millicelsius;
+            let low = *self.pressures.get(index)?;
+            if remainder == 0 {
+                return Some(low);
+            }
+            let high = *self.pressures.get(index + 1)?;
+            let delta = u64::from(high - low) * remainder as u64 / self.step_millicelsius as u64;
+            // The result lies between `low` and `high`.
+            Some(low + delta as u32)
+        }
+
+        /// Interpolate the temperature at which the saturation vapour
+        /// pressure is `pressure`, the inverse of `saturation_at()`.
+        fn temperature_at(&self, pressure: u32) -> Option<i32> {
+            // The number of entries at or below `pressure`, which is at least
+            // one unless the dew point is below the table.
+            let index = self
+                .pressures
+                .partition_point(|&p| p <= pressure)
+                .checked_sub(1)?;
+            let base = MIN_MILLICELSIUS + index as i32 * self.step_millicelsius;
+            let Some(&high) = self.pressures.get(index + 1) else {
+                return Some(base);
+            };
+            let low = self.pressures[index];
+            let fraction = i64::from(pressure - low) * i64::from(self.step_millicelsius)
+                / i64::from(high - low);
+            // The fraction is less than one step.
+            Some(base + fraction as i32)
+        }
+    }
+
+    /// Saturation vapour pressure in hundredths of a pascal at
+    /// `decicelsius`, from the Magnus formula with Sensirion's coefficients.
+    ///
+    /// Values too large for a `u32` saturate.
+    const fn saturation_vapour_pressure_centipascals(decicelsius: i128) -> u32 {
+        // 17.62 × T / (243.12 + T), with 32 fractional bits.
+        let exponent = ((1_762 * decicelsius) << 32) / (10 * (24_312 + 10 * decicelsius));
+        let pressure = (61_120 * exp(exponent) + (1 << 31)) >> 32;
+        if pressure > u32::MAX as i128 {
+            u32::MAX
+        } else {
+            pressure as u32
+        }
+    }
+
+    /// Exponential of `x`, with 32 fractional bits in both `x` and the
+    /// result.
+    ///
+    /// `x` is reduced to `k × ln(2) + r` with `r` in `0..ln(2)`, and `e^r`
+    /// computed from its Taylor series.
+    const fn exp(x: i128) -> i128 {
+        const ONE: i128 = 1 << 32;
+        const LN_2: i128 = 2_977_044_472;
+        let k = x.div_euclid(LN_2);
+        let r = x - k * LN_2;
+        let mut sum = ONE;
+        let mut term = ONE;
+        let mut n = 1;
+        while term > 0 {
+            term = term * r / ONE / n;
+            sum += term;
+            n += 1;
+        }
+        if k >= 0 { sum << k } else { sum >> -k }
+    }
+
+    #[cfg(test)]
+    mod test {
+        extern crate std;
+
+        use super::{DewPointTable, table_len};
+
+        /// The Magnus formula in `f64`, for comparison.
+        fn reference(temperature_reading: u16, humidity_reading: u16) -> f64 {
+            let t = -45.0 + 175.0 * f64::from(temperature_reading) / 65_535.0;
+            let rh = (-6.0 + 125.0 * f64::from(humidity_reading) / 65_535.0).min(100.0);
+            let gamma = (rh / 100.0).ln() + 17.62 * t / (243.12 + t);
+            243.12 * gamma / (17.62 - gamma)
+        }
+
+        fn max_error<const N: usize>(table: &DewPointTable<N>) -> f64 {
+            let mut max = 0.0_f64;
+            for t in (0..=u16::MAX).step_by(1_021) {
+                // From 5%RH, below which the integer humidity is too coarse.
+                for h in (5_767..=u16::MAX).step_by(1_023) {
+                    let expected = reference(t, h);
+                    match table.dew_point_millicelsius(t, h) {
+                        Some(milli) => max = max.max((f64::from(milli) / 1_000.0 - expected).abs()),
+                        None => assert!(expected < -44.9, "{t} {h}: {expected}"),
+                    }
+                }
+            }
+            max
+        }
+
+        #[test]
+        fn accuracy_depends_on_step() {
+            const HALF: DewPointTable<{ table_len(5) }> = DewPointTable::new(5);
+            const ONE: DewPointTable<{ table_len(10) }> = DewPointTable::new(10);
+            const FIVE: DewPointTable<{ table_len(50) }> = DewPointTable::new(50);
+            assert_eq!(table_len(5), 351);
+            assert_eq!(table_len(10), 176);
+            assert_eq!(table_len(50), 36);
+            assert!(max_error(&HALF) < 0.02);
+            assert!(max_error(&ONE) < 0.025);
+            assert!(max_error(&FIVE) < 0.3);
+        }
+
+        #[test]
+        fn undefined_outside_table() {
+            const SHORT: DewPointTable<21> = DewPointTable::new(50);
+            // 55°C is the last entry.
+            assert!(SHORT.dew_point_millicelsius(37_448, 30_000).is_some());
+            assert_eq!(SHORT.dew_point_millicelsius(37_500, 30_000), None);
+            assert_eq!(SHORT.dew_point_millicelsius(30_000, 0), None);
+            assert_eq!(SHORT.dew_point_millicelsius(30_000, 3_145), None);
+            // Very dry cold air has a dew point below -45°C.
+            assert_eq!(SHORT.dew_point_millicelsius(3_000, 4_000), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use super::{
+        SaturationFormula, absolute_humidity_g_m3, dew_point_celsius, exp, humidex,
+        humidity_reading_to_deci_percent_rh, humidity_reading_to_milli_percent_rh, ln,
+        mixing_ratio, specific_humidity, temperature_reading_to_decicelsius,
+        temperature_reading_to_millicelsius, vapour_pressure_deficit_kpa,
+    };
+
+    const MAGNUS: SaturationFormula = SaturationFormula::Magnus;
+
+    /// Whether `micromath`'s less accurate approximations are in use.
+    const APPROXIMATE: bool = cfg!(all(feature = "micromath", not(feature = "libm")));
+
+    fn temperature(celsius: f32) -> u16 {
+        ((celsius + 45.0) / 175.0 * 65_535.0 + 0.5) as u16
+    }
+
+    fn humidity(percent_rh: f32) -> u16 {
+        ((percent_rh + 6.0) / 125.0 * 65_535.0 + 0.5) as u16
+    }
+
+    #[test]
+    fn exp_is_accurate() {
+        for i in -50..=70 {
+            let x = i as f32 / 10.0;
+            let expected = f64::from(x).exp();
+            let relative = (f64::from(exp(x)) - expected).abs() / expected;
+            let tolerance = if APPROXIMATE { 2e-3 } else { 1e-6 };
+            assert!(relative < tolerance, "{x}: {} != {expected}", exp(x));
+        }
+    }
+
+    #[test]
+    fn unclamped_humidity() {
+        use crate::common::Measurement;
+        assert_eq!(super::humidity_reading_to_percent_rh_unclamped(0), -6.0);
+        assert_eq!(
+            super::humidity_reading_to_percent_rh_unclamped(u16::MAX),
+            119.0
+        );
+        assert!(Measurement::from_raw(0, 3_145).is_out_of_physical_range());
+        assert!(!Measurement::from_raw(0, 3_146).is_out_of_physical_range());
+        assert!(!Measurement::from_raw(0, 55_573).is_out_of_physical_range());
+        assert!(Measurement::from_raw(0, 55_574).is_out_of_physical_range());
+    }
+
+    #[test]
+    fn inverse_conversions_round_trip() {
+        for reading in (0..=u16::MAX).step_by(97) {
+            let celsius = super::temperature_reading_to_celsius(reading);
+            assert_eq!(super::celsius_to_raw(celsius), reading);
+        }
+        assert_eq!(super::percent_rh_to_raw(56.5), 0x8000);
+        assert_eq!(super::celsius_to_raw(-100.0), 0);
+        assert_eq!(super::percent_rh_to_raw(200.0), u16::MAX);
+    }
+
+    #[test]
+    fn integer_conversions() {
+        assert_eq!(temperature_reading_to_millicelsius(0), -45_000);
+        assert_eq!(temperature_reading_to_millicelsius(0x6666), 24_998);
+        assert_eq!(temperature_reading_to_millicelsius(u16::MAX), 129_997);
+        assert_eq!(humidity_reading_to_milli_percent_rh(0), 0);
+        assert_eq!(humidity_reading_to_milli_percent_rh(0x8000), 56_500);
+        assert_eq!(humidity_reading_to_milli_percent_rh(u16::MAX), 100_000);
+    }
+
+    #[test]
+    fn deci_conversions_round_to_nearest() {
+        assert_eq!(temperature_reading_to_decicelsius(0), -450);
+        assert_eq!(temperature_reading_to_decicelsius(0x6666), 250);
+        assert_eq!(temperature_reading_to_decicelsius(u16::MAX), 1300);
+        // -0.054°C and -0.049°C.
+        assert_eq!(temperature_reading_to_decicelsius(16_832), -1);
+        assert_eq!(temperature_reading_to_decicelsius(16_834), 0);
+        assert_eq!(humidity_reading_to_deci_percent_rh(0x8000), 565);
+        assert_eq!(humidity_reading_to_deci_percent_rh(u16::MAX), 1000);
+    }
+
+    #[test]
+    fn ln_is_accurate() {
+        for x in [1e-6, 0.001, 0.3, 0.5, 0.999, 1.0, 1.5, 2.0, 100.0] {
+            let expected = f64::from(x).ln();
+            let tolerance = if APPROXIMATE { 0.1 } else { 1e-5 };
+            assert!(
+                (f64::from(ln(x)) - expected).abs() < tolerance,
+                "{x}: {}",
+                ln(x)
+            );
+        }
+    }
+
+    #[test]
+    fn absolute_humidity() {
+        // 25°C and 50%RH is about 11.5g/m³.
+        let (t, h) = (temperature(25.0), humidity(50.0));
+        assert!((absolute_humidity_g_m3(t, h, MAGNUS) - 11.5).abs() < 0.05);
+        assert_eq!(absolute_humidity_g_m3(t, 0, MAGNUS), 0.0);
+    }
+
+    #[test]
+    fn compensated_humidity() {
+        let (t, h) = (temperature(22.0), humidity(40.0));
+        // No offset, no change.
+        let unchanged = super::humidity_compensated_percent_rh(t, h, 0, MAGNUS);
+        assert!((unchanged - 40.0).abs() < 0.01);
+        // The sensor read 25°C, but the air was 22°C: 40%RH at 25°C is about
+        // 47.9%RH at 22°C.
+        let offset = -(temperature(25.0) as i16 - t as i16);
+        let compensated = super::humidity_compensated_percent_rh(t, h, offset, MAGNUS);
+        assert!((compensated - 47.9).abs() < 0.1, "{compensated}");
+    }
+
+    #[test]
+    fn dew_point() {
+        // 25°C and 50%RH has a dew point of about 13.9°C.
+        let dew_point = dew_point_celsius(temperature(25.0), humidity(50.0), MAGNUS).unwrap();
+        assert!((dew_point - 13.85).abs() < 0.05);
+        assert_eq!(dew_point_celsius(temperature(25.0), 0, MAGNUS), None);
+    }
+
+    #[test]
+    fn humidex_matches_environment_canada() {
+        // 30°C and 50%RH (dew point 18.4°C) has a humidex of 36.3.
+        let humidex = humidex(temperature(30.0), humidity(50.0), MAGNUS);
+        assert!((humidex - 36.3).abs() < 0.1, "{humidex}");
+    }
+    #[test]
+    fn apparent_temperature() {
+        let (t, h) = (temperature(30.0), humidity(50.0));
+        let still = super::apparent_temperature_celsius(t, h, None);
+        assert!((still - 32.98).abs() < 0.02, "{still}");
+        let windy = super::apparent_temperature_celsius(t, h, Some(5.0));
+        assert!((still - windy - 3.5).abs() < 1e-4, "{windy}");
+    }
+
+    #[test]
+    fn wbgt_estimate() {
+        let wbgt = super::wbgt_indoor_celsius(temperature(30.0), humidity(50.0));
+        assert!((wbgt - 29.26).abs() < 0.02, "{wbgt}");
+    }
+
+    #[test]
+    fn vapour_pressure_deficit() {
+        // Saturation vapour pressure at 25°C is about 3.17kPa.
+        let vpd = vapour_pressure_deficit_kpa(temperature(25.0), humidity(50.0), MAGNUS);
+        assert!((vpd - 1.584).abs() < 0.005, "{vpd}");
+        assert!(
+            vapour_pressure_deficit_kpa(temperature(25.0), humidity(100.0), MAGNUS).abs() < 1e-6
+        );
+    }
+    #[test]
+    fn mass_based_humidity() {
+        // 25°C and 50%RH at sea level: about 9.9g/kg.
+        let (t, h) = (temperature(25.0), humidity(50.0));
+        let w = mixing_ratio(t, h, 101_325.0, MAGNUS);
+        let q = specific_humidity(t, h, 101_325.0, MAGNUS);
+        assert!((w - 0.00985).abs() < 0.00005, "{w}");
+        assert!((q - w / (1.0 + w)).abs() < 1e-7, "{q}");
+    }
+    #[test]
+    fn saturation_formulas_agree_and_invert() {
+        use SaturationFormula::{ArdenBuck, Magnus, Tetens};
+        for formula in [Magnus, ArdenBuck, Tetens] {
+            // 31.7hPa at 25°C.
+            let hpa = formula.saturation_vapour_pressure_hpa(25.0);
+            assert!((hpa - 31.7).abs() < 0.1, "{formula:?}: {hpa}");
+            for t in [-40.0, -10.0, 0.0, 20.0, 60.0, 120.0] {
+                let e = formula.saturation_vapour_pressure_hpa(t);
+                let inverse = formula.saturation_temperature(e);
+                let tolerance = if APPROXIMATE { 2.0 } else { 0.01 };
+                assert!(
+                    (inverse - t).abs() < tolerance,
+                    "{formula:?}: {t} {inverse}"
+                );
+            }
+        }
+    }
 }
@@ -2,7 +2,8 @@
 ///
 /// The three bytes passed should be two data bytes with the following CRC
 /// byte, as read from the sensor. The result of the function will be 0
-/// if the CRC byte is correct for the preceding two data bytes.
+/// if the CRC byte is correct for the preceding two data bytes. If only
+/// the two data bytes are passed, the result is their CRC byte.
 ///
 /// # Example usage
 ///
@@ -22,7 +23,7 @@
 ///
 /// [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
 #[must_use]
-fn crc8(bytes: [u8; 3]) -> u8 {
+fn crc8<const N: usize>(bytes: [u8; N]) -> u8 {
     const fn top_bit_set(b: u8) -> bool {
         b & 0x80 == 0x80
     }
@@ -59,6 +60,12 @@ pub(crate) fn validate_crc(bytes: [u8; 3]) -> Result<(), u8> {
     }
 }
 
+/// Calculate the CRC byte the sensor would send after the two data bytes.
+#[cfg(feature = "simulator")]
+pub(crate) fn calculate_crc(data: [u8; 2]) -> u8 {
+    crc8(data)
+}
+
 #[cfg(test)]
 mod test {
     use super::crc8;
@@ -73,4 +80,9 @@ mod test {
     fn crc_BEEF() {
         assert_eq!(crc8([0xBE, 0xEF, 0x92]), 0x00);
     }
+
+    #[test]
+    fn crc_of_data_bytes() {
+        assert_eq!(crc8([0xBE, 0xEF]), 0x92);
+    }
 }
@@ -0,0 +1,126 @@
+//! Periodic sampling with the async driver on [`embassy`].
+//!
+//! [`Sampler`] owns an [async driver][SHT4x] and a delay, and measures each
+//! time an [`embassy_time::Ticker`] ticks, publishing each measurement to
+//! any destination implementing [`Publish`], such as an `embassy-sync`
+//! [`Watch`] or [`PubSubChannel`]. This is the task most firmware using
+//! `embassy` writes to share a sensor between other tasks.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # #[pollster::main]
+//! # async fn main() -> anyhow::Result<()> {
+//! # let delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! # ];
+//! # let i2c = Mock::new(&expectations);
+//! use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+//! use embassy_sync::watch::Watch;
+//! use embassy_time::Duration;
+//! use sht4x_rjw::asynch::SHT4x;
+//! use sht4x_rjw::common::Measurement;
+//! use sht4x_rjw::embassy::Sampler;
+//!
+//! let latest: Watch<NoopRawMutex, Measurement, 2> = Watch::new();
+//! let mut display = latest.receiver().unwrap();
+//!
+//! let sensor = SHT4x::new(i2c, Default::default());
+//! let mut sampler = Sampler::new(sensor, delay, Duration::from_secs(2));
+//! # embassy_time::MockDriver::get().advance(Duration::from_secs(2));
+//! sampler.publish_next_measurement(&latest).await?;
+//!
+//! assert!(display.try_changed().is_some());
+//! # let (sensor, _) = sampler.destroy();
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! In a task, [`Sampler::run()`] publishes measurements until one fails.
+//!
+//! [`embassy`]: https://embassy.dev
+//! [`Watch`]: embassy_sync::watch::Watch
+//! [`PubSubChannel`]: embassy_sync::pubsub::PubSubChannel
+use embassy_time::{Duration, Ticker};
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+
+use crate::asynch::SHT4x;
+use crate::common::Measurement;
+use crate::error::Error;
+use crate::publish::Publish;
+
+/// Takes a measurement each time a [`Ticker`] ticks.
+///
+/// See the [module documentation](crate::embassy) for an example.
+pub struct Sampler<I: I2c, D> {
+    sensor: SHT4x<I>,
+    delay: D,
+    ticker: Ticker,
+}
+
+impl<I: I2c, D: DelayNs> Sampler<I, D> {
+    /// Create a sampler that measures every `interval`, with the settings
+    /// provided upon construction of `sensor`.
+    ///
+    /// The first measurement is taken one `interval` after creation. If a
+    /// measurement is late, the ticker catches up without skipping ticks,
+    /// as described for [`Ticker`].
+    pub fn new(sensor: SHT4x<I>, delay: D, interval: Duration) -> Self {
+        Self {
+            sensor,
+            delay,
+            ticker: Ticker::every(interval),
+        }
+    }
+
+    /// Access the sensor driver, for example to read its statistics.
+    pub fn sensor_mut(&mut self) -> &mut SHT4x<I> {
+        &mut self.sensor
+    }
+
+    /// Drop the sampler and return the sensor driver and delay.
+    pub fn destroy(self) -> (SHT4x<I>, D) {
+        (self.sensor, self.delay)
+    }
+
+    /// Wait for the next tick, then measure.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`].
+    pub async fn next_measurement(&mut self) -> Result<Measurement, Error<I::Error>> {
+        self.ticker.next().await;
+        self.sensor.measure(&mut self.delay).await
+    }
+
+    /// Wait for the next tick, measure, and publish the measurement.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`]. Nothing is published if an error
+    /// occurs.
+    pub async fn publish_next_measurement(
+        &mut self,
+        mut publisher: impl Publish,
+    ) -> Result<Measurement, Error<I::Error>> {
+        let measurement = self.next_measurement().await?;
+        publisher.publish(measurement);
+        Ok(measurement)
+    }
+
+    /// Publish a measurement on every tick until a measurement fails,
+    /// returning the error.
+    ///
+    /// Call this again to carry on sampling after handling the error, for
+    /// example in a loop that logs it.
+    pub async fn run(&mut self, mut publisher: impl Publish) -> Error<I::Error> {
+        loop {
+            if let Err(error) = self.publish_next_measurement(&mut publisher).await {
+                return error;
+            }
+        }
+    }
+}
@@ -1,6 +1,10 @@
 /// Error wrapper for all driver methods that interact with the sensor.
+///
+/// New variants may be added in minor releases. To handle errors without
+/// being generic over the I2C error type, match on [`Error::kind()`].
 #[derive(Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Error<I2cError>
 where
     I2cError: embedded_hal::i2c::Error,
@@ -14,9 +18,70 @@ where
 
     /// An error was returned from the underlying I2C interface.
     I2c(I2cError),
+
+    /// A measurement could not be completed before its deadline.
+    DeadlineExceeded,
+
+    /// An operation did not complete within its timeout.
+    Timeout,
+
+    /// A measurement was refused because the minimum interval set by the
+    /// config's rate limit had not passed since the last one.
+    TooSoon,
+
+    /// No device acknowledged at the sensor's address, when probing or when
+    /// sending a measurement command. Check the wiring and address.
+    NotPresent,
+
+    /// The sensor did not acknowledge the read of a measurement, as it had
+    /// not finished measuring. Waiting longer, such as with
+    /// [`DelayMode::Maximum`](crate::common::DelayMode::Maximum), or trying
+    /// again may succeed.
+    NotReady,
+
+    /// The sensor's serial number was not the one expected, so it may have
+    /// been replaced, or a different sensor may be at this address.
+    SerialMismatch { expected: u32, actual: u32 },
+
+    /// The humidity reading was outside `0..=100`%RH, and the config's
+    /// [`HumidityClamp`](crate::common::HumidityClamp) asks for an error.
+    HumidityOutOfRange { raw_humidity: u16 },
+
+    /// A heater command was refused because it would break one of the
+    /// config's heater limits.
+    HeaterLimitExceeded(HeaterLimit),
+
+    /// The reading was outside the config's
+    /// [`Plausibility`](crate::common::Plausibility) ranges, which may
+    /// mean it was corrupted on the bus without failing the CRC check.
+    ImplausibleReading { raw_temp: u16, raw_humidity: u16 },
+
+    /// A setting passed for a single call was invalid, such as a reserved
+    /// address given to `measure_at()`.
+    InvalidConfig(ConfigError),
+}
+
+/// Which of the config's heater limits refused a heater command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HeaterLimit {
+    /// [`Config::heater_max_duty_cycle_percent`](crate::common::Config::heater_max_duty_cycle_percent).
+    DutyCycle,
+    /// [`Config::heater_max_ambient_celsius`](crate::common::Config::heater_max_ambient_celsius).
+    AmbientTemperature,
+}
+
+impl core::fmt::Display for HeaterLimit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::DutyCycle => write!(f, "duty cycle"),
+            Self::AmbientTemperature => write!(f, "ambient temperature"),
+        }
+    }
 }
 
 /// Describes which byte pair had an incorrect CRC.
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum CrcFailureReason {
     /// The first two bytes of the four-byte serial number.
     SerialNumberFirstPair,
@@ -57,6 +122,251 @@ impl core::fmt::Display for CrcFailureReason {
     }
 }
 
+impl<I2cError> Error<I2cError>
+where
+    I2cError: embedded_hal::i2c::Error,
+{
+    /// The category of this error, without the I2C error type.
+    ///
+    /// ```
+    /// # use embedded_hal::i2c::ErrorKind;
+    /// use sht4x_rjw::error::{Error, Sht4xErrorKind};
+    ///
+    /// fn should_alert<E: embedded_hal::i2c::Error>(error: &Error<E>) -> bool {
+    ///     matches!(
+    ///         error.kind(),
+    ///         Sht4xErrorKind::NotPresent | Sht4xErrorKind::SerialMismatch
+    ///     )
+    /// }
+    ///
+    /// assert!(should_alert(&Error::<ErrorKind>::NotPresent));
+    /// assert!(!should_alert(&Error::<ErrorKind>::TooSoon));
+    /// ```
+    pub fn kind(&self) -> Sht4xErrorKind {
+        match self {
+            Error::CrcValidationFailed { reason, .. } => {
+                Sht4xErrorKind::CrcValidationFailed(*reason)
+            }
+            Error::I2c(e) => Sht4xErrorKind::I2c(e.kind()),
+            Error::DeadlineExceeded => Sht4xErrorKind::DeadlineExceeded,
+            Error::Timeout => Sht4xErrorKind::Timeout,
+            Error::TooSoon => Sht4xErrorKind::TooSoon,
+            Error::NotPresent => Sht4xErrorKind::NotPresent,
+            Error::NotReady => Sht4xErrorKind::NotReady,
+            Error::SerialMismatch { .. } => Sht4xErrorKind::SerialMismatch,
+            Error::HumidityOutOfRange { .. } => Sht4xErrorKind::HumidityOutOfRange,
+            Error::HeaterLimitExceeded(limit) => Sht4xErrorKind::HeaterLimitExceeded(*limit),
+            Error::ImplausibleReading { .. } => Sht4xErrorKind::ImplausibleReading,
+            Error::InvalidConfig(error) => Sht4xErrorKind::InvalidConfig(*error),
+        }
+    }
+
+    /// Whether the error is likely to be transient, so that simply trying
+    /// again may succeed.
+    ///
+    /// See [`Sht4xErrorKind::is_transient()`].
+    pub fn is_transient(&self) -> bool {
+        self.kind().is_transient()
+    }
+}
+
+/// The category of an [`Error`], without the I2C error type parameter.
+///
+/// Unlike [`Error`], this is a plain `Copy` type, so it can be stored
+/// and compared without being generic over the I2C interface. Like
+/// [`Error`], new variants may be added in minor releases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Sht4xErrorKind {
+    /// A byte pair had an incorrect CRC.
+    CrcValidationFailed(CrcFailureReason),
+    /// The I2C interface returned an error of the given kind.
+    I2c(embedded_hal::i2c::ErrorKind),
+    /// A measurement could not be completed before its deadline.
+    DeadlineExceeded,
+    /// An operation did not complete within its timeout.
+    Timeout,
+    /// A measurement was refused by the rate limit.
+    TooSoon,
+    /// No device acknowledged at the sensor's address.
+    NotPresent,
+    /// The sensor had not finished measuring when the result was read.
+    NotReady,
+    /// The sensor's serial number was not the one expected.
+    SerialMismatch,
+    /// The humidity reading was outside the physical range.
+    HumidityOutOfRange,
+    /// A heater command was refused by the given heater limit.
+    HeaterLimitExceeded(HeaterLimit),
+    /// The reading was outside the plausible ranges.
+    ImplausibleReading,
+    /// A setting passed for a single call was invalid.
+    InvalidConfig(ConfigError),
+}
+
+impl Sht4xErrorKind {
+    /// Whether the error is likely to be transient, so that simply trying
+    /// again may succeed.
+    ///
+    /// CRC failures, implausible readings, sensors not ready with a
+    /// measurement, and I2C NACKs, bus errors and arbitration loss are
+    /// considered transient. Other I2C errors, missed deadlines, timeouts,
+    /// rate-limited measurements, absent sensors, serial number mismatches,
+    /// out-of-range humidity readings, refused heater commands and invalid
+    /// settings are not.
+    pub fn is_transient(&self) -> bool {
+        use embedded_hal::i2c::ErrorKind;
+        match self {
+            Self::CrcValidationFailed(_) | Self::ImplausibleReading | Self::NotReady => true,
+            Self::I2c(kind) => matches!(
+                kind,
+                ErrorKind::NoAcknowledge(_) | ErrorKind::Bus | ErrorKind::ArbitrationLoss
+            ),
+            Self::DeadlineExceeded
+            | Self::Timeout
+            | Self::TooSoon
+            | Self::NotPresent
+            | Self::SerialMismatch
+            | Self::HumidityOutOfRange
+            | Self::HeaterLimitExceeded(_)
+            | Self::InvalidConfig(_) => false,
+        }
+    }
+}
+
+impl core::fmt::Display for Sht4xErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::CrcValidationFailed(reason) => write!(f, "CRC validation failed for {reason}"),
+            Self::I2c(kind) => write!(f, "I2C error: {kind}"),
+            Self::DeadlineExceeded => write!(f, "deadline exceeded"),
+            Self::Timeout => write!(f, "timed out"),
+            Self::TooSoon => write!(f, "too soon"),
+            Self::NotPresent => write!(f, "not present"),
+            Self::NotReady => write!(f, "not ready"),
+            Self::SerialMismatch => write!(f, "serial number mismatch"),
+            Self::HumidityOutOfRange => write!(f, "humidity out of range"),
+            Self::HeaterLimitExceeded(limit) => write!(f, "heater {limit} limit exceeded"),
+            Self::ImplausibleReading => write!(f, "implausible reading"),
+            Self::InvalidConfig(error) => write!(f, "invalid config: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Sht4xErrorKind {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self {
+            Self::CrcValidationFailed(reason) => {
+                defmt::write!(fmt, "CRC validation failed for {}", reason)
+            }
+            Self::I2c(kind) => defmt::write!(fmt, "I2C error: {}", defmt::Display2Format(kind)),
+            Self::DeadlineExceeded => defmt::write!(fmt, "deadline exceeded"),
+            Self::Timeout => defmt::write!(fmt, "timed out"),
+            Self::TooSoon => defmt::write!(fmt, "too soon"),
+            Self::NotPresent => defmt::write!(fmt, "not present"),
+            Self::NotReady => defmt::write!(fmt, "not ready"),
+            Self::SerialMismatch => defmt::write!(fmt, "serial number mismatch"),
+            Self::HumidityOutOfRange => defmt::write!(fmt, "humidity out of range"),
+            Self::HeaterLimitExceeded(limit) => {
+                defmt::write!(fmt, "heater {} limit exceeded", limit)
+            }
+            Self::ImplausibleReading => defmt::write!(fmt, "implausible reading"),
+            Self::InvalidConfig(error) => defmt::write!(fmt, "invalid config: {}", error),
+        }
+    }
+}
+
+/// Why a [`Config`](crate::common::Config) was rejected.
+///
+/// Returned by [`Config::validate()`], [`Config::validate_heater()`] and
+/// [`ConfigBuilder::build()`], and wrapped in [`Error::InvalidConfig`] when
+/// a driver method is given an invalid setting.
+///
+/// [`Config::validate()`]: crate::common::Config::validate
+/// [`Config::validate_heater()`]: crate::common::Config::validate_heater
+/// [`ConfigBuilder::build()`]: crate::common::ConfigBuilder::build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// [`DelayMode::Poll`](crate::common::DelayMode::Poll) was given a zero
+    /// interval, which would flood the bus while the sensor is busy.
+    ZeroPollInterval,
+    /// The retry backoff's initial delay is longer than its maximum delay.
+    InvalidBackoff,
+    /// A custom I2C address is reserved, or does not fit in seven bits.
+    InvalidAddress(u8),
+    /// A heater reading mode is used with a custom delay shorter than the
+    /// heater pulse, so the measurement could never be read in time.
+    DelayShorterThanHeaterPulse,
+    /// A heater reading mode is used with a rate limit that allows the
+    /// heater to run for more than the datasheet's maximum duty cycle.
+    HeaterDutyCycleExceeded,
+    /// [`Config::heater_max_ambient_celsius`] is above
+    /// [`HeaterStats::MAX_AMBIENT_CELSIUS`].
+    ///
+    /// [`Config::heater_max_ambient_celsius`]: crate::common::Config::heater_max_ambient_celsius
+    /// [`HeaterStats::MAX_AMBIENT_CELSIUS`]: crate::common::HeaterStats::MAX_AMBIENT_CELSIUS
+    HeaterAmbientLimitTooHigh(i8),
+    /// [`Config::heater_max_duty_cycle_percent`] is above
+    /// [`HeaterStats::MAX_DUTY_CYCLE_PERCENT`].
+    ///
+    /// [`Config::heater_max_duty_cycle_percent`]: crate::common::Config::heater_max_duty_cycle_percent
+    /// [`HeaterStats::MAX_DUTY_CYCLE_PERCENT`]: crate::common::HeaterStats::MAX_DUTY_CYCLE_PERCENT
+    HeaterDutyCycleLimitTooHigh(u8),
+    /// A [`Plausibility`](crate::common::Plausibility) range has its
+    /// minimum above its maximum.
+    InvalidPlausibilityRange,
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ZeroPollInterval => write!(f, "Poll delay mode has a zero interval"),
+            Self::InvalidBackoff => {
+                write!(f, "Retry backoff initial delay is longer than its maximum")
+            }
+            Self::InvalidAddress(address) => write!(f, "Invalid I2C address {address:#04X}"),
+            Self::DelayShorterThanHeaterPulse => {
+                write!(f, "Custom delay is shorter than the heater pulse")
+            }
+            Self::HeaterDutyCycleExceeded => {
+                write!(f, "Rate limit allows the heater to exceed its duty cycle")
+            }
+            Self::HeaterAmbientLimitTooHigh(celsius) => {
+                let max = crate::common::HeaterStats::MAX_AMBIENT_CELSIUS;
+                write!(f, "Heater ambient limit {celsius}°C is above {max}°C")
+            }
+            Self::HeaterDutyCycleLimitTooHigh(percent) => {
+                let max = crate::common::HeaterStats::MAX_DUTY_CYCLE_PERCENT;
+                write!(f, "Heater duty cycle limit {percent}% is above {max}%")
+            }
+            Self::InvalidPlausibilityRange => {
+                write!(f, "Plausibility range minimum is above its maximum")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ConfigError {}
+
+/// A byte that is not one of the sensor's commands.
+///
+/// Returned when converting a byte to a
+/// [`Command`](crate::common::Command) or
+/// [`ReadingMode`](crate::common::ReadingMode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UnknownCommand(pub u8);
+
+impl core::fmt::Display for UnknownCommand {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown command byte {:#04X}", self.0)
+    }
+}
+
+impl core::error::Error for UnknownCommand {}
+
 /// Enable `?` to convert embedded-hal I2C errors into our `Error`.
 impl<I2cError> From<I2cError> for Error<I2cError>
 where
@@ -85,6 +395,32 @@ where
                 )
             }
             Error::I2c(e) => write!(f, "Received I2C error: {:?}", e),
+            Error::DeadlineExceeded => {
+                write!(f, "Measurement could not be completed before the deadline")
+            }
+            Error::Timeout => write!(f, "Operation did not complete within the timeout"),
+            Error::TooSoon => write!(f, "Measurement refused by the rate limit"),
+            Error::NotPresent => write!(f, "No device acknowledged at the sensor's address"),
+            Error::NotReady => write!(f, "Sensor had not finished measuring"),
+            Error::SerialMismatch { expected, actual } => write!(
+                f,
+                "Expected serial number {expected:08X}, but the sensor reported {actual:08X}"
+            ),
+            Error::HumidityOutOfRange { raw_humidity } => write!(
+                f,
+                "Humidity reading {raw_humidity:#06X} is outside the physical range"
+            ),
+            Error::HeaterLimitExceeded(limit) => {
+                write!(f, "Heater command refused by the {limit} limit")
+            }
+            Error::ImplausibleReading {
+                raw_temp,
+                raw_humidity,
+            } => write!(
+                f,
+                "Reading {raw_temp:#06X}, {raw_humidity:#06X} is outside the plausible ranges"
+            ),
+            Error::InvalidConfig(error) => write!(f, "Invalid config: {error}"),
         }
     }
 }
@@ -0,0 +1,379 @@
+//! Filters for smoothing and cleaning up series of measurements.
+//!
+//! A [`Filter`] takes each new measurement and returns a (possibly
+//! modified) measurement, or `None` if it has nothing to output for that
+//! input. Filters can be chained with [`Filter::then()`], and a filter or
+//! chain of filters can be given to a [`PeriodicSampler`] to be applied to
+//! every measurement it takes.
+//!
+//! The built-in filters all operate on the raw sensor readings using integer
+//! arithmetic, so their parameters are given in raw sensor "ticks". One
+//! tick of temperature is about 0.0027°C (so 1°C is about 374 ticks), and
+//! one tick of humidity is about 0.0019%RH (so 1%RH is about 524 ticks).
+//! See section 4.6 of the [datasheet] for the conversion formulas. Their
+//! output follows the driver's [`Config::humidity_clamp`] setting, as the
+//! measurements given to them do, and is flagged as implausible (see
+//! [`Measurement::is_plausible()`]) while it is derived from a measurement
+//! that was.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # use sht4x_rjw::blocking::SHT4x;
+//! # fn main() -> anyhow::Result<()> {
+//! # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! # ];
+//! # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+//! use sht4x_rjw::filter::{Ewma, Filter, OutlierRejection};
+//!
+//! // Discard jumps of more than about 2°C or 5%RH, then smooth what remains.
+//! let mut filter = OutlierRejection::new(750, 2_600, 3).then(Ewma::new(2));
+//! if let Some(measurement) = filter.update(sensor.measure(&mut delay)?) {
+//!     println!("{}°C", measurement.celsius());
+//! }
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Config::humidity_clamp`]: crate::common::Config::humidity_clamp
+//! [`Measurement::is_plausible()`]: crate::common::Measurement::is_plausible
+//! [`PeriodicSampler`]: crate::sampler::PeriodicSampler
+//! [datasheet]: https://sensirion.com/media/documents/33FD6951/67EB9032/HT_DS_Datasheet_SHT4x_5.pdf
+use crate::common::Measurement;
+
+/// A processing stage applied to each measurement in a series.
+pub trait Filter {
+    /// Process a new measurement.
+    ///
+    /// Returns the filtered measurement, or `None` if the filter has nothing
+    /// to output (for instance, because the input was rejected as an
+    /// outlier).
+    fn update(&mut self, measurement: Measurement) -> Option<Measurement>;
+
+    /// Chain this filter with another, so that the output of this filter
+    /// is fed into `next`.
+    fn then<F>(self, next: F) -> Chain<Self, F>
+    where
+        Self: Sized,
+        F: Filter,
+    {
+        Chain {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+impl<F> Filter for &mut F
+where
+    F: Filter + ?Sized,
+{
+    fn update(&mut self, measurement: Measurement) -> Option<Measurement> {
+        (**self).update(measurement)
+    }
+}
+
+/// A filter that passes every measurement through unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoFilter;
+
+impl Filter for NoFilter {
+    fn update(&mut self, measurement: Measurement) -> Option<Measurement> {
+        Some(measurement)
+    }
+}
+
+/// Two filters applied in sequence, created by [`Filter::then()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> Filter for Chain<A, B>
+where
+    A: Filter,
+    B: Filter,
+{
+    fn update(&mut self, measurement: Measurement) -> Option<Measurement> {
+        self.first
+            .update(measurement)
+            .and_then(|measurement| self.second.update(measurement))
+    }
+}
+
+/// Exponentially-weighted moving average.
+///
+/// Each output is the previous output moved `1 / 2^shift` of the way
+/// towards the new measurement, so larger shifts give smoother but slower
+/// output. The first measurement is passed through unchanged.
+///
+/// The output is flagged as implausible until the weight of any implausible
+/// measurement in the average has decayed away.
+#[derive(Debug, Clone, Copy)]
+pub struct Ewma {
+    shift: u8,
+    /// Running averages of the raw temperature and humidity, with 16
+    /// fractional bits to avoid accumulating rounding errors.
+    state: Option<(u32, u32)>,
+    /// Running weight of implausible measurements in the average, with 16
+    /// fractional bits.
+    implausible_weight: u32,
+}
+
+impl Ewma {
+    /// Create an average with a smoothing factor of `1 / 2^shift`.
+    ///
+    /// `shift` is limited to 15, beyond which the output would barely move.
+    pub fn new(shift: u8) -> Self {
+        Self {
+            shift: shift.min(15),
+            state: None,
+            implausible_weight: 0,
+        }
+    }
+}
+
+impl Filter for Ewma {
+    fn update(&mut self, measurement: Measurement) -> Option<Measurement> {
+        let input = (
+            u32::from(measurement.raw_temperature_reading()) << 16,
+            u32::from(measurement.raw_humidity_reading()) << 16,
+        );
+        let step = |average: u32, input: u32| {
+            let delta = (i64::from(input) - i64::from(average)) >> self.shift;
+            (i64::from(average) + delta) as u32
+        };
+        let implausible = if measurement.is_plausible() {
+            0
+        } else {
+            1 << 16
+        };
+        let (temp, humidity, implausible_weight) = match self.state {
+            Some((temp, humidity)) => (
+                step(temp, input.0),
+                step(humidity, input.1),
+                step(self.implausible_weight, implausible),
+            ),
+            None => (input.0, input.1, implausible),
+        };
+        self.state = Some((temp, humidity));
+        self.implausible_weight = implausible_weight;
+        let round = |value: u32| (value.saturating_add(0x8000) >> 16) as u16;
+        let output = Measurement::from_raw_like(&measurement, round(temp), round(humidity));
+        Some(if implausible_weight > 0 {
+            output.flag_implausible()
+        } else {
+            output
+        })
+    }
+}
+
+/// Running median over the last `N` measurements.
+///
+/// The median is taken separately for temperature and humidity, so the
+/// output may combine values from different measurements. Until `N`
+/// measurements have been seen, the median of those available is used.
+/// Where the window holds an even number of measurements, the upper of the
+/// two middle values is used. The output is flagged as implausible while
+/// any measurement in the window is.
+#[derive(Debug, Clone, Copy)]
+pub struct Median<const N: usize> {
+    window: [(u16, u16, bool); N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> Median<N> {
+    /// Create a median filter over a window of `N` measurements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero.
+    pub fn new() -> Self {
+        assert!(N > 0, "median window must not be empty");
+        Self {
+            window: [(0, 0, false); N],
+            len: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<const N: usize> Default for Median<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Filter for Median<N> {
+    fn update(&mut self, measurement: Measurement) -> Option<Measurement> {
+        self.window[self.next] = (
+            measurement.raw_temperature_reading(),
+            measurement.raw_humidity_reading(),
+            !measurement.is_plausible(),
+        );
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+
+        let mut temps = [0u16; N];
+        let mut humidities = [0u16; N];
+        let mut implausible = false;
+        for (index, &(temp, humidity, flagged)) in self.window[..self.len].iter().enumerate() {
+            temps[index] = temp;
+            humidities[index] = humidity;
+            implausible |= flagged;
+        }
+        let temps = &mut temps[..self.len];
+        let humidities = &mut humidities[..self.len];
+        temps.sort_unstable();
+        humidities.sort_unstable();
+        let middle = self.len / 2;
+        let output = Measurement::from_raw_like(&measurement, temps[middle], humidities[middle]);
+        Some(if implausible {
+            output.flag_implausible()
+        } else {
+            output
+        })
+    }
+}
+
+/// Rejects measurements that jump too far from the last accepted one.
+///
+/// A measurement is rejected (the filter returns `None`) if its raw
+/// temperature or humidity differs from the last accepted measurement by
+/// more than the configured maximum step. So that a genuine, sudden change
+/// in conditions doesn't cause every later measurement to be rejected, a
+/// measurement is always accepted after `max_consecutive_rejections`
+/// measurements in a row have been rejected.
+///
+/// The first measurement is always accepted.
+#[derive(Debug, Clone, Copy)]
+pub struct OutlierRejection {
+    max_temp_step: u16,
+    max_humidity_step: u16,
+    max_consecutive_rejections: u8,
+    rejections: u8,
+    last: Option<Measurement>,
+}
+
+impl OutlierRejection {
+    /// Create a filter rejecting jumps of more than the given number of
+    /// raw ticks in temperature or humidity.
+    pub fn new(max_temp_step: u16, max_humidity_step: u16, max_consecutive_rejections: u8) -> Self {
+        Self {
+            max_temp_step,
+            max_humidity_step,
+            max_consecutive_rejections,
+            rejections: 0,
+            last: None,
+        }
+    }
+}
+
+impl Filter for OutlierRejection {
+    fn update(&mut self, measurement: Measurement) -> Option<Measurement> {
+        if let Some(last) = self.last {
+            let temp_step = measurement
+                .raw_temperature_reading()
+                .abs_diff(last.raw_temperature_reading());
+            let humidity_step = measurement
+                .raw_humidity_reading()
+                .abs_diff(last.raw_humidity_reading());
+            let is_outlier =
+                temp_step > self.max_temp_step || humidity_step > self.max_humidity_step;
+            if is_outlier && self.rejections < self.max_consecutive_rejections {
+                self.rejections += 1;
+                return None;
+            }
+        }
+        self.rejections = 0;
+        self.last = Some(measurement);
+        Some(measurement)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Ewma, Filter, Median, OutlierRejection};
+    #[cfg(feature = "float")]
+    use crate::common::HumidityClamp;
+    use crate::common::Measurement;
+
+    fn m(raw: u16) -> Measurement {
+        Measurement::from_raw(raw, raw)
+    }
+
+    fn raw(measurement: Option<Measurement>) -> Option<u16> {
+        measurement.map(|m| m.raw_temperature_reading())
+    }
+
+    #[test]
+    fn ewma_converges() {
+        let mut filter = Ewma::new(1);
+        assert_eq!(raw(filter.update(m(1000))), Some(1000));
+        assert_eq!(raw(filter.update(m(2000))), Some(1500));
+        assert_eq!(raw(filter.update(m(2000))), Some(1750));
+        assert_eq!(raw(filter.update(m(0))), Some(875));
+    }
+
+    #[test]
+    fn ewma_flags_output_while_implausible_input_has_weight() {
+        let mut filter = Ewma::new(1);
+        assert!(filter.update(m(1000)).unwrap().is_plausible());
+        assert!(
+            !filter
+                .update(m(1000).flag_implausible())
+                .unwrap()
+                .is_plausible()
+        );
+        assert!(!filter.update(m(1000)).unwrap().is_plausible());
+        let later = (0..20).filter_map(|_| filter.update(m(1000))).last();
+        assert!(later.unwrap().is_plausible());
+    }
+
+    #[test]
+    fn median_ignores_spike() {
+        let mut filter = Median::<3>::new();
+        assert_eq!(raw(filter.update(m(10))), Some(10));
+        assert_eq!(raw(filter.update(m(60_000))), Some(60_000));
+        assert_eq!(raw(filter.update(m(12))), Some(12));
+        assert_eq!(raw(filter.update(m(11))), Some(12));
+        assert_eq!(raw(filter.update(m(13))), Some(12));
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn output_keeps_humidity_clamp() {
+        let unclamped =
+            Measurement::from_raw(0x6666, 60_000).with_humidity_clamp(HumidityClamp::DontClamp);
+        let mut ewma = Ewma::new(1);
+        let mut median = Median::<3>::new();
+        for _ in 0..2 {
+            assert!(ewma.update(unclamped).unwrap().humidity() > 100.0);
+            assert!(median.update(unclamped).unwrap().humidity() > 100.0);
+        }
+    }
+
+    #[test]
+    fn outliers_rejected_until_limit() {
+        let mut filter = OutlierRejection::new(100, 100, 2);
+        assert_eq!(raw(filter.update(m(1000))), Some(1000));
+        assert_eq!(raw(filter.update(m(5000))), None);
+        assert_eq!(raw(filter.update(m(1050))), Some(1050));
+        assert_eq!(raw(filter.update(m(5000))), None);
+        assert_eq!(raw(filter.update(m(5000))), None);
+        assert_eq!(raw(filter.update(m(5000))), Some(5000));
+    }
+
+    #[test]
+    fn chain_stops_at_rejection() {
+        let mut filter = OutlierRejection::new(100, 100, 1).then(Ewma::new(1));
+        assert_eq!(raw(filter.update(m(1000))), Some(1000));
+        assert_eq!(raw(filter.update(m(3000))), None);
+        assert_eq!(raw(filter.update(m(1100))), Some(1050));
+    }
+}
@@ -0,0 +1,64 @@
+//! Hexadecimal formatting of serial numbers and raw sensor data.
+//!
+//! These functions write upper-case hex digits into a caller-provided
+//! buffer without using `core::fmt`, which can add several kilobytes to a
+//! firmware image. They are intended for reporting device IDs and raw
+//! frames over constrained links.
+//!
+//! ```
+//! use sht4x_rjw::hex;
+//!
+//! let mut buf = [0; 8];
+//! assert_eq!(hex::serial_number(0x0102_0304, &mut buf), "01020304");
+//!
+//! let mut buf = [0; 12];
+//! let frame = [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D];
+//! assert_eq!(hex::encode(&frame, &mut buf), Some("12343756787D"));
+//! ```
+const DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Write the serial number as 8 hex digits into `buf`, returning the
+/// digits as a string slice.
+pub fn serial_number(serial: u32, buf: &mut [u8; 8]) -> &str {
+    encode(&serial.to_be_bytes(), buf).expect("buffer holds 8 digits")
+}
+
+/// Write `bytes` as hex digits into the start of `buf`, two digits per byte,
+/// returning the digits as a string slice.
+///
+/// Returns `None` if `buf` is shorter than twice the length of `bytes`.
+pub fn encode<'a>(bytes: &[u8], buf: &'a mut [u8]) -> Option<&'a str> {
+    let out = buf.get_mut(..bytes.len().checked_mul(2)?)?;
+    for (pair, byte) in out.chunks_exact_mut(2).zip(bytes) {
+        pair[0] = DIGITS[usize::from(byte >> 4)];
+        pair[1] = DIGITS[usize::from(byte & 0x0F)];
+    }
+    // Only ASCII digits were written.
+    core::str::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{encode, serial_number};
+
+    #[test]
+    fn formats_serial_number() {
+        let mut buf = [0; 8];
+        assert_eq!(serial_number(0xDEAD_00BF, &mut buf), "DEAD00BF");
+        assert_eq!(serial_number(0, &mut buf), "00000000");
+    }
+
+    #[test]
+    fn encodes_into_larger_buffer() {
+        let mut buf = [b'x'; 8];
+        assert_eq!(encode(&[0xAB, 0x01, 0xF0], &mut buf), Some("AB01F0"));
+        assert_eq!(&buf[6..], b"xx");
+        assert_eq!(encode(&[], &mut buf), Some(""));
+    }
+
+    #[test]
+    fn rejects_small_buffer() {
+        let mut buf = [0; 5];
+        assert_eq!(encode(&[1, 2, 3], &mut buf), None);
+    }
+}
@@ -17,12 +17,65 @@
 //! - **[`defmt`]** support through the `defmt` feature flag.
 //! - **Fixed-point** conversions through the `fixed` feature flag and the
 //!   [`fixed`] crate.
+//! - **[`fugit`]** durations for measurement times, through the `fugit`
+//!   feature flag and [`common::ReadingMode::measurement_duration_fugit()`].
+//! - **Deadline-aware** async measurements with [`embassy-time`], through the
+//!   `embassy-time` feature flag and [`asynch::SHT4x::measure_by()`].
+//! - **Publishing** sampled measurements to [`embassy-sync`] channels, through
+//!   the `embassy-sync` feature flag and the [`publish`] module.
+//! - **Embassy sampling** on an [`embassy-time`] ticker, publishing each
+//!   measurement for other tasks, through the `embassy` feature flag (which
+//!   enables both of the above) and the [`embassy`] module.
+//! - **Streams** of measurements from the async driver, through the `futures`
+//!   feature flag and the [`stream`] module.
+//! - **Half-precision** conversions, returning [`half::f16`], through the
+//!   `half` feature flag.
+//! - **[`libm`]** for the exponentials and logarithms in derived quantities
+//!   such as the dew point, through the `libm` feature flag, or smaller and
+//!   faster approximations from [`micromath`] through the `micromath` feature
+//!   flag. Without either, the crate uses its own approximations.
+//! - **Generic float** conversions, for `f64` or software-float types, through
+//!   the `num-traits` feature flag and the `conversions::generic` module.
+//! - **Unit-safe** temperature and humidity quantities from [`uom`], through
+//!   the `uom` feature flag.
+//! - **Simulation** of a sensor with scripted temperature and humidity
+//!   profiles, for testing on the host, through the `simulator` feature flag
+//!   and the [`simulator`] module.
+//! - **[`serde`]** serialization of [`common::Config`] and the types it
+//!   holds, through the `serde` feature flag. [`common::Config::to_bytes()`]
+//!   provides a compact encoding without it.
+//! - **Non-blocking** measurements with [`nb`] semantics, for superloops and
+//!   RTIC tasks, through the `nb` feature flag and the [`nonblocking`] module.
+//!
+//! The [`compression`] module provides a compact delta encoding for series of
+//! measurements, for when readings are batched up and sent over slow links,
+//! and the [`resample`] module converts irregularly-timed measurements into
+//! an evenly-spaced series. The [`sampler`] module takes measurements at a
+//! fixed or adaptive interval, optionally smoothing them with the [`filter`] module and
+//! reducing them for reporting with the [`aggregate`] module. The [`retry`]
+//! module provides policies for retrying measurements after transient errors,
+//! and the [`hex`] module formats serial numbers without `core::fmt`. The
+//! [`calibration`] module persists calibration offsets and heater use, and
+//! the [`comfort`] module classifies measurements by thermal comfort. The
+//! [`mould`] module tracks the risk of mould growth over time.
 //!
 //! You can remove the blocking driver by passing `--no-default-features` to
 //! `cargo add`, or adding `default-features = false` to the dependency spec in
-//! your `Cargo.toml`.
+//! your `Cargo.toml`. This also removes the default `float` feature, which
+//! provides the floating-point conversions and derived quantities, so that no
+//! floating-point code is linked on soft-float targets. Measurements then offer
+//! only the raw, integer and (with the `fixed` feature) fixed-point conversions.
 //!
 //! [`defmt`]: https://defmt.ferrous-systems.com/
+//! [`embassy-sync`]: https://docs.rs/embassy-sync
+//! [`embassy-time`]: https://docs.rs/embassy-time
+//! [`fugit`]: https://docs.rs/fugit
+//! [`half::f16`]: https://docs.rs/half/latest/half/struct.f16.html
+//! [`libm`]: https://docs.rs/libm
+//! [`micromath`]: https://docs.rs/micromath
+//! [`nb`]: https://docs.rs/nb
+//! [`serde`]: https://serde.rs
+//! [`uom`]: https://docs.rs/uom
 //!
 //! ## Example usage
 //!
@@ -101,14 +154,18 @@
 //! have I2C addresses of `0x45` and `0x46`, respectively. See section 9 of the
 //! [datasheet].
 //!
-//! Should you need to use an address other than `0x44`, instantiate the struct
-//! as normal and write to its `address` field, as so:
+//! Should you need to use an address other than `0x44`, set the `address`
+//! field of the [`Config`], as so:
 //!
 //! ```
 //! # use sht4x_rjw::blocking::SHT4x;
 //! # let i2c = embedded_hal_mock::eh1::i2c::Mock::new(&[]);
-//! let mut sensor = SHT4x::new(i2c, Default::default());
-//! sensor.address = 0x46;
+//! use sht4x_rjw::common::{Address, Config};
+//! let config = Config {
+//!     address: Address::Alt0x46,
+//!     ..Default::default()
+//! };
+//! let mut sensor = SHT4x::new(i2c, config);
 //! # sensor.destroy().done()
 //! ```
 //!
@@ -151,12 +208,36 @@
 
 #![no_std]
 
+pub mod aggregate;
 #[cfg(feature = "async")]
 pub mod asynch;
 #[cfg(feature = "blocking")]
 pub mod blocking;
+pub mod calibration;
+pub mod clock;
+#[cfg(feature = "float")]
+pub mod comfort;
 pub mod common;
+pub mod compression;
 pub mod conversions;
+#[cfg(feature = "embassy")]
+pub mod embassy;
 pub mod error;
+pub mod filter;
+pub mod hex;
+#[cfg(feature = "float")]
+pub mod mould;
+#[cfg(feature = "nb")]
+pub mod nonblocking;
+#[cfg(feature = "embassy-sync")]
+pub mod publish;
+pub mod resample;
+pub mod retry;
+#[cfg(feature = "blocking")]
+pub mod sampler;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+#[cfg(feature = "futures")]
+pub mod stream;
 
 mod crc;
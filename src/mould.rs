@@ -0,0 +1,156 @@
+//! Accumulating mould growth risk.
+//!
+//! A single humid measurement says little about mould, which needs humid
+//! conditions to persist for days. [`MouldRisk`] tracks a mould index from
+//! successive measurements, rising while conditions favour growth and
+//! slowly falling while they don't, so building monitors can raise an alert
+//! on sustained dampness rather than on a passing shower.
+//!
+//! The index follows a simplified form of the VTT model of Hukka and
+//! Viitanen (1999), for pine sapwood:
+//!
+//! - 0: no growth
+//! - 1: some growth visible under a microscope
+//! - 3: some growth visible to the naked eye
+//! - 6: heavy, tight growth covering the surface
+//!
+//! Growth only occurs above a critical humidity, which is 80%RH above 20°C
+//! and rises towards 100%RH as the temperature approaches 0°C. The model
+//! describes the surface the sensor is measuring, so place the sensor at
+//! the surface of concern (such as an external wall) for meaningful results.
+//!
+//! ```
+//! use sht4x_rjw::mould::MouldRisk;
+//! # use sht4x_rjw::blocking::SHT4x;
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # fn main() -> anyhow::Result<()> {
+//! # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+//! # ];
+//! # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+//!
+//! let mut risk = MouldRisk::new();
+//! // Called once an hour, say.
+//! let measurement = sensor.measure(&mut delay)?;
+//! risk.update(&measurement, 3_600_000_000);
+//! assert_eq!(risk.index(), 0.0); // 56.5%RH is too dry for growth.
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+use crate::common::Measurement;
+use crate::conversions::{exp, ln};
+
+const US_PER_HOUR: u64 = 3_600_000_000;
+
+/// Mould growth index, accumulated from successive measurements.
+///
+/// See the [module documentation](crate::mould) for details.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MouldRisk {
+    index: f32,
+    /// Time since conditions last favoured growth, in microseconds.
+    dry_us: u64,
+}
+
+impl MouldRisk {
+    /// Create a tracker with an index of zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The current mould index, from 0 (no growth) to 6 (heavy growth).
+    pub fn index(&self) -> f32 {
+        self.index
+    }
+
+    /// Update the index, given that conditions have been as in
+    /// `measurement` for the last `dt_us` microseconds.
+    ///
+    /// Call this with each new measurement and the time since the previous
+    /// one. The model changes over days, so measuring every few minutes is
+    /// plenty.
+    pub fn update(&mut self, measurement: &Measurement, dt_us: u64) {
+        let t = measurement.celsius();
+        let rh = measurement.humidity();
+        let critical = critical_humidity(t);
+        if t > 0.0 && rh > critical {
+            self.dry_us = 0;
+            let days = dt_us as f32 / (24 * US_PER_HOUR) as f32;
+            // The index approaches a maximum that depends on how far the
+            // humidity is above the critical level.
+            let excess = (rh - critical) / (100.0 - critical);
+            let max_index = 1.0 + 7.0 * excess - 2.0 * excess * excess;
+            let k2 = (1.0 - exp(2.3 * (self.index - max_index))).max(0.0);
+            let rate = k2 / (7.0 * exp(-0.68 * ln(t) - 13.9 * ln(rh) + 66.02));
+            self.index = (self.index + rate * days).min(6.0);
+        } else {
+            let start = self.dry_us;
+            self.dry_us = self.dry_us.saturating_add(dt_us);
+            // Hours of the dry spell falling in each band of the decline.
+            let hours_between = |from: u64, to: u64| {
+                let overlap = self.dry_us.min(to).saturating_sub(start.max(from));
+                overlap as f32 / US_PER_HOUR as f32
+            };
+            let decline = 0.00133 * hours_between(0, 6 * US_PER_HOUR)
+                + 0.000667 * hours_between(24 * US_PER_HOUR, u64::MAX);
+            self.index = (self.index - decline).max(0.0);
+        }
+    }
+}
+
+/// Relative humidity above which mould can grow at `t` celsius.
+fn critical_humidity(t: f32) -> f32 {
+    if t <= 20.0 {
+        -0.00267 * t * t * t + 0.16 * t * t - 3.13 * t + 100.0
+    } else {
+        80.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MouldRisk, critical_humidity};
+    use crate::common::Measurement;
+
+    const DAY: u64 = 86_400_000_000;
+
+    #[test]
+    fn critical_humidity_is_continuous() {
+        assert!((critical_humidity(20.0) - 80.0).abs() < 0.1);
+        assert!(critical_humidity(5.0) > 85.0);
+    }
+
+    #[test]
+    fn grows_in_sustained_damp_and_declines_when_dry() {
+        let mut risk = MouldRisk::new();
+        let damp = Measurement::from_celsius_and_percent_rh(20.0, 97.0);
+        for _ in 0..10 {
+            risk.update(&damp, DAY);
+        }
+        // About one index point per ten days at 20°C and 97%RH.
+        assert!((0.8..1.2).contains(&risk.index()), "{}", risk.index());
+        let grown = risk.index();
+
+        // No growth below the critical humidity, and a slow decline.
+        risk.update(&Measurement::from_celsius_and_percent_rh(20.0, 70.0), DAY);
+        let declined = grown - 0.00133 * 6.0;
+        assert!((risk.index() - declined).abs() < 1e-5, "{}", risk.index());
+        risk.update(&Measurement::from_celsius_and_percent_rh(20.0, 70.0), DAY);
+        assert!((risk.index() - (declined - 0.000667 * 24.0)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn index_is_limited_by_humidity() {
+        let mut risk = MouldRisk::new();
+        for _ in 0..1_000 {
+            risk.update(&Measurement::from_celsius_and_percent_rh(25.0, 85.0), DAY);
+        }
+        // The maximum index for 85%RH is 1 + 7 × 0.25 - 2 × 0.25².
+        assert!(risk.index() < 2.625);
+        assert!(risk.index() > 2.0);
+    }
+}
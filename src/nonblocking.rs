@@ -0,0 +1,273 @@
+//! Non-blocking measurements with [`nb`] semantics.
+//!
+//! [`SHT4x`] wraps the [blocking driver](crate::blocking::SHT4x) so that
+//! measuring never waits: the first call to [`SHT4x::measure()`] sends the
+//! measurement command, and later calls return [`nb::Error::WouldBlock`]
+//! until the sensor has had time to complete it, then return the
+//! measurement. This fits superloops and RTIC tasks that poll their
+//! peripherals, and needs a [`Clock`] rather than a `DelayNs`.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # use core::cell::Cell;
+//! # fn main() -> anyhow::Result<()> {
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! # ];
+//! # let i2c = Mock::new(&expectations);
+//! use sht4x_rjw::{blocking, nonblocking};
+//!
+//! let now_us = Cell::new(0);
+//! let mut sensor = nonblocking::SHT4x::new(
+//!     blocking::SHT4x::new(i2c, Default::default()),
+//!     || now_us.get(),
+//! );
+//!
+//! // The measurement is started, but not yet complete.
+//! assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+//! now_us.set(8_300);
+//! // The measurement is complete, so this returns without blocking.
+//! let measurement = nb::block!(sensor.measure())?;
+//! # let (sensor, _) = sensor.destroy();
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use [`nb::block!`] to wait for a measurement where blocking is acceptable.
+//!
+//! The blocking driver's [`Config`] applies, with some exceptions, as this
+//! driver never waits and takes exactly one measurement for each one it
+//! returns:
+//!
+//! - [`Config::discard_first_measurement`] is ignored, so no measurement is
+//!   discarded after construction or a soft reset.
+//! - [`Config::retry`] is ignored. An error is returned as soon as it
+//!   occurs, and the next call starts a new measurement.
+//! - [`Config::cache_max_age_us`] is ignored, so every measurement is read
+//!   from the sensor.
+//! - [`DelayMode::Poll`] doesn't poll the sensor, but waits for the maximum
+//!   delay for the reading mode.
+//!
+//! [`Config`]: crate::common::Config
+//! [`Config::discard_first_measurement`]: crate::common::Config::discard_first_measurement
+//! [`Config::retry`]: crate::common::Config::retry
+//! [`Config::cache_max_age_us`]: crate::common::Config::cache_max_age_us
+//!
+//! [`nb`]: https://docs.rs/nb
+use embedded_hal::i2c::I2c;
+
+use crate::blocking;
+use crate::clock::Clock;
+use crate::common::{DelayMode, Measurement, ReadingMode, Sensor};
+use crate::error::Error;
+
+/// SHT4x driver that measures without blocking.
+///
+/// See the [module documentation](crate::nonblocking) for an example.
+pub struct SHT4x<I: I2c, C: Clock> {
+    sensor: blocking::SHT4x<I>,
+    clock: C,
+    /// When the measurement in progress, if any, will be complete.
+    ready_at_us: Option<u64>,
+    /// When the last measurement started will be complete, so that a new
+    /// one isn't started while a cancelled one is still running.
+    busy_until_us: u64,
+}
+
+impl<I: I2c, C: Clock> SHT4x<I, C> {
+    /// Wrap a blocking driver, using `clock` to tell when each measurement
+    /// is complete.
+    pub const fn new(sensor: blocking::SHT4x<I>, clock: C) -> Self {
+        Self {
+            sensor,
+            clock,
+            ready_at_us: None,
+            busy_until_us: 0,
+        }
+    }
+
+    /// Destroy the driver, returning the blocking driver and the clock.
+    ///
+    /// If a measurement is in progress, the sensor may not accept commands
+    /// until it completes.
+    pub fn destroy(self) -> (blocking::SHT4x<I>, C) {
+        (self.sensor, self.clock)
+    }
+
+    /// Whether a measurement has been started but not yet returned.
+    pub fn is_measuring(&self) -> bool {
+        self.ready_at_us.is_some()
+    }
+
+    /// Abandon the measurement in progress, if any, so that the next call
+    /// to [`SHT4x::measure()`] starts a new one, for instance with
+    /// different settings.
+    ///
+    /// The sensor can't be interrupted, so the new measurement isn't
+    /// started until the abandoned one would have completed.
+    pub fn cancel(&mut self) {
+        self.ready_at_us = None;
+    }
+
+    /// Measure temperature and humidity with the settings in the blocking
+    /// driver's [`Config`](crate::common::Config).
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure_with_settings()`].
+    pub fn measure(&mut self) -> nb::Result<Measurement, Error<I::Error>> {
+        let config = self.sensor.config();
+        self.measure_with_settings(config.reading_mode, config.delay_mode)
+    }
+
+    /// Measure temperature and humidity with the given settings.
+    ///
+    /// If no measurement is in progress, this sends the measurement command
+    /// and returns [`nb::Error::WouldBlock`]. Until the delay for
+    /// `reading_mode` and `delay_mode` has passed, as measured by the
+    /// clock, it continues to return `WouldBlock`. After that, it reads and
+    /// returns the measurement, and the next call starts a new one.
+    ///
+    /// The settings are only used to start a measurement, so changing them
+    /// while one is in progress has no effect until it has been returned.
+    ///
+    /// The blocking driver's [`Config::rate_limit`] applies to the start of
+    /// each measurement, using the clock. With [`RateLimitAction::Wait`],
+    /// this returns `WouldBlock` until the limit allows a new measurement.
+    ///
+    /// [`Config::rate_limit`]: crate::common::Config::rate_limit
+    /// [`RateLimitAction::Wait`]: crate::common::RateLimitAction::Wait
+    ///
+    /// # Errors
+    ///
+    /// An error may be returned if either the temperature or humidity
+    /// data bytes fail to pass CRC validation, or if a problem occurs
+    /// with the I2C interface. Either way the measurement in progress is
+    /// abandoned, and the next call starts a new one. With
+    /// [`RateLimitAction::Reject`], [`Error::TooSoon`] is returned if the
+    /// rate limit refuses a new measurement.
+    ///
+    /// [`RateLimitAction::Reject`]: crate::common::RateLimitAction::Reject
+    pub fn measure_with_settings(
+        &mut self,
+        reading_mode: ReadingMode,
+        delay_mode: DelayMode,
+    ) -> nb::Result<Measurement, Error<I::Error>> {
+        let now_us = self.sensor.note_time(&self.clock);
+        let Some(ready_at_us) = self.ready_at_us else {
+            // Rather than waiting for the rate limit or a cancelled
+            // measurement, keep returning `WouldBlock` until the sensor
+            // can start this one.
+            if now_us < self.busy_until_us || self.sensor.rate_limit_wait_us(now_us) > 0 {
+                return Err(nb::Error::WouldBlock);
+            }
+            self.sensor
+                .send_measurement_command(Sensor::Configured, reading_mode, Some(now_us))?;
+            let us = self.sensor.config().delay_us(reading_mode, delay_mode);
+            self.busy_until_us = now_us.saturating_add(u64::from(us));
+            self.ready_at_us = Some(self.busy_until_us);
+            return Err(nb::Error::WouldBlock);
+        };
+        if now_us < ready_at_us {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.ready_at_us = None;
+        Ok(self.sensor.read_measurement(Sensor::Configured)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use core::cell::Cell;
+    use std::vec;
+
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+    use super::SHT4x;
+    use crate::blocking;
+    use crate::error::Error;
+
+    const MEASUREMENT: [u8; 6] = [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D];
+
+    #[test]
+    fn would_block_until_ready() {
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let now_us = Cell::new(0);
+        let blocking = blocking::SHT4x::new(Mock::new(&expectations), Default::default());
+        let mut sensor = SHT4x::new(blocking, || now_us.get());
+
+        assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+        assert!(sensor.is_measuring());
+        now_us.set(6_899);
+        assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+        now_us.set(6_900);
+        let measurement = sensor.measure().unwrap();
+        assert_eq!(measurement.raw_temperature_reading(), 0x1234);
+        assert!(!sensor.is_measuring());
+
+        let (blocking, _) = sensor.destroy();
+        blocking.destroy().done();
+    }
+
+    #[test]
+    fn crc_failure_abandons_measurement() {
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, vec![0x12, 0x34, 0x00, 0x56, 0x78, 0x7D]),
+            Transaction::write(0x44, vec![0xFD]),
+        ];
+        let now_us = Cell::new(0);
+        let blocking = blocking::SHT4x::new(Mock::new(&expectations), Default::default());
+        let mut sensor = SHT4x::new(blocking, || now_us.get());
+
+        assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+        now_us.set(8_300);
+        assert!(matches!(
+            sensor.measure(),
+            Err(nb::Error::Other(Error::CrcValidationFailed { .. }))
+        ));
+        assert!(!sensor.is_measuring());
+        // The next call starts a new measurement.
+        assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+        assert!(sensor.is_measuring());
+
+        let (blocking, _) = sensor.destroy();
+        blocking.destroy().done();
+    }
+
+    #[test]
+    fn restart_after_cancel_waits_for_the_sensor() {
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, MEASUREMENT.to_vec()),
+        ];
+        let now_us = Cell::new(0);
+        let blocking = blocking::SHT4x::new(Mock::new(&expectations), Default::default());
+        let mut sensor = SHT4x::new(blocking, || now_us.get());
+
+        assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+        now_us.set(1_000);
+        sensor.cancel();
+        assert!(!sensor.is_measuring());
+        // The sensor is still busy with the cancelled measurement.
+        assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+        assert!(!sensor.is_measuring());
+        now_us.set(8_300);
+        assert!(matches!(sensor.measure(), Err(nb::Error::WouldBlock)));
+        assert!(sensor.is_measuring());
+        now_us.set(16_600);
+        assert_eq!(sensor.measure().unwrap().raw_temperature_reading(), 0x1234);
+
+        let (blocking, _) = sensor.destroy();
+        blocking.destroy().done();
+    }
+}
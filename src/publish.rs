@@ -0,0 +1,106 @@
+//! Publishing measurements to [`embassy-sync`] channels.
+//!
+//! In firmware with several tasks interested in the current temperature
+//! and humidity (a display, a logger, a control loop), it is convenient for
+//! one task to own the sensor and publish each new measurement for the
+//! others to observe. The [`Publish`] trait is implemented for `embassy-sync`
+//! [`Watch`]es and [`PubSubChannel`]s and their senders and publishers, and
+//! [`PeriodicSampler::publish_next_measurement()`] takes a measurement and
+//! publishes it in one step.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # fn main() -> anyhow::Result<()> {
+//! # let delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! # ];
+//! # let i2c = Mock::new(&expectations);
+//! # let clock = || 0;
+//! use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+//! use embassy_sync::watch::Watch;
+//! use sht4x_rjw::blocking::SHT4x;
+//! use sht4x_rjw::common::Measurement;
+//! use sht4x_rjw::sampler::PeriodicSampler;
+//!
+//! let latest: Watch<NoopRawMutex, Measurement, 2> = Watch::new();
+//! let mut display = latest.receiver().unwrap();
+//!
+//! let sensor = SHT4x::new(i2c, Default::default());
+//! let mut sampler = PeriodicSampler::new(sensor, delay, clock, 2_000_000);
+//! sampler.publish_next_measurement(&latest)?;
+//!
+//! assert!(display.try_changed().is_some());
+//! # let (sensor, _, _) = sampler.destroy();
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`embassy-sync`]: https://docs.rs/embassy-sync
+//! [`PeriodicSampler::publish_next_measurement()`]: crate::sampler::PeriodicSampler::publish_next_measurement
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::pubsub::{ImmediatePublisher, PubSubChannel, Publisher};
+use embassy_sync::watch::{Sender, Watch};
+
+use crate::common::Measurement;
+
+/// A destination for new measurements.
+pub trait Publish {
+    /// Make `measurement` available to observers, without waiting.
+    fn publish(&mut self, measurement: Measurement);
+}
+
+impl<P> Publish for &mut P
+where
+    P: Publish + ?Sized,
+{
+    fn publish(&mut self, measurement: Measurement) {
+        (**self).publish(measurement)
+    }
+}
+
+/// Replaces the watched value, waking all receivers.
+impl<M: RawMutex, const N: usize> Publish for &Watch<M, Measurement, N> {
+    fn publish(&mut self, measurement: Measurement) {
+        self.sender().send(measurement)
+    }
+}
+
+/// Replaces the watched value, waking all receivers.
+impl<M: RawMutex, const N: usize> Publish for Sender<'_, M, Measurement, N> {
+    fn publish(&mut self, measurement: Measurement) {
+        self.send(measurement)
+    }
+}
+
+/// Publishes immediately, so if the queue is full the oldest message is
+/// dropped and lagging subscribers are notified.
+impl<M: RawMutex, const CAP: usize, const SUBS: usize, const PUBS: usize> Publish
+    for &PubSubChannel<M, Measurement, CAP, SUBS, PUBS>
+{
+    fn publish(&mut self, measurement: Measurement) {
+        self.immediate_publisher().publish_immediate(measurement)
+    }
+}
+
+/// Publishes immediately, so if the queue is full the oldest message is
+/// dropped and lagging subscribers are notified.
+impl<M: RawMutex, const CAP: usize, const SUBS: usize, const PUBS: usize> Publish
+    for Publisher<'_, M, Measurement, CAP, SUBS, PUBS>
+{
+    fn publish(&mut self, measurement: Measurement) {
+        self.publish_immediate(measurement)
+    }
+}
+
+/// Publishes immediately, so if the queue is full the oldest message is
+/// dropped and lagging subscribers are notified.
+impl<M: RawMutex, const CAP: usize, const SUBS: usize, const PUBS: usize> Publish
+    for ImmediatePublisher<'_, M, Measurement, CAP, SUBS, PUBS>
+{
+    fn publish(&mut self, measurement: Measurement) {
+        self.publish_immediate(measurement)
+    }
+}
@@ -0,0 +1,178 @@
+//! Resampling of irregularly-timed measurements onto a fixed interval.
+//!
+//! Measurements rarely arrive exactly on schedule: tasks are delayed,
+//! heater measurements take longer than others, and readings may be
+//! skipped after errors. Some analyses (control loops with a fixed period,
+//! FFT-style frequency analysis) need evenly-spaced samples, which
+//! [`Resampler`] produces by linearly interpolating between the readings
+//! on either side of each sample time.
+//!
+//! Timestamps are plain `u64`s in whatever unit you like (microseconds,
+//! if you are using a [`Clock`](crate::clock::Clock)), and must not
+//! decrease.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # use sht4x_rjw::blocking::SHT4x;
+//! # fn main() -> anyhow::Result<()> {
+//! # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x36, 0x55, 0x56, 0x76, 0x62]),
+//! # ];
+//! # let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+//! use sht4x_rjw::resample::Resampler;
+//!
+//! // Readings taken at 0ms and 25ms...
+//! let readings = [
+//!     (0, sensor.measure(&mut delay)?),
+//!     (25_000, sensor.measure(&mut delay)?),
+//! ];
+//! // ...resampled every 10ms.
+//! let resampled: Vec<_> = Resampler::new(readings.into_iter(), 10_000).collect();
+//! assert_eq!(resampled.len(), 3);
+//! assert_eq!(resampled[2].0, 20_000);
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+use crate::common::Measurement;
+
+/// Iterator adapter that resamples timestamped measurements at a fixed
+/// interval.
+///
+/// The first output sample is at the timestamp of the first input
+/// measurement, and output continues at each multiple of the interval
+/// after that until the input measurements run out. Each output is a pair
+/// of the sample time and the interpolated measurement.
+///
+/// See the [module documentation](crate::resample) for an example.
+pub struct Resampler<I> {
+    input: I,
+    interval: u64,
+    next_time: u64,
+    before: Option<(u64, Measurement)>,
+    after: Option<(u64, Measurement)>,
+}
+
+impl<I> Resampler<I>
+where
+    I: Iterator<Item = (u64, Measurement)>,
+{
+    /// Resample `input` at every `interval` time units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is zero.
+    pub fn new(input: I, interval: u64) -> Self {
+        assert!(
+            interval > 0,
+            "resampling interval must be greater than zero"
+        );
+        Self {
+            input,
+            interval,
+            next_time: 0,
+            before: None,
+            after: None,
+        }
+    }
+}
+
+impl<I> Iterator for Resampler<I>
+where
+    I: Iterator<Item = (u64, Measurement)>,
+{
+    type Item = (u64, Measurement);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (mut t0, mut m0) = match self.before {
+            Some(before) => before,
+            None => {
+                let first = self.input.next()?;
+                self.before = Some(first);
+                self.next_time = first.0;
+                first
+            }
+        };
+        let target = self.next_time;
+
+        // Advance through the input until the target lies between the
+        // measurements either side of it.
+        let result = loop {
+            if target == t0 {
+                break m0;
+            }
+            let (t1, m1) = match self.after {
+                Some(after) => after,
+                None => *self.after.insert(self.input.next()?),
+            };
+            if t1 < target {
+                (t0, m0) = (t1, m1);
+                self.before = Some((t1, m1));
+                self.after = None;
+                continue;
+            }
+            break m0.lerp_ratio(&m1, target - t0, t1 - t0);
+        };
+
+        self.next_time = target.checked_add(self.interval)?;
+        Some((target, result))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Resampler;
+    use crate::common::Measurement;
+
+    fn readings(points: &[(u64, u16)]) -> impl Iterator<Item = (u64, Measurement)> + '_ {
+        points
+            .iter()
+            .map(|&(t, raw)| (t, Measurement::from_raw(raw, raw)))
+    }
+
+    fn resample(points: &[(u64, u16)], interval: u64) -> ([(u64, u16); 8], usize) {
+        let mut out = [(0, 0); 8];
+        let mut count = 0;
+        for (slot, (t, m)) in out
+            .iter_mut()
+            .zip(Resampler::new(readings(points), interval))
+        {
+            *slot = (t, m.raw_temperature_reading());
+            count += 1;
+        }
+        (out, count)
+    }
+
+    #[test]
+    fn interpolates_between_readings() {
+        let (out, count) = resample(&[(100, 0), (200, 1000), (350, 400)], 50);
+        assert_eq!(count, 6);
+        assert_eq!(
+            out[..count],
+            [
+                (100, 0),
+                (150, 500),
+                (200, 1000),
+                (250, 800),
+                (300, 600),
+                (350, 400)
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_readings_between_samples() {
+        let (out, count) = resample(&[(0, 0), (10, 5000), (20, 10), (100, 1000)], 100);
+        assert_eq!(out[..count], [(0, 0), (100, 1000)]);
+    }
+
+    #[test]
+    fn handles_decreasing_values_and_duplicate_timestamps() {
+        let (out, count) = resample(&[(0, 1000), (0, 900), (30, 0)], 10);
+        assert_eq!(out[..count], [(0, 1000), (10, 600), (20, 300), (30, 0)]);
+    }
+}
@@ -0,0 +1,297 @@
+//! Policies for retrying failed sensor operations.
+//!
+//! I2C errors and CRC failures are usually transient, caused by electrical
+//! noise or a busy bus, and an operation that fails once will often succeed
+//! if tried again. A [`RetryPolicy`] decides whether a failed operation is
+//! retried, and how long to wait before doing so.
+//!
+//! Pass a policy to `measure_with_retry()` or `serial_number_with_retry()`
+//! on either driver. The built-in policies are:
+//!
+//! - [`NoRetry`], which never retries.
+//! - [`FixedRetry`], which retries a fixed number of times with a constant
+//!   delay between attempts.
+//! - [`ExponentialBackoff`], which doubles the delay after each attempt, up
+//!   to a maximum, optionally with random jitter so that several devices
+//!   sharing a bus don't retry in lockstep.
+//!
+//! The built-in policies only retry [transient] errors.
+//!
+//! To retry without passing a policy each time, set [`Config::retry`] to a
+//! [`RetryConfig`], which `measure()` then follows automatically.
+//! `serial_number()` takes no delay, so it uses the number of attempts but
+//! retries immediately, ignoring the backoff.
+//!
+//! [transient]: crate::error::Sht4xErrorKind::is_transient
+//! [`Config::retry`]: crate::common::Config::retry
+use crate::error::Sht4xErrorKind;
+
+/// Decides whether, and when, a failed operation is retried.
+pub trait RetryPolicy {
+    /// Called after an attempt fails with `error`.
+    ///
+    /// `failures` is the number of attempts that have failed so far, so is
+    /// 1 after the first failure. Return the number of microseconds to wait
+    /// before trying again, or `None` to give up and return the error.
+    fn retry_after_us(&mut self, failures: u32, error: Sht4xErrorKind) -> Option<u32>;
+}
+
+impl<P> RetryPolicy for &mut P
+where
+    P: RetryPolicy + ?Sized,
+{
+    fn retry_after_us(&mut self, failures: u32, error: Sht4xErrorKind) -> Option<u32> {
+        (**self).retry_after_us(failures, error)
+    }
+}
+
+/// Never retry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn retry_after_us(&mut self, _failures: u32, _error: Sht4xErrorKind) -> Option<u32> {
+        None
+    }
+}
+
+/// Retry transient errors up to `max_retries` times, waiting `delay_us`
+/// microseconds before each retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FixedRetry {
+    /// The maximum number of retries, not counting the first attempt.
+    pub max_retries: u32,
+    /// The delay before each retry, in microseconds.
+    pub delay_us: u32,
+}
+
+impl FixedRetry {
+    /// Create a policy with the given number of retries and delay.
+    pub fn new(max_retries: u32, delay_us: u32) -> Self {
+        Self {
+            max_retries,
+            delay_us,
+        }
+    }
+}
+
+impl RetryPolicy for FixedRetry {
+    fn retry_after_us(&mut self, failures: u32, error: Sht4xErrorKind) -> Option<u32> {
+        (error.is_transient() && failures <= self.max_retries).then_some(self.delay_us)
+    }
+}
+
+/// Retry transient errors up to `max_retries` times, doubling the delay
+/// before each retry up to a maximum.
+///
+/// The first retry waits `initial_delay_us`, the second twice that, and so
+/// on, up to `max_delay_us`. With jitter enabled by
+/// [`ExponentialBackoff::with_jitter()`], each delay is instead chosen at
+/// random between half and all of that value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ExponentialBackoff {
+    max_retries: u32,
+    initial_delay_us: u32,
+    max_delay_us: u32,
+    /// State of the xorshift generator used for jitter, or `None` if jitter
+    /// is disabled.
+    rng: Option<u32>,
+}
+
+impl ExponentialBackoff {
+    /// Create a policy with the given number of retries and delay limits,
+    /// without jitter.
+    pub fn new(max_retries: u32, initial_delay_us: u32, max_delay_us: u32) -> Self {
+        Self {
+            max_retries,
+            initial_delay_us,
+            max_delay_us,
+            rng: None,
+        }
+    }
+
+    /// Enable jitter, seeding the random number generator with `seed`.
+    ///
+    /// Use a seed that differs between devices, such as the sensor serial
+    /// number, so that their retries are spread out.
+    pub fn with_jitter(self, seed: u32) -> Self {
+        Self {
+            // Xorshift gets stuck at zero, so avoid it.
+            rng: Some(if seed == 0 { 0x9E37_79B9 } else { seed }),
+            ..self
+        }
+    }
+
+    fn next_random(&mut self) -> Option<u32> {
+        let state = self.rng.as_mut()?;
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        Some(*state)
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_after_us(&mut self, failures: u32, error: Sht4xErrorKind) -> Option<u32> {
+        if !error.is_transient() || failures > self.max_retries {
+            return None;
+        }
+        let shift = failures.saturating_sub(1).min(31);
+        let delay = self
+            .initial_delay_us
+            .saturating_mul(1 << shift)
+            .min(self.max_delay_us);
+        Some(match self.next_random() {
+            Some(random) => delay - (random % (delay / 2 + 1)),
+            None => delay,
+        })
+    }
+}
+
+/// Retry settings stored in a [`Config`](crate::common::Config), and
+/// followed automatically by `measure()` on either driver.
+///
+/// `serial_number()` also uses `max_attempts`, but as it takes no delay
+/// it ignores `backoff` and retries immediately. Pass these settings to
+/// `serial_number_with_retry()` to wait between attempts.
+///
+/// Like the built-in policies, only [transient] errors (such as CRC
+/// failures and reads made before the measurement is ready) are retried.
+/// Other errors are returned immediately, including a NACK of the
+/// measurement command, which is reported as
+/// [`Error::NotPresent`](crate::error::Error::NotPresent).
+///
+/// [transient]: crate::error::Sht4xErrorKind::is_transient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetryConfig {
+    /// The maximum number of attempts, including the first. Zero or one
+    /// disables retries.
+    pub max_attempts: u32,
+    /// How long to wait before each retry. Ignored by `serial_number()`,
+    /// which has no delay to wait with.
+    pub backoff: Backoff,
+}
+
+/// How long to wait before each retry, for a [`RetryConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Backoff {
+    /// Wait the same time before each retry, as with [`FixedRetry`].
+    Fixed {
+        /// The delay before each retry, in microseconds.
+        delay_us: u32,
+    },
+    /// Double the wait before each retry up to a maximum, as with
+    /// [`ExponentialBackoff`] (without jitter).
+    Exponential {
+        /// The delay before the first retry, in microseconds.
+        initial_delay_us: u32,
+        /// The longest delay before any retry, in microseconds.
+        max_delay_us: u32,
+    },
+}
+
+impl RetryConfig {
+    /// Make a single attempt, without retrying. This is the default.
+    pub const NONE: Self = Self::new(1, Backoff::Fixed { delay_us: 0 });
+
+    /// Create retry settings with the given number of attempts and backoff.
+    pub const fn new(max_attempts: u32, backoff: Backoff) -> Self {
+        Self {
+            max_attempts,
+            backoff,
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+impl RetryPolicy for RetryConfig {
+    fn retry_after_us(&mut self, failures: u32, error: Sht4xErrorKind) -> Option<u32> {
+        let max_retries = self.max_attempts.saturating_sub(1);
+        match self.backoff {
+            Backoff::Fixed { delay_us } => {
+                FixedRetry::new(max_retries, delay_us).retry_after_us(failures, error)
+            }
+            Backoff::Exponential {
+                initial_delay_us,
+                max_delay_us,
+            } => ExponentialBackoff::new(max_retries, initial_delay_us, max_delay_us)
+                .retry_after_us(failures, error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+    use super::{Backoff, ExponentialBackoff, FixedRetry, NoRetry, RetryConfig, RetryPolicy};
+    use crate::error::Sht4xErrorKind;
+
+    const NACK: Sht4xErrorKind =
+        Sht4xErrorKind::I2c(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+    const FATAL: Sht4xErrorKind = Sht4xErrorKind::I2c(ErrorKind::Other);
+
+    #[test]
+    fn no_retry() {
+        assert_eq!(NoRetry.retry_after_us(1, NACK), None);
+    }
+
+    #[test]
+    fn fixed_retry() {
+        let mut policy = FixedRetry::new(2, 500);
+        assert_eq!(policy.retry_after_us(1, NACK), Some(500));
+        assert_eq!(policy.retry_after_us(2, NACK), Some(500));
+        assert_eq!(policy.retry_after_us(3, NACK), None);
+        assert_eq!(policy.retry_after_us(1, FATAL), None);
+    }
+
+    #[test]
+    fn exponential_backoff_doubles_up_to_max() {
+        let mut policy = ExponentialBackoff::new(5, 100, 500);
+        let delays = [1, 2, 3, 4, 5, 6].map(|failures| policy.retry_after_us(failures, NACK));
+        assert_eq!(
+            delays,
+            [Some(100), Some(200), Some(400), Some(500), Some(500), None]
+        );
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let mut policy = ExponentialBackoff::new(u32::MAX, 1000, 1000).with_jitter(0);
+        let mut delays = [0; 32];
+        for delay in delays.iter_mut() {
+            *delay = policy.retry_after_us(1, NACK).unwrap();
+            assert!((500..=1000).contains(delay));
+        }
+        assert!(delays.iter().any(|&delay| delay != delays[0]));
+    }
+
+    #[test]
+    fn retry_config() {
+        let mut none = RetryConfig::default();
+        assert_eq!(none.retry_after_us(1, NACK), None);
+
+        let mut exponential = RetryConfig::new(
+            3,
+            Backoff::Exponential {
+                initial_delay_us: 100,
+                max_delay_us: 150,
+            },
+        );
+        let delays = [1, 2, 3].map(|failures| exponential.retry_after_us(failures, NACK));
+        assert_eq!(delays, [Some(100), Some(150), None]);
+        assert_eq!(exponential.retry_after_us(1, FATAL), None);
+    }
+}
@@ -0,0 +1,655 @@
+//! Periodic sampling with the blocking driver.
+//!
+//! [`PeriodicSampler`] owns a [blocking driver][SHT4x], a delay and a
+//! [`Clock`], and takes a measurement at a fixed interval each time
+//! [`PeriodicSampler::next_measurement()`] is called, passing each
+//! measurement through an optional [`Filter`]. With
+//! [`PeriodicSampler::next_aggregate()`], the filtered measurements can be
+//! reduced by an [`Aggregator`] for less frequent reporting.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # use core::cell::Cell;
+//! # fn main() -> anyhow::Result<()> {
+//! # let delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! # ];
+//! # let i2c = Mock::new(&expectations);
+//! # let now = Cell::new(0);
+//! # let clock = || now.get();
+//! use sht4x_rjw::blocking::SHT4x;
+//! use sht4x_rjw::filter::Median;
+//! use sht4x_rjw::sampler::PeriodicSampler;
+//!
+//! let sensor = SHT4x::new(i2c, Default::default());
+//! // Measure every 2 seconds, taking the median of the last 5 measurements.
+//! let mut sampler =
+//!     PeriodicSampler::new(sensor, delay, clock, 2_000_000).with_filter(Median::<5>::new());
+//! if let Some(measurement) = sampler.next_measurement()? {
+//!     println!("{}°C", measurement.celsius());
+//! }
+//! # let (sensor, _, _) = sampler.destroy();
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`AdaptiveSampler`] instead varies its interval between bounds, sampling
+//! quickly while readings are changing and backing off while they are
+//! stable, which suits battery-powered loggers.
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::aggregate::Aggregator;
+use crate::blocking::SHT4x;
+use crate::clock::Clock;
+use crate::common::Measurement;
+use crate::error::Error;
+use crate::filter::{Filter, NoFilter};
+#[cfg(feature = "embassy-sync")]
+use crate::publish::Publish;
+
+/// A sampling cycle that didn't keep to the sampler's schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Overrun {
+    /// Taking the measurement took longer than the sampling interval, so
+    /// the schedule can't be kept even if the consumer is never late.
+    ///
+    /// This usually means the reading mode (especially a heater mode) or
+    /// delay mode is too slow for the interval.
+    MeasurementTooLong {
+        /// How long the measurement took, in microseconds.
+        duration_us: u64,
+    },
+    /// [`PeriodicSampler::next_measurement()`] was called after the
+    /// scheduled start of the next measurement, because the consumer took
+    /// too long to process the previous one.
+    ConsumerTooSlow {
+        /// How far past the scheduled start the call came, in microseconds,
+        /// or past the end of the previous measurement if that was later.
+        late_by_us: u64,
+        /// The number of whole sampling intervals that were skipped.
+        missed_samples: u32,
+    },
+}
+
+/// Running totals of [`Overrun`]s seen by a [`PeriodicSampler`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OverrunStats {
+    /// Number of measurements that took longer than the sampling interval.
+    pub measurement_overruns: u32,
+    /// Number of calls that came after the scheduled measurement time.
+    pub consumer_overruns: u32,
+    /// Total number of whole sampling intervals skipped due to late calls.
+    pub missed_samples: u32,
+}
+
+impl OverrunStats {
+    fn record(&mut self, overrun: Overrun) {
+        match overrun {
+            Overrun::MeasurementTooLong { .. } => {
+                self.measurement_overruns = self.measurement_overruns.saturating_add(1);
+            }
+            Overrun::ConsumerTooSlow { missed_samples, .. } => {
+                self.consumer_overruns = self.consumer_overruns.saturating_add(1);
+                self.missed_samples = self.missed_samples.saturating_add(missed_samples);
+            }
+        }
+    }
+}
+
+/// Takes measurements at a fixed interval, passing them through a filter.
+///
+/// Measurements are taken with the sensor's default settings (from its
+/// [`Config`](crate::common::Config)). The interval is measured from the
+/// start of one measurement to the start of the next, using the clock, so
+/// the time your program spends between calls to
+/// [`PeriodicSampler::next_measurement()`] doesn't cause the schedule to
+/// drift. If a call comes too late for the scheduled start time, the
+/// measurement is taken immediately and the schedule restarts from then.
+///
+/// Rather than drifting silently, the sampler records each time it falls
+/// behind schedule as an [`Overrun`], either because the measurement itself
+/// took longer than the interval or because the consumer was too slow to
+/// call again. Check [`PeriodicSampler::overrun_stats()`] for running totals,
+/// or [`PeriodicSampler::take_overrun()`] for the most recent overrun.
+///
+/// See the [module documentation](crate::sampler) for an example.
+pub struct PeriodicSampler<I: I2c, D, C, F = NoFilter> {
+    sensor: SHT4x<I>,
+    delay: D,
+    clock: C,
+    filter: F,
+    interval_us: u32,
+    next_start_us: Option<u64>,
+    previous_end_us: Option<u64>,
+    overrun_stats: OverrunStats,
+    last_overrun: Option<Overrun>,
+}
+
+impl<I, D, C> PeriodicSampler<I, D, C, NoFilter>
+where
+    I: I2c,
+    D: DelayNs,
+    C: Clock,
+{
+    /// Sample `sensor` every `interval_us` microseconds, without filtering.
+    pub fn new(sensor: SHT4x<I>, delay: D, clock: C, interval_us: u32) -> Self {
+        Self {
+            sensor,
+            delay,
+            clock,
+            filter: NoFilter,
+            interval_us,
+            next_start_us: None,
+            previous_end_us: None,
+            overrun_stats: OverrunStats::default(),
+            last_overrun: None,
+        }
+    }
+}
+
+impl<I, D, C, F> PeriodicSampler<I, D, C, F>
+where
+    I: I2c,
+    D: DelayNs,
+    C: Clock,
+    F: Filter,
+{
+    /// Replace the sampler's filter.
+    ///
+    /// Use [`Filter::then()`] to build a chain of several filters.
+    pub fn with_filter<G: Filter>(self, filter: G) -> PeriodicSampler<I, D, C, G> {
+        PeriodicSampler {
+            sensor: self.sensor,
+            delay: self.delay,
+            clock: self.clock,
+            filter,
+            interval_us: self.interval_us,
+            next_start_us: self.next_start_us,
+            previous_end_us: self.previous_end_us,
+            overrun_stats: self.overrun_stats,
+            last_overrun: self.last_overrun,
+        }
+    }
+
+    /// Mutable access to the sampler's filter.
+    pub fn filter_mut(&mut self) -> &mut F {
+        &mut self.filter
+    }
+
+    /// Mutable access to the sensor driver, for instance to change its
+    /// config or read its serial number.
+    pub fn sensor_mut(&mut self) -> &mut SHT4x<I> {
+        &mut self.sensor
+    }
+
+    /// Take the next measurement as for
+    /// [`PeriodicSampler::next_measurement()`], and publish it if the filter
+    /// produced one.
+    ///
+    /// See the [`publish`](crate::publish) module for an example.
+    ///
+    /// # Errors
+    ///
+    /// As for [`PeriodicSampler::next_measurement()`]. Nothing is published
+    /// if an error occurs.
+    #[cfg(feature = "embassy-sync")]
+    pub fn publish_next_measurement(
+        &mut self,
+        mut publisher: impl Publish,
+    ) -> Result<Option<Measurement>, Error<I::Error>> {
+        let measurement = self.next_measurement()?;
+        if let Some(measurement) = measurement {
+            publisher.publish(measurement);
+        }
+        Ok(measurement)
+    }
+
+    /// Totals of the overruns seen since the sampler was created.
+    pub fn overrun_stats(&self) -> OverrunStats {
+        self.overrun_stats
+    }
+
+    /// The most recent overrun, if any has occurred since the last call
+    /// to this method.
+    pub fn take_overrun(&mut self) -> Option<Overrun> {
+        self.last_overrun.take()
+    }
+
+    /// Drop the sampler and return the sensor driver, delay and clock.
+    pub fn destroy(self) -> (SHT4x<I>, D, C) {
+        (self.sensor, self.delay, self.clock)
+    }
+
+    /// Wait until the next scheduled measurement time, measure, and return
+    /// the filtered measurement.
+    ///
+    /// The first call measures immediately. `Ok(None)` is returned if the
+    /// filter produced no output for the measurement.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`]. If an error occurs, the schedule is not
+    /// advanced, so the next call will measure immediately.
+    pub fn next_measurement(&mut self) -> Result<Option<Measurement>, Error<I::Error>> {
+        let interval = u64::from(self.interval_us);
+        let now = self.clock.now_us();
+        let scheduled = self.next_start_us.unwrap_or(now);
+        // The consumer can't call before the previous measurement ends, so
+        // it is only late after that, even if the measurement overran.
+        let due = self
+            .previous_end_us
+            .map_or(scheduled, |end| end.max(scheduled));
+        if scheduled > now {
+            let wait = u32::try_from(scheduled - now).unwrap_or(u32::MAX);
+            self.delay.delay_us(wait);
+        } else if now > due {
+            let late_by_us = now - due;
+            let missed_samples = late_by_us.checked_div(interval).unwrap_or(0);
+            self.record_overrun(Overrun::ConsumerTooSlow {
+                late_by_us,
+                missed_samples: u32::try_from(missed_samples).unwrap_or(u32::MAX),
+            });
+        }
+        let start = scheduled.max(now);
+
+        let now_us = self.sensor.note_time(&self.clock);
+        let measurement = self
+            .sensor
+            .take_measurement(&mut self.delay, Some(now_us))?;
+        let end = self.clock.now_us();
+        let duration_us = end.saturating_sub(start);
+        if duration_us > interval {
+            self.record_overrun(Overrun::MeasurementTooLong { duration_us });
+        }
+        self.next_start_us = Some(start.saturating_add(interval));
+        self.previous_end_us = Some(end);
+        Ok(self.filter.update(measurement))
+    }
+
+    fn record_overrun(&mut self, overrun: Overrun) {
+        #[cfg(feature = "defmt")]
+        defmt::warn!("Sampler overrun: {}", overrun);
+
+        self.overrun_stats.record(overrun);
+        self.last_overrun = Some(overrun);
+    }
+
+    /// Take `samples` measurements at the sampler's interval, and return
+    /// their aggregate.
+    ///
+    /// Each measurement is passed through the sampler's filter and then
+    /// added to `aggregator`. This makes "sample every 2 seconds, report the
+    /// 1-minute mean" a matter of choosing the interval, aggregator and
+    /// number of samples (here, 30):
+    ///
+    /// ```
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # use core::cell::Cell;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// # let expectations: Vec<_> = (0..30)
+    /// #   .flat_map(|_| [
+    /// #     Transaction::write(0x44, vec![0xFD]),
+    /// #     Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   ])
+    /// #   .collect();
+    /// # let i2c = Mock::new(&expectations);
+    /// # let now = Cell::new(0);
+    /// # let clock = || now.get();
+    /// use sht4x_rjw::aggregate::Mean;
+    /// use sht4x_rjw::blocking::SHT4x;
+    /// use sht4x_rjw::sampler::PeriodicSampler;
+    ///
+    /// let sensor = SHT4x::new(i2c, Default::default());
+    /// let mut sampler = PeriodicSampler::new(sensor, delay, clock, 2_000_000);
+    /// let mut mean = Mean::default();
+    /// if let Some(report) = sampler.next_aggregate(&mut mean, 30)? {
+    ///     println!("1-minute mean: {}°C", report.celsius());
+    /// }
+    /// # let (sensor, _, _) = sampler.destroy();
+    /// # sensor.destroy().done();
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// `Ok(None)` is returned if the filter produced no output for any of
+    /// the measurements.
+    ///
+    /// # Errors
+    ///
+    /// As for [`PeriodicSampler::next_measurement()`]. An error is returned
+    /// as soon as it occurs, leaving the measurements taken so far in the
+    /// aggregator, so you may call [`Aggregator::finish()`] yourself if you
+    /// want to report the partial interval.
+    pub fn next_aggregate(
+        &mut self,
+        mut aggregator: impl Aggregator,
+        samples: u32,
+    ) -> Result<Option<Measurement>, Error<I::Error>> {
+        for _ in 0..samples {
+            if let Some(measurement) = self.next_measurement()? {
+                aggregator.add(measurement);
+            }
+        }
+        Ok(aggregator.finish())
+    }
+}
+
+/// Bounds and thresholds for an [`AdaptiveSampler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AdaptiveConfig {
+    /// Shortest interval between the starts of consecutive measurements, in
+    /// microseconds, used while readings are changing.
+    pub min_interval_us: u32,
+    /// Longest interval between the starts of consecutive measurements, in
+    /// microseconds, backed off to while readings are stable.
+    pub max_interval_us: u32,
+    /// A change in temperature between consecutive measurements larger than
+    /// this, in millicelsius, counts as changing.
+    pub temperature_threshold_millicelsius: u32,
+    /// A change in relative humidity between consecutive measurements larger
+    /// than this, in thousandths of a percent, counts as changing.
+    pub humidity_threshold_milli_percent_rh: u32,
+}
+
+/// Takes measurements at an interval that adapts to how quickly the
+/// readings are changing.
+///
+/// The sampler starts at the minimum interval. After each measurement, if
+/// the temperature or humidity has changed by more than its threshold since
+/// the previous measurement, the interval returns to the minimum; otherwise
+/// it doubles, up to the maximum. As with [`PeriodicSampler`], the interval
+/// is measured from the start of one measurement to the start of the next.
+///
+/// To bound self-heating, the minimum interval is raised if necessary to
+/// ten times the duration of a measurement with the sensor's configured
+/// reading and delay modes, including its [`Config::delay_table`] and
+/// [`Config::delay_margin_percent`], keeping the sensor's duty cycle at or
+/// below 10%. This also keeps heater modes within the heater's 10% duty
+/// limit.
+///
+/// [`Config::delay_table`]: crate::common::Config::delay_table
+/// [`Config::delay_margin_percent`]: crate::common::Config::delay_margin_percent
+///
+/// ```
+/// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+/// # use core::cell::Cell;
+/// # fn main() -> anyhow::Result<()> {
+/// # let delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+/// # let expectations = [
+/// #   Transaction::write(0x44, vec![0xFD]),
+/// #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+/// #   Transaction::write(0x44, vec![0xFD]),
+/// #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+/// # ];
+/// # let i2c = Mock::new(&expectations);
+/// # let now = Cell::new(0);
+/// # let clock = || now.get();
+/// use sht4x_rjw::blocking::SHT4x;
+/// use sht4x_rjw::sampler::{AdaptiveConfig, AdaptiveSampler};
+///
+/// let sensor = SHT4x::new(i2c, Default::default());
+/// // Sample every 10 seconds while readings change by more than 0.1°C or
+/// // 0.5%RH, backing off to every 5 minutes.
+/// let config = AdaptiveConfig {
+///     min_interval_us: 10_000_000,
+///     max_interval_us: 300_000_000,
+///     temperature_threshold_millicelsius: 100,
+///     humidity_threshold_milli_percent_rh: 500,
+/// };
+/// let mut sampler = AdaptiveSampler::new(sensor, delay, clock, config);
+/// sampler.next_measurement()?;
+/// sampler.next_measurement()?;
+/// // The readings were stable, so the interval has doubled.
+/// assert_eq!(sampler.interval_us(), 20_000_000);
+/// # let (sensor, _, _) = sampler.destroy();
+/// # sensor.destroy().done();
+/// # Ok(())
+/// # }
+/// ```
+pub struct AdaptiveSampler<I: I2c, D, C> {
+    sensor: SHT4x<I>,
+    delay: D,
+    clock: C,
+    config: AdaptiveConfig,
+    interval_us: u32,
+    previous: Option<Measurement>,
+    next_start_us: Option<u64>,
+}
+
+impl<I, D, C> AdaptiveSampler<I, D, C>
+where
+    I: I2c,
+    D: DelayNs,
+    C: Clock,
+{
+    /// Sample `sensor` at an interval within the bounds of `config`.
+    pub fn new(sensor: SHT4x<I>, delay: D, clock: C, config: AdaptiveConfig) -> Self {
+        let mut sampler = Self {
+            sensor,
+            delay,
+            clock,
+            config,
+            interval_us: 0,
+            previous: None,
+            next_start_us: None,
+        };
+        sampler.interval_us = sampler.min_interval_us();
+        sampler
+    }
+
+    /// The current interval between measurements, in microseconds.
+    pub fn interval_us(&self) -> u32 {
+        self.interval_us
+    }
+
+    /// Mutable access to the sensor driver, for example to change its
+    /// config between measurements.
+    pub fn sensor_mut(&mut self) -> &mut SHT4x<I> {
+        &mut self.sensor
+    }
+
+    /// Drop the sampler and return the sensor driver, delay and clock.
+    pub fn destroy(self) -> (SHT4x<I>, D, C) {
+        (self.sensor, self.delay, self.clock)
+    }
+
+    /// Wait until the next scheduled measurement time, measure, and adapt
+    /// the interval to the change since the previous measurement.
+    ///
+    /// The first call measures immediately.
+    ///
+    /// # Errors
+    ///
+    /// As for [`SHT4x::measure()`]. If an error occurs, the schedule is not
+    /// advanced, so the next call will measure immediately.
+    pub fn next_measurement(&mut self) -> Result<Measurement, Error<I::Error>> {
+        let now = self.clock.now_us();
+        let scheduled = self.next_start_us.unwrap_or(now);
+        if scheduled > now {
+            let wait = u32::try_from(scheduled - now).unwrap_or(u32::MAX);
+            self.delay.delay_us(wait);
+        }
+        let start = scheduled.max(now);
+
+        let now_us = self.sensor.note_time(&self.clock);
+        let measurement = self
+            .sensor
+            .take_measurement(&mut self.delay, Some(now_us))?;
+        let min_interval_us = self.min_interval_us();
+        self.interval_us = match self.previous {
+            Some(previous) if !self.is_changing(previous, measurement) => {
+                self.interval_us.saturating_mul(2).clamp(
+                    min_interval_us,
+                    self.config.max_interval_us.max(min_interval_us),
+                )
+            }
+            _ => min_interval_us,
+        };
+        self.previous = Some(measurement);
+        self.next_start_us = Some(start.saturating_add(u64::from(self.interval_us)));
+        Ok(measurement)
+    }
+
+    /// The configured minimum interval, raised if necessary to bound
+    /// self-heating.
+    fn min_interval_us(&self) -> u32 {
+        let config = self.sensor.config();
+        let duration_us = config.delay_us(config.reading_mode, config.delay_mode);
+        self.config
+            .min_interval_us
+            .max(duration_us.saturating_mul(10))
+    }
+
+    /// Whether the readings changed by more than the thresholds.
+    fn is_changing(&self, previous: Measurement, current: Measurement) -> bool {
+        let temperature = previous.millicelsius().abs_diff(current.millicelsius());
+        let humidity = previous
+            .milli_percent_rh()
+            .abs_diff(current.milli_percent_rh());
+        temperature > self.config.temperature_threshold_millicelsius
+            || humidity > self.config.humidity_threshold_milli_percent_rh
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use core::cell::Cell;
+    use std::vec;
+
+    use embedded_hal::delay::DelayNs;
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+    use super::{AdaptiveConfig, AdaptiveSampler, Overrun, OverrunStats, PeriodicSampler};
+    use crate::blocking::SHT4x;
+    use crate::common::Config;
+
+    /// A delay that advances a fake clock.
+    struct ClockDelay<'a>(&'a Cell<u64>);
+
+    impl DelayNs for ClockDelay<'_> {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0.set(self.0.get() + u64::from(ns.div_ceil(1_000)));
+        }
+    }
+
+    fn expectations(count: usize) -> vec::Vec<Transaction> {
+        (0..count)
+            .flat_map(|_| {
+                [
+                    Transaction::write(0x44, vec![0xFD]),
+                    Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn on_schedule_records_no_overruns() {
+        let now = Cell::new(0);
+        let sensor = SHT4x::new(Mock::new(&expectations(3)), Default::default());
+        let mut sampler = PeriodicSampler::new(sensor, ClockDelay(&now), || now.get(), 10_000);
+        for _ in 0..3 {
+            sampler.next_measurement().unwrap();
+        }
+        // Measurements start at 0, 10ms and 20ms.
+        assert_eq!(now.get(), 20_000 + 6_900);
+        assert_eq!(sampler.overrun_stats(), OverrunStats::default());
+        let (sensor, _, _) = sampler.destroy();
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn slow_consumer_and_slow_measurement() {
+        let now = Cell::new(0);
+        let sensor = SHT4x::new(Mock::new(&expectations(3)), Default::default());
+        let mut sampler = PeriodicSampler::new(sensor, ClockDelay(&now), || now.get(), 5_000);
+
+        // The high-precision measurement takes longer than the interval.
+        sampler.next_measurement().unwrap();
+        assert_eq!(
+            sampler.take_overrun(),
+            Some(Overrun::MeasurementTooLong { duration_us: 6_900 })
+        );
+        assert_eq!(sampler.take_overrun(), None);
+
+        sampler.next_measurement().unwrap();
+        // The consumer takes a while to process the measurement.
+        now.set(now.get() + 10_000);
+        sampler.next_measurement().unwrap();
+        let stats = sampler.overrun_stats();
+        assert_eq!(stats.measurement_overruns, 3);
+        assert_eq!(stats.consumer_overruns, 1);
+        assert_eq!(stats.missed_samples, 2);
+        let (sensor, _, _) = sampler.destroy();
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn adaptive_interval_backs_off_and_speeds_up() {
+        let stable = [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D];
+        let changed = [0x66, 0x66, 0x93, 0x80, 0x00, 0xA2];
+        let expectations: vec::Vec<_> = [stable, stable, stable, stable, changed]
+            .into_iter()
+            .flat_map(|frame| {
+                [
+                    Transaction::write(0x44, vec![0xFD]),
+                    Transaction::read(0x44, frame.to_vec()),
+                ]
+            })
+            .collect();
+        let now = Cell::new(0);
+        let sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        let config = AdaptiveConfig {
+            min_interval_us: 100_000,
+            max_interval_us: 400_000,
+            temperature_threshold_millicelsius: 100,
+            humidity_threshold_milli_percent_rh: 500,
+        };
+        let mut sampler = AdaptiveSampler::new(sensor, ClockDelay(&now), || now.get(), config);
+
+        let mut intervals = vec::Vec::new();
+        for _ in 0..5 {
+            sampler.next_measurement().unwrap();
+            intervals.push(sampler.interval_us());
+        }
+        assert_eq!(intervals, [100_000, 200_000, 400_000, 400_000, 100_000]);
+        // Measurements start at 0, 100ms, 300ms, 700ms and 1.1s.
+        assert_eq!(now.get(), 1_100_000 + 6_900);
+        let (sensor, _, _) = sampler.destroy();
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn adaptive_minimum_interval_bounds_self_heating() {
+        let sensor = SHT4x::new(
+            Mock::new(&[]),
+            Config {
+                delay_margin_percent: 20,
+                ..Default::default()
+            },
+        );
+        let config = AdaptiveConfig {
+            min_interval_us: 1_000,
+            max_interval_us: 10_000,
+            temperature_threshold_millicelsius: 100,
+            humidity_threshold_milli_percent_rh: 500,
+        };
+        let now = Cell::new(0);
+        let sampler = AdaptiveSampler::new(sensor, ClockDelay(&now), || now.get(), config);
+        // Ten times the 6.9ms typical high-precision measurement time, plus
+        // the 20% margin.
+        assert_eq!(sampler.interval_us(), 82_800);
+        let (sensor, _, _) = sampler.destroy();
+        sensor.destroy().done();
+    }
+}
@@ -0,0 +1,319 @@
+//! A simulated SHT4x, for testing on the host without hardware.
+//!
+//! [`SimulatedSht4x`] implements the `embedded-hal` (and, with the `async`
+//! feature, `embedded-hal-async`) I2C traits, responding to the sensor's
+//! commands as a real SHT4x would. Pass it to a driver in place of a real
+//! I2C interface.
+//!
+//! The simulated temperature and humidity follow programmable
+//! [`Profile`]s over virtual time, read from a [`Clock`], so control loops
+//! and alerting logic can be tested against ramps, steps, daily cycles and
+//! slow sensor drift without waiting for them in real time.
+//!
+//! ```
+//! # fn main() -> anyhow::Result<()> {
+//! # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! use core::cell::Cell;
+//! use sht4x_rjw::blocking::SHT4x;
+//! use sht4x_rjw::simulator::{Profile, SimulatedSht4x};
+//!
+//! let now_us = Cell::new(0);
+//! // Temperature steps from 20°C to 30°C after one minute, and humidity
+//! // falls from 60% to 40% over the same minute.
+//! let simulated = SimulatedSht4x::new(
+//!     || now_us.get(),
+//!     Profile::Step { before: 20.0, after: 30.0, at_us: 60_000_000 },
+//!     Profile::Ramp { from: 60.0, to: 40.0, start_us: 0, duration_us: 60_000_000 },
+//! );
+//! let mut sensor = SHT4x::new(simulated, Default::default());
+//!
+//! let measurement = sensor.measure(&mut delay)?;
+//! assert!((measurement.celsius() - 20.0).abs() < 0.01);
+//!
+//! now_us.set(60_000_000);
+//! let measurement = sensor.measure(&mut delay)?;
+//! assert!((measurement.celsius() - 30.0).abs() < 0.01);
+//! assert!((measurement.humidity() - 40.0).abs() < 0.01);
+//! # Ok(())
+//! # }
+//! ```
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+use crate::clock::Clock;
+use crate::common::Command;
+use crate::conversions;
+use crate::crc::calculate_crc;
+
+/// How a simulated quantity (temperature in °C, or relative humidity in
+/// %RH) varies over virtual time, in microseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Profile {
+    /// A constant value.
+    Constant(f32),
+    /// A linear change from `from` to `to`, starting at `start_us` and
+    /// lasting `duration_us`. The value is `from` before the ramp and `to`
+    /// after it.
+    Ramp {
+        from: f32,
+        to: f32,
+        start_us: u64,
+        duration_us: u64,
+    },
+    /// A sudden change from `before` to `after` at `at_us`.
+    Step { before: f32, after: f32, at_us: u64 },
+    /// A sinusoid around `mean`, peaking at `mean + amplitude` a quarter of
+    /// the way through each `period_us`. The shape is approximated, to
+    /// within about 0.1% of the amplitude.
+    Sine {
+        mean: f32,
+        amplitude: f32,
+        period_us: u64,
+    },
+}
+
+impl Profile {
+    /// The value of the profile at `t_us`.
+    pub fn value_at(&self, t_us: u64) -> f32 {
+        match *self {
+            Profile::Constant(value) => value,
+            Profile::Ramp {
+                from,
+                to,
+                start_us,
+                duration_us,
+            } => {
+                if t_us <= start_us {
+                    from
+                } else if t_us >= start_us.saturating_add(duration_us) {
+                    to
+                } else {
+                    let fraction = (t_us - start_us) as f32 / duration_us as f32;
+                    from + (to - from) * fraction
+                }
+            }
+            Profile::Step {
+                before,
+                after,
+                at_us,
+            } => {
+                if t_us < at_us {
+                    before
+                } else {
+                    after
+                }
+            }
+            Profile::Sine {
+                mean,
+                amplitude,
+                period_us,
+            } => {
+                let turns = (t_us % period_us.max(1)) as f32 / period_us.max(1) as f32;
+                mean + amplitude * sin_turns(turns)
+            }
+        }
+    }
+}
+
+/// Approximate `sin(2π × turns)` for `turns` in `0.0..1.0`, without `libm`.
+///
+/// Uses Bhaskara's approximation for each half turn.
+fn sin_turns(turns: f32) -> f32 {
+    let (half, sign) = if turns < 0.5 {
+        (turns * 2.0, 1.0)
+    } else {
+        (turns * 2.0 - 1.0, -1.0)
+    };
+    // Bhaskara: sin(πx) ≈ 16x(1 - x) / (5 - 4x(1 - x)) for x in 0..=1.
+    let p = half * (1.0 - half);
+    sign * 16.0 * p / (5.0 - 4.0 * p)
+}
+
+/// A simulated SHT4x sensor on its own I2C bus.
+///
+/// See the [module documentation](crate::simulator) for an example.
+///
+/// The simulation responds at [`SimulatedSht4x::address`] to the
+/// measurement (including heater), serial number and soft reset commands.
+/// Other commands, and reads when no response is waiting, are rejected
+/// with a NACK. Measurements are not delayed, so they may be read as soon
+/// as the command is sent, and the heater has no effect on the readings.
+pub struct SimulatedSht4x<C> {
+    clock: C,
+    temperature: Profile,
+    humidity: Profile,
+    temperature_drift_per_hour: f32,
+    humidity_drift_per_hour: f32,
+    response: Option<[u8; 6]>,
+    /// I2C address the simulated sensor responds at. The default is `0x44`.
+    pub address: u8,
+    /// Serial number reported by the simulated sensor.
+    pub serial_number: u32,
+}
+
+impl<C: Clock> SimulatedSht4x<C> {
+    /// Create a simulated sensor whose temperature (in °C) and relative
+    /// humidity (in %RH) follow the given profiles, with time read from
+    /// `clock`.
+    pub fn new(clock: C, temperature: Profile, humidity: Profile) -> Self {
+        Self {
+            clock,
+            temperature,
+            humidity,
+            temperature_drift_per_hour: 0.0,
+            humidity_drift_per_hour: 0.0,
+            response: None,
+            address: 0x44,
+            serial_number: 0x0102_0304,
+        }
+    }
+
+    /// Add a steady sensor drift to the profiles, in °C and %RH per hour of
+    /// virtual time since time zero.
+    pub fn with_drift(self, celsius_per_hour: f32, percent_rh_per_hour: f32) -> Self {
+        Self {
+            temperature_drift_per_hour: celsius_per_hour,
+            humidity_drift_per_hour: percent_rh_per_hour,
+            ..self
+        }
+    }
+
+    /// The simulated temperature (in °C) and relative humidity (in %RH) at
+    /// `t_us`, including drift, before conversion to sensor readings.
+    pub fn environment_at(&self, t_us: u64) -> (f32, f32) {
+        let hours = t_us as f32 / 3_600_000_000.0;
+        (
+            self.temperature.value_at(t_us) + self.temperature_drift_per_hour * hours,
+            self.humidity.value_at(t_us) + self.humidity_drift_per_hour * hours,
+        )
+    }
+
+    fn handle_command(&mut self, command: u8) -> Result<(), ErrorKind> {
+        let command = Command::try_from(command)
+            .map_err(|_| ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))?;
+        self.response = match command {
+            Command::ReadSerialNumber => {
+                let [b0, b1, b2, b3] = self.serial_number.to_be_bytes();
+                Some(frame([b0, b1], [b2, b3]))
+            }
+            Command::SoftReset => None,
+            Command::Measure(_) => {
+                let (celsius, humidity) = self.environment_at(self.clock.now_us());
+                let raw_temp = conversions::celsius_to_raw(celsius);
+                let raw_humidity = conversions::percent_rh_to_raw(humidity);
+                Some(frame(raw_temp.to_be_bytes(), raw_humidity.to_be_bytes()))
+            }
+        };
+        Ok(())
+    }
+}
+
+/// Build a 6-byte response frame with valid CRCs.
+fn frame(first: [u8; 2], second: [u8; 2]) -> [u8; 6] {
+    [
+        first[0],
+        first[1],
+        calculate_crc(first),
+        second[0],
+        second[1],
+        calculate_crc(second),
+    ]
+}
+
+impl<C> ErrorType for SimulatedSht4x<C> {
+    type Error = ErrorKind;
+}
+
+impl<C: Clock> I2c for SimulatedSht4x<C> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address != self.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        for operation in operations {
+            match operation {
+                Operation::Write([command]) => self.handle_command(*command)?,
+                Operation::Write(_) => {
+                    return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+                }
+                Operation::Read(buffer) => {
+                    let response = self
+                        .response
+                        .take()
+                        .ok_or(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))?;
+                    let len = buffer.len().min(response.len());
+                    buffer[..len].copy_from_slice(&response[..len]);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<C: Clock> embedded_hal_async::i2c::I2c for SimulatedSht4x<C> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        I2c::transaction(self, address, operations)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_hal::i2c::I2c;
+
+    use super::{Profile, SimulatedSht4x, sin_turns};
+
+    #[test]
+    fn profiles() {
+        let ramp = Profile::Ramp {
+            from: 10.0,
+            to: 20.0,
+            start_us: 100,
+            duration_us: 100,
+        };
+        assert_eq!(ramp.value_at(0), 10.0);
+        assert_eq!(ramp.value_at(150), 15.0);
+        assert_eq!(ramp.value_at(1_000), 20.0);
+
+        let sine = Profile::Sine {
+            mean: 20.0,
+            amplitude: 5.0,
+            period_us: 400,
+        };
+        assert_eq!(sine.value_at(0), 20.0);
+        assert_eq!(sine.value_at(100), 25.0);
+        assert_eq!(sine.value_at(300), 15.0);
+    }
+
+    #[test]
+    fn sine_approximation_is_close() {
+        for (turns, expected) in [(1.0 / 12.0, 0.5), (0.125, 0.70710677), (0.75, -1.0)] {
+            assert!((sin_turns(turns) - expected).abs() < 0.002);
+        }
+    }
+
+    #[test]
+    fn drift_accumulates() {
+        let sim = SimulatedSht4x::new(|| 0, Profile::Constant(20.0), Profile::Constant(50.0))
+            .with_drift(0.5, -1.0);
+        assert_eq!(sim.environment_at(7_200_000_000), (21.0, 48.0));
+    }
+
+    #[test]
+    fn rejects_unknown_commands_and_early_reads() {
+        let mut sim = SimulatedSht4x::new(|| 0, Profile::Constant(20.0), Profile::Constant(50.0));
+        let mut buffer = [0; 6];
+        assert!(sim.read(0x44, &mut buffer).is_err());
+        assert!(sim.write(0x44, &[0x42]).is_err());
+        assert!(sim.write(0x45, &[0xFD]).is_err());
+        sim.write(0x44, &[0x89]).unwrap();
+        sim.read(0x44, &mut buffer).unwrap();
+        assert_eq!(buffer, [0x01, 0x02, 0x17, 0x03, 0x04, 0x68]);
+    }
+}
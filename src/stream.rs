@@ -0,0 +1,280 @@
+//! Streams of measurements from the async driver.
+//!
+//! [`SHT4x::measurements()`] returns a [`MeasurementStream`], which takes a
+//! new measurement each time it is polled for the next item. It implements
+//! [`futures_core::Stream`], so it can be used with the `StreamExt`
+//! combinators from [`futures-util`] and similar crates.
+//!
+//! What the stream does when a measurement fails is set by its
+//! [`ErrorPolicy`]: errors can be passed on to the consumer, transient
+//! errors (such as CRC failures) can be skipped, or the stream can end at
+//! the first error. In all cases, [`MeasurementStream::errors()`] counts
+//! the errors encountered.
+//!
+//! ```
+//! # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+//! # #[pollster::main]
+//! # async fn main() -> anyhow::Result<()> {
+//! # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+//! # let expectations = [
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x00, 0x56, 0x78, 0x7D]),
+//! #   Transaction::write(0x44, vec![0xFD]),
+//! #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+//! # ];
+//! # let i2c = Mock::new(&expectations);
+//! use core::pin::pin;
+//! use futures_util::StreamExt;
+//! use sht4x_rjw::asynch::SHT4x;
+//! use sht4x_rjw::stream::ErrorPolicy;
+//!
+//! let mut sensor = SHT4x::new(i2c, Default::default());
+//! # {
+//! let mut stream = pin!(sensor.measurements(&mut delay, ErrorPolicy::SkipTransient));
+//! // The first measurement fails its CRC check, so is skipped.
+//! let measurement = stream.next().await.unwrap()?;
+//! assert_eq!(stream.errors(), 1);
+//! println!("{}°C", measurement.celsius());
+//! # }
+//! # sensor.destroy().done();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`futures-util`]: https://docs.rs/futures-util
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+use futures_core::Stream;
+use pin_project_lite::pin_project;
+
+use crate::asynch::SHT4x;
+use crate::common::Measurement;
+use crate::error::Error;
+
+/// What a [`MeasurementStream`] does when a measurement fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorPolicy {
+    /// Yield every error to the consumer and carry on measuring.
+    #[default]
+    YieldAll,
+    /// Skip transient errors (see [`Error::is_transient()`]) and carry on
+    /// measuring, so the stream only yields good measurements. Other
+    /// errors are yielded, and end the stream.
+    SkipTransient,
+    /// Yield the first error, and end the stream.
+    Terminate,
+}
+
+pin_project! {
+    /// A stream of measurements, created by [`SHT4x::measurements()`].
+    ///
+    /// See the [module documentation](crate::stream) for an example.
+    pub struct MeasurementStream<S> {
+        #[pin]
+        inner: S,
+        policy: ErrorPolicy,
+        errors: u32,
+        finished: bool,
+    }
+}
+
+impl<S> MeasurementStream<S> {
+    /// The number of failed measurements, including any that were skipped.
+    pub fn errors(&self) -> u32 {
+        self.errors
+    }
+
+    /// The error policy the stream was created with.
+    pub fn policy(&self) -> ErrorPolicy {
+        self.policy
+    }
+}
+
+impl<S, E> Stream for MeasurementStream<S>
+where
+    S: Stream<Item = Result<Measurement, Error<E>>>,
+    E: embedded_hal::i2c::Error,
+{
+    type Item = Result<Measurement, Error<E>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.finished {
+            return Poll::Ready(None);
+        }
+        let error = match ready!(this.inner.poll_next(cx)) {
+            Some(Err(error)) => error,
+            other => return Poll::Ready(other),
+        };
+
+        *this.errors = this.errors.saturating_add(1);
+        match this.policy {
+            ErrorPolicy::YieldAll => {}
+            ErrorPolicy::SkipTransient if error.is_transient() => {
+                // Measure again on the next poll, rather than looping here,
+                // so that an unresponsive sensor can't starve the executor.
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            ErrorPolicy::SkipTransient | ErrorPolicy::Terminate => *this.finished = true,
+        }
+        Poll::Ready(Some(Err(error)))
+    }
+}
+
+impl<I: I2c> SHT4x<I> {
+    /// Create a stream that takes a new measurement, with the settings
+    /// provided upon construction of the sensor struct, each time it is
+    /// polled for the next item.
+    ///
+    /// The stream borrows the sensor, so no other measurements can be
+    /// taken while it exists.
+    pub fn measurements<'a, D>(
+        &'a mut self,
+        delay: D,
+        policy: ErrorPolicy,
+    ) -> MeasurementStream<impl Stream<Item = Result<Measurement, Error<I::Error>>> + 'a>
+    where
+        D: DelayNs + 'a,
+    {
+        self.measurements_every(delay, 0, policy)
+    }
+
+    /// Create a stream that takes a new measurement, with the settings
+    /// provided upon construction of the sensor struct, waiting
+    /// `interval_us` microseconds after each measurement before taking the
+    /// next.
+    ///
+    /// The first measurement is taken without waiting. The wait also
+    /// applies after a measurement that fails, including one skipped by
+    /// [`ErrorPolicy::SkipTransient`].
+    ///
+    /// ```
+    /// # use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    /// # #[pollster::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// # let mut delay = embedded_hal_mock::eh1::delay::NoopDelay::new();
+    /// # let expectations = [
+    /// #   Transaction::write(0x44, vec![0xFD]),
+    /// #   Transaction::read(0x44, vec![0x12, 0x34, 0x37, 0x56, 0x78, 0x7D]),
+    /// #   Transaction::write(0x44, vec![0xFD]),
+    /// #   Transaction::read(0x44, vec![0x12, 0x36, 0x55, 0x56, 0x76, 0x62]),
+    /// # ];
+    /// # let i2c = Mock::new(&expectations);
+    /// use futures_util::StreamExt;
+    /// use sht4x_rjw::asynch::SHT4x;
+    /// use sht4x_rjw::stream::ErrorPolicy;
+    ///
+    /// let mut sensor = SHT4x::new(i2c, Default::default());
+    /// // One measurement a second, stopping after two.
+    /// let measurements: Vec<_> = sensor
+    ///     .measurements_every(&mut delay, 1_000_000, ErrorPolicy::Terminate)
+    ///     .take(2)
+    ///     .collect()
+    ///     .await;
+    /// assert_eq!(measurements.len(), 2);
+    /// # sensor.destroy().done();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn measurements_every<'a, D>(
+        &'a mut self,
+        delay: D,
+        interval_us: u32,
+        policy: ErrorPolicy,
+    ) -> MeasurementStream<impl Stream<Item = Result<Measurement, Error<I::Error>>> + 'a>
+    where
+        D: DelayNs + 'a,
+    {
+        let state = (self, delay, false);
+        let inner =
+            futures_util::stream::unfold(state, move |(sensor, mut delay, started)| async move {
+                if started && interval_us > 0 {
+                    delay.delay_us(interval_us).await;
+                }
+                let result = sensor.measure(&mut delay).await;
+                Some((result, (sensor, delay, true)))
+            });
+        MeasurementStream {
+            inner,
+            policy,
+            errors: 0,
+            finished: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use core::pin::pin;
+    use std::vec;
+
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+    use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+    use futures_util::StreamExt;
+
+    use super::ErrorPolicy;
+    use crate::asynch::SHT4x;
+
+    const GOOD: [u8; 6] = [0x12, 0x34, 0x37, 0x56, 0x78, 0x7D];
+
+    fn failing_then_good(kind: ErrorKind) -> [Transaction; 3] {
+        [
+            Transaction::write(0x44, vec![0xFD]).with_error(kind),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, GOOD.to_vec()),
+        ]
+    }
+
+    #[test]
+    fn yield_all_continues_after_error() {
+        let expectations = failing_then_good(ErrorKind::Other);
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        pollster::block_on(async {
+            let mut stream = pin!(sensor.measurements(NoopDelay::new(), ErrorPolicy::YieldAll));
+            assert!(stream.next().await.unwrap().is_err());
+            assert!(stream.next().await.unwrap().is_ok());
+            assert_eq!(stream.errors(), 1);
+        });
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn skip_transient_ends_on_fatal_error() {
+        let expectations = [
+            Transaction::write(0x44, vec![0xFD]).with_error(ErrorKind::Bus),
+            Transaction::write(0x44, vec![0xFD]),
+            Transaction::read(0x44, GOOD.to_vec()),
+            Transaction::write(0x44, vec![0xFD]).with_error(ErrorKind::Other),
+        ];
+        let mut sensor = SHT4x::new(Mock::new(&expectations), Default::default());
+        pollster::block_on(async {
+            let mut stream =
+                pin!(sensor.measurements(NoopDelay::new(), ErrorPolicy::SkipTransient));
+            assert!(stream.next().await.unwrap().is_ok());
+            assert!(stream.next().await.unwrap().is_err());
+            assert!(stream.next().await.is_none());
+            assert_eq!(stream.errors(), 2);
+        });
+        sensor.destroy().done();
+    }
+
+    #[test]
+    fn terminate_ends_on_first_error() {
+        let expectations = &failing_then_good(ErrorKind::Bus)[..1];
+        let mut sensor = SHT4x::new(Mock::new(expectations), Default::default());
+        pollster::block_on(async {
+            let mut stream = pin!(sensor.measurements(NoopDelay::new(), ErrorPolicy::Terminate));
+            assert!(stream.next().await.unwrap().is_err());
+            assert!(stream.next().await.is_none());
+        });
+        sensor.destroy().done();
+    }
+}